@@ -0,0 +1,145 @@
+//! Build script of `tinyklv`
+//!
+//! With the `dictionary` feature, compiles the subset of the SMPTE
+//! Metadata Registers in `dictionary/*.xml` into a static table, see
+//! `tinyklv::codecs::ul::dictionary`
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "dictionary")]
+    dictionary::generate();
+}
+
+#[cfg(feature = "dictionary")]
+mod dictionary {
+    // --------------------------------------------------
+    // std
+    // --------------------------------------------------
+    use std::collections::HashMap;
+    use std::fmt::Write;
+
+    /// The index of the version byte of a universal label
+    const VERSION_INDEX: usize = 7;
+
+    /// Returns the label as looked up in the table, see
+    /// `tinyklv::codecs::ul::dictionary::normalize`
+    fn normalize(mut key: [u8; 16]) -> [u8; 16] {
+        key[VERSION_INDEX] = 0;
+        if key[4] == 0x02 {
+            key[5] = 0x7F;
+        }
+        key
+    }
+
+    /// A register entry, as read from the XML files
+    struct Entry {
+        key: [u8; 16],
+        register: String,
+        name: String,
+        ty: Option<String>,
+        description: String,
+    }
+
+    /// Deduplicated pool of every string of the table
+    #[derive(Default)]
+    struct Pool {
+        strings: String,
+        spans: HashMap<String, (u32, u32)>,
+    }
+    impl Pool {
+        /// Returns the `(offset, len)` of `s` in the pool, adding it if new
+        fn span(&mut self, s: &str) -> (u32, u32) {
+            if let Some(span) = self.spans.get(s) {
+                return *span
+            }
+            let span = (self.strings.len() as u32, s.len() as u32);
+            self.strings.push_str(s);
+            self.spans.insert(s.to_string(), span);
+            span
+        }
+    }
+
+    /// Reads every `dictionary/*.xml` file, and writes `$OUT_DIR/dictionary.rs`
+    pub fn generate() {
+        let dir = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("dictionary");
+        println!("cargo:rerun-if-changed={}", dir.display());
+        let mut paths: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap_or_else(|err| panic!("unable to read {}: {}", dir.display(), err))
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "xml"))
+            .collect();
+        paths.sort();
+        let mut entries = Vec::new();
+        for path in paths.iter() {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let text = std::fs::read_to_string(path).unwrap_or_else(|err| panic!("unable to read {}: {}", path.display(), err));
+            let doc = roxmltree::Document::parse(&text).unwrap_or_else(|err| panic!("unable to parse {}: {}", path.display(), err));
+            let register = doc.root_element().tag_name().name().to_string();
+            for node in doc.descendants().filter(|node| node.tag_name().name() == "Entry") {
+                let child = |name: &str| node
+                    .children()
+                    .find(|child| child.tag_name().name() == name)
+                    .and_then(|child| child.text())
+                    .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+                let (Some(ul), Some(name)) = (child("UL"), child("Name")) else { continue };
+                let key = parse_ul(&ul).unwrap_or_else(|| panic!("invalid universal label `{}` in {}", ul, path.display()));
+                entries.push(Entry {
+                    key,
+                    register: register.clone(),
+                    name,
+                    ty: child("Type"),
+                    description: child("Definition").unwrap_or_default(),
+                });
+            }
+        }
+        // --------------------------------------------------
+        // sorted by normalized label, keeping the first entry
+        // of each label
+        // --------------------------------------------------
+        for entry in entries.iter_mut() {
+            entry.key = normalize(entry.key);
+        }
+        entries.sort_by_key(|entry| entry.key);
+        entries.dedup_by_key(|entry| entry.key);
+
+        let mut pool = Pool::default();
+        let mut table = String::new();
+        for entry in entries.iter() {
+            let register = pool.span(&entry.register);
+            let name = pool.span(&entry.name);
+            let ty = entry.ty.as_ref().map(|ty| pool.span(ty));
+            let description = pool.span(&entry.description);
+            writeln!(
+                table,
+                "    RawEntry {{ key: {:?}, register: {:?}, name: {:?}, ty: {:?}, description: {:?} }},",
+                entry.key, register, name, ty, description,
+            ).unwrap();
+        }
+        let generated = format!(
+            "/// Every string of the table\nconst STRINGS: &str = {:?};\n\n/// Every entry, sorted by normalized label\nconst ENTRIES: &[RawEntry] = &[\n{}];\n",
+            pool.strings, table,
+        );
+        let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("dictionary.rs");
+        std::fs::write(&out, generated).unwrap_or_else(|err| panic!("unable to write {}: {}", out.display(), err));
+    }
+
+    /// Parses a universal label from a `urn:smpte:ul:` URN or dotted hex
+    fn parse_ul(ul: &str) -> Option<[u8; 16]> {
+        let digits: Vec<u8> = ul
+            .trim()
+            .strip_prefix("urn:smpte:ul:")
+            .unwrap_or(ul.trim())
+            .chars()
+            .filter(|c| *c != '.')
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<_>>()?;
+        let mut key = [0; 16];
+        if digits.len() != 2 * key.len() {
+            return None
+        }
+        for (byte, pair) in key.iter_mut().zip(digits.chunks(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        Some(key)
+    }
+}
@@ -75,6 +75,12 @@ pub enum StructNames {
     /// the entire stream ingestion and is only using the `decode` method for parsing of the
     /// data.
     /// 
+    /// On byte streams, a SMPTE universal label URN (a string starting with
+    /// `urn:smpte:ul:`, then 16 bytes in hex, optionally separated by `.`) is
+    /// recognized regardless of its version byte (byte 7), as receivers are
+    /// supposed to (see [`tinyklv::codecs::ul`](https://docs.rs/tinyklv/latest/tinyklv/codecs/ul/index.html)).
+    /// It is encoded as written.
+    /// 
     /// # Syntax
     /// 
     /// `sentinel = <literal>`
@@ -87,6 +93,7 @@ pub enum StructNames {
     /// 
     /// * `#[klv(sentinel = b"\x00\x00\x00", ...)]`
     /// * `#[klv(sentinel = b"my_packet_starts_with_this_message", ...)]`
+    /// * `#[klv(sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000", ...)]`
     /// 
     /// In practice, setting the sentinel would look like:
     /// 
@@ -202,6 +209,74 @@ pub enum StructNames {
 
     #[value = "allow_unimplemented_encode"]
     AllowUnimplementedEncode,
+
+    #[value = "transparent"]
+    /// `transparent` ***(Optional)***: Delegates decoding / encoding to the single inner field
+    ///
+    /// The struct must have exactly one field, which does not require a `#[klv(..)]`
+    /// attribute. The generated `Decode` and `EncodeValue` implementations call the
+    /// field's `dec` / `enc` (set on the field, or through a struct-level `default(..)`),
+    /// otherwise the `Decode` / `EncodeValue` implementations of the inner type.
+    ///
+    /// No `key`, `len`, or `sentinel` is used.
+    ///
+    /// # Syntax
+    ///
+    /// `transparent`
+    ///
+    /// # Example usage
+    ///
+    /// ```no_run ignore
+    /// use tinyklv::Klv;
+    /// use tinyklv::prelude::*;
+    ///
+    /// #[derive(Klv)]
+    /// #[klv(transparent)]
+    /// struct Tag(#[klv(dec = tinyklv::dec::binary::be_u16, enc = crate::tag_encoder)] u16);
+    /// ```
+    Transparent,
+
+    #[value = "form"]
+    /// `form` ***(Optional)***: The SMPTE 336M coding form of the packet
+    ///
+    /// * `local_set`: every item is a key, a length and a value (the default)
+    /// * `universal_set`: a local set, keyed by full 16-byte universal labels.
+    ///   Every `key` must be a 16-byte byte string. The key defaults to
+    ///   `tinyklv::codecs::binary::{dec, enc}::universal_key` and the length to
+    ///   BER, if not set
+    /// * `defined_pack`: the values of every field in declaration order,
+    ///   without keys or lengths. Fields must be of static length (no `dyn`)
+    /// * `variable_pack`: a length and a value for every field in declaration
+    ///   order, without keys
+    ///
+    /// In packs, the `key` of a field is not used. Optional fields may be
+    /// missing from the end of a pack, but not from the middle: when encoding,
+    /// only trailing fields should be [`None`].
+    ///
+    /// # Syntax
+    ///
+    /// `form = <local_set | universal_set | defined_pack | variable_pack>`
+    ///
+    /// # Defaults to
+    ///
+    /// `local_set`
+    ///
+    /// # Example usage
+    ///
+    /// ```no_run ignore
+    /// use tinyklv::Klv;
+    /// use tinyklv::prelude::*;
+    ///
+    /// #[derive(Klv)]
+    /// #[klv(form = defined_pack)]
+    /// struct Position {
+    ///     #[klv(dec = tinyklv::dec::binary::be_i32)]
+    ///     latitude: i32,
+    ///     #[klv(dec = tinyklv::dec::binary::be_i32)]
+    ///     longitude: i32,
+    /// }
+    /// ```
+    Form,
 }
 
 #[derive(Const)]
@@ -457,4 +532,160 @@ pub enum FieldNames {
     /// }
     /// ```
     Encoder,
+
+    #[value = "name"]
+    /// `name` ***(Optional, requires the `serde` feature)***: The human-readable name of the field
+    /// 
+    /// This is only metadata, and is used when converting the struct to/from
+    /// JSON with field annotations. It has no effect on encoding/decoding.
+    /// 
+    /// # Syntax
+    /// 
+    /// `name = <string-literal>`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x05, name = "Platform Heading Angle", ...)]`
+    Name,
+
+    #[value = "units"]
+    /// `units` ***(Optional, requires the `serde` feature)***: The units of the field
+    /// 
+    /// This is only metadata, and is used when converting the struct to/from
+    /// JSON with field annotations. It has no effect on encoding/decoding.
+    /// 
+    /// # Syntax
+    /// 
+    /// `units = <string-literal>`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x05, units = "deg", ...)]`
+    Units,
+
+    #[value = "default"]
+    /// `default` ***(Optional)***: The value used when the field is missing from a packet
+    /// 
+    /// The expression must be of the field's type (or, for [`Option<T>`] fields, of
+    /// type `T`, which is then wrapped in [`Some`]). Setting a default implies
+    /// `required = false`.
+    /// 
+    /// # Syntax
+    /// 
+    /// `default = <expression>`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x01, default = 0, ...)]`
+    /// * `#[klv(key = 0x02, default = String::from("N/A"), ...)]`
+    /// * `#[klv(key = 0x03, default = crate::DEFAULT_HEADING, ...)]`
+    Default,
+
+    #[value = "required"]
+    /// `required` ***(Optional)***: Whether decoding fails when the field is missing from a packet
+    /// 
+    /// Non-[`Option`] fields which are not required fall back to their
+    /// [`Default::default`] value. [`Option`] fields which are required fail to
+    /// decode when missing, instead of being set to [`None`].
+    /// 
+    /// # Syntax
+    /// 
+    /// `required = <bool>`
+    /// 
+    /// # Defaults to
+    /// 
+    /// `false` for [`Option`] fields, or fields with a `default`, otherwise `true`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x01, required = false, ...)]`
+    Required,
+
+    #[value = "skip_if"]
+    /// `skip_if` ***(Optional)***: Predicate used to omit the field when encoding
+    /// 
+    /// A path to a function with signature `fn(&T) -> bool`, where `T` is the type
+    /// of the field. When it returns `true`, the key, length and value of the field
+    /// are not encoded. This is commonly used for low-bandwidth profiles, to omit
+    /// fields which are equal to their default.
+    /// 
+    /// # Syntax
+    /// 
+    /// `skip_if = <path-to-predicate>`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x01, skip_if = crate::is_zero, ...)]`
+    /// * `#[klv(key = 0x02, skip_if = String::is_empty, ...)]`
+    SkipIf,
+
+    #[value = "order"]
+    /// `order` ***(Optional)***: The position of the field when encoding
+    /// 
+    /// Fields are encoded in the following order:
+    /// 
+    /// 1. fields marked `first`, in declaration order
+    /// 2. fields with an `order`, in ascending order (ties in declaration order)
+    /// 3. fields without any ordering, in declaration order
+    /// 4. fields marked `last`, in declaration order
+    /// 
+    /// Decoding accepts fields in any order.
+    /// 
+    /// # Syntax
+    /// 
+    /// `order = <unsigned integer>`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x05, order = 1, ...)]`
+    Order,
+
+    #[value = "first"]
+    /// `first` ***(Optional)***: Encodes the field before all others
+    /// 
+    /// See [`FieldNames::Order`]. For example, the MISB ST 0601 timestamp (tag 2)
+    /// must be the first item of a packet.
+    /// 
+    /// # Syntax
+    /// 
+    /// `first`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x02, first, ...)]`
+    First,
+
+    #[value = "last"]
+    /// `last` ***(Optional)***: Encodes the field after all others
+    /// 
+    /// See [`FieldNames::Order`]. For example, the MISB ST 0601 checksum (tag 1)
+    /// must be the last item of a packet.
+    /// 
+    /// # Syntax
+    /// 
+    /// `last`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(key = 0x01, last, ...)]`
+    Last,
+
+    #[value = "record_order"]
+    /// `record_order` ***(Optional)***: Records the order in which keys arrived when decoding
+    /// 
+    /// Set on a single field of type [`Vec<usize>`], ***without*** a `key`. When decoding,
+    /// the index of each recognized field is pushed as its key arrives, where the index is
+    /// the position of the field in `KlvSchema::FIELDS` (declaration order of the
+    /// `#[klv(..)]` fields). When encoding, the fields are emitted in the recorded order
+    /// first, followed by any remaining fields in the order described in [`FieldNames::Order`].
+    /// This allows a re-encode to reproduce the original ordering exactly.
+    /// 
+    /// # Syntax
+    /// 
+    /// `record_order`
+    /// 
+    /// # Example usage
+    /// 
+    /// * `#[klv(record_order)] arrival: Vec<usize>`
+    RecordOrder,
 }
\ No newline at end of file
@@ -49,12 +49,19 @@ impl syn::parse::Parse for MetaValue {
                 let macro_: syn::Macro = input.parse()?;
                 return Ok(MetaValue::Macro(macro_));
             }
+            // --------------------------------------------------
+            // function calls: a path followed by '('
+            // --------------------------------------------------
+            if fork.peek(syn::token::Paren) {
+                let call: syn::Expr = input.parse()?;
+                return Ok(MetaValue::Expr(call));
+            }
         }
         drop(fork);
         // --------------------------------------------------
         // prioritize expressions
         // --------------------------------------------------
-        if input.peek(syn::token::Paren) || input.peek(syn::token::Brace) || input.peek(syn::Token![if]) || input.peek(syn::Token![match]) {
+        if input.peek(syn::token::Paren) || input.peek(syn::token::Brace) || input.peek(syn::Token![if]) || input.peek(syn::Token![match]) || input.peek(syn::Token![-]) {
             if let Ok(x) = input.parse::<syn::Expr>() {
                 return Ok(MetaValue::Expr(x));
             }
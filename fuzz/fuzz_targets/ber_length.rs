@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tinyklv::prelude::*;
+use tinyklv::codecs::ber::{BerLength, BerLengthConfig};
+
+fuzz_target!(|data: &[u8]| {
+    // must never panic, for any target type or configuration
+    let _ = BerLength::<u8>::decode(&mut &data[..]);
+    let _ = BerLength::<u64>::decode(&mut &data[..]);
+    let _ = BerLength::<u128>::decode_with(&mut &data[..], &BerLengthConfig::new().der());
+    let _ = tinyklv::dec::ber::ber_length_max::<65536>(&mut &data[..]);
+    // anything which decodes must re-encode (canonically) to the same length
+    if let Ok(len) = BerLength::<u128>::decode(&mut &data[..]) {
+        let encoded = len.encode_value();
+        let decoded = BerLength::<u128>::decode_with(&mut &encoded[..], &BerLengthConfig::new().der()).unwrap();
+        assert_eq!(decoded.as_u128(), len.as_u128());
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tinyklv::prelude::*;
+use tinyklv::codecs::ber::BerOid;
+
+fuzz_target!(|data: &[u8]| {
+    // must never panic, for any target type
+    let _ = BerOid::<u8>::decode(&mut &data[..]);
+    let _ = BerOid::<u64>::decode(&mut &data[..]);
+    // anything which decodes must re-encode to a value which decodes the same
+    if let Ok(oid) = BerOid::<u128>::decode(&mut &data[..]) {
+        let encoded = oid.encode_value();
+        assert_eq!(BerOid::<u128>::decode(&mut &encoded[..]).unwrap(), oid);
+    }
+});
@@ -0,0 +1,40 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = 0x06,
+    key(enc = encode_key, dec = tinyklv::dec::ber::ber_oid::<u64>),
+    len(enc = encode_len, dec = tinyklv::dec::ber::ber_length),
+)]
+/// A MISB ST 0601-like local set, with BER-OID keys and BER lengths
+struct Uas {
+    #[klv(key = 0x02, enc = encode_u64, dec = tinyklv::dec::binary::be_u64)]
+    timestamp: u64,
+
+    #[klv(key = 0x03, dyn = true, enc = encode_string, dec = tinyklv::dec::binary::to_string_utf8)]
+    mission: Option<String>,
+
+    #[klv(key = 0x05, enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+
+    #[klv(key = 0x0D, enc = encode_opt_i32, dec = tinyklv::dec::binary::be_i32)]
+    latitude: Option<i32>,
+}
+
+fn encode_key(key: u64) -> Vec<u8> { tinyklv::enc::ber::ber_oid(&key) }
+fn encode_len(len: usize) -> Vec<u8> { tinyklv::enc::ber::ber_length(&len) }
+fn encode_u64(input: &u64) -> Vec<u8> { tinyklv::enc::binary::be_u64(*input) }
+fn encode_string(input: &Option<String>) -> Option<Vec<u8>> { input.as_ref().map(|x| x.as_bytes().to_vec()) }
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_u16) }
+fn encode_opt_i32(input: &Option<i32>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_i32) }
+
+fuzz_target!(|data: &[u8]| {
+    // must never panic
+    let Ok(uas) = Uas::extract(&mut &data[..]) else { return };
+    // anything which extracts must survive a round trip
+    let encoded = uas.encode();
+    assert_eq!(Uas::extract(&mut &encoded[..]).unwrap(), uas);
+});
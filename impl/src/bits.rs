@@ -0,0 +1,186 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::Error;
+
+// --------------------------------------------------
+// constants
+// --------------------------------------------------
+const NAME: &str = "KlvBits";
+const ATTR: &str = "bits";
+const MAX_BYTES: u32 = 16;
+
+/// A field of a bit-packed struct, occupying bits `lo..lo + width`
+struct BitsField {
+    name: String,
+    member: syn::Member,
+    ty: syn::Type,
+    range: Option<(u32, u32)>,
+}
+
+/// Derive [`crate::KlvBits`]
+pub fn derive(input: &syn::DeriveInput) -> proc_macro::TokenStream {
+    let expanded = match expand(input) {
+        Ok(expanded) => expanded,
+        Err(err) => panic!("{}", err),
+    };
+    expanded.into()
+}
+
+fn expand(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let data = match &input.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(_) => return Err(Error::DeriveForNonStruct(NAME.into(), "enum".into())),
+        syn::Data::Union(_) => return Err(Error::DeriveForNonStruct(NAME.into(), "union".into())),
+    };
+    // --------------------------------------------------
+    // parse fields, and check for overlapping ranges
+    // --------------------------------------------------
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| parse_field(index, field))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut ranged: Vec<_> = fields
+        .iter()
+        .filter_map(|f| f.range.map(|(lo, width)| (lo, width, &f.name)))
+        .collect();
+    ranged.sort_by_key(|(lo, _, _)| *lo);
+    for pair in ranged.windows(2) {
+        if pair[0].0 + pair[0].1 > pair[1].0 {
+            return Err(Error::BitsOverlap(pair[0].2.clone(), pair[1].2.clone()));
+        }
+    }
+    // --------------------------------------------------
+    // width: explicit, or smallest number of bytes to hold the highest bit
+    // --------------------------------------------------
+    let highest = ranged.iter().map(|(lo, width, _)| lo + width).max().unwrap_or(0);
+    let bytes = match parse_bytes(input)? {
+        Some(bytes) => bytes,
+        None => highest.div_ceil(8).max(1),
+    };
+    if bytes == 0 || bytes > MAX_BYTES || highest > bytes * 8 {
+        return Err(Error::BitsWidth(name.to_string(), bytes, highest));
+    }
+    let bytes = bytes as usize;
+    // --------------------------------------------------
+    // generate
+    // --------------------------------------------------
+    let decoded = fields.iter().map(|f| {
+        let BitsField { member, ty, .. } = f;
+        match f.range {
+            Some((lo, width)) => quote! {
+                #member: <#ty as ::tinyklv::codecs::bits::BitField>::from_bits(::tinyklv::codecs::bits::get(word, #lo, #width))
+            },
+            None => quote! { #member: <#ty as ::core::default::Default>::default() },
+        }
+    });
+    // --------------------------------------------------
+    // the width of each range must fit in its field's type
+    // --------------------------------------------------
+    let fits = fields.iter().filter_map(|f| {
+        let ty = &f.ty;
+        let message = format!("Bit range of field `{}` is wider than its type.", f.name);
+        f.range.map(|(_, width)| quote_spanned! { ty.span() =>
+            const _: () = ::core::assert!(#width <= <#ty as ::tinyklv::codecs::bits::BitField>::BITS, #message);
+        })
+    });
+    let encoded = fields.iter().filter_map(|f| {
+        let member = &f.member;
+        f.range.map(|(lo, width)| quote! {
+            word = ::tinyklv::codecs::bits::set(word, #lo, #width, ::tinyklv::codecs::bits::BitField::to_bits(&self.#member));
+        })
+    });
+    Ok(quote! {
+        #(#fits)*
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation")]
+        impl #name {
+            /// The width of the bit-packed word, in bytes
+            pub const BYTES: usize = #bytes;
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`&[u8]`]")]
+        impl ::tinyklv::prelude::Decode<&[u8]> for #name {
+            fn decode(input: &mut &[u8]) -> ::tinyklv::reexport::winnow::PResult<Self> {
+                let word = ::tinyklv::codecs::bits::decode_word(#bytes)(input)?;
+                Ok(#name { #(#decoded,)* })
+            }
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::EncodeValue`]")]
+        impl ::tinyklv::prelude::EncodeValue<Vec<u8>> for #name {
+            fn encode_value(&self) -> Vec<u8> {
+                #[allow(unused_mut)]
+                let mut word: u128 = 0;
+                #(#encoded)*
+                ::tinyklv::codecs::bits::encode_word(#bytes)(word)
+            }
+        }
+    })
+}
+
+/// Parses the optional `#[bits(bytes = N)]` struct attribute
+fn parse_bytes(input: &syn::DeriveInput) -> Result<Option<u32>, Error> {
+    let mut bytes = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident(ATTR)) {
+        let nv: syn::MetaNameValue = attr
+            .parse_args()
+            .map_err(|_| Error::BitsInvalidAttr(input.ident.to_string()))?;
+        match (nv.path.is_ident("bytes"), &nv.lit) {
+            (true, syn::Lit::Int(lit)) => bytes = Some(lit
+                .base10_parse::<u32>()
+                .map_err(|_| Error::BitsInvalidAttr(input.ident.to_string()))?
+            ),
+            _ => return Err(Error::BitsInvalidAttr(input.ident.to_string())),
+        }
+    }
+    Ok(bytes)
+}
+
+/// Parses a field, with an optional `#[bits(N)]`, `#[bits(A..B)]` or `#[bits(A..=B)]` attribute
+fn parse_field(index: usize, field: &syn::Field) -> Result<BitsField, Error> {
+    let (name, member) = match &field.ident {
+        Some(ident) => (ident.to_string(), syn::Member::Named(ident.clone())),
+        None => (index.to_string(), syn::Member::Unnamed(index.into())),
+    };
+    let mut range = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident(ATTR)) {
+        let expr: syn::Expr = attr.parse_args().map_err(|_| Error::BitsInvalidRange(name.clone()))?;
+        let (lo, hi) = match &expr {
+            syn::Expr::Range(r) => {
+                let lo = r.from.as_deref().map_or(Some(0), int);
+                let hi = r.to.as_deref().and_then(int);
+                match (lo, hi, &r.limits) {
+                    (Some(lo), Some(hi), syn::RangeLimits::Closed(_)) => (lo, hi),
+                    (Some(lo), Some(hi), syn::RangeLimits::HalfOpen(_)) if hi > 0 => (lo, hi - 1),
+                    _ => return Err(Error::BitsInvalidRange(name)),
+                }
+            },
+            expr => match int(expr) {
+                Some(bit) => (bit, bit),
+                None => return Err(Error::BitsInvalidRange(name)),
+            },
+        };
+        if hi < lo || hi >= MAX_BYTES * 8 {
+            return Err(Error::BitsInvalidRange(name));
+        }
+        range = Some((lo, hi - lo + 1));
+    }
+    Ok(BitsField { name, member, ty: field.ty.clone(), range })
+}
+
+/// Parses an integer literal expression
+fn int(expr: &syn::Expr) -> Option<u32> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => lit.base10_parse::<u32>().ok(),
+        _ => None,
+    }
+}
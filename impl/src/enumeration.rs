@@ -0,0 +1,154 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use quote::quote;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::Error;
+
+// --------------------------------------------------
+// constants
+// --------------------------------------------------
+const NAME: &str = "KlvEnum";
+const REPRS: &[&str] = &["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+/// Derive [`crate::KlvEnum`]
+pub fn derive(input: &syn::DeriveInput) -> proc_macro::TokenStream {
+    let expanded = match expand(input) {
+        Ok(expanded) => expanded,
+        Err(err) => panic!("{}", err),
+    };
+    expanded.into()
+}
+
+fn expand(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let data = match &input.data {
+        syn::Data::Enum(data) => data,
+        syn::Data::Struct(_) => return Err(Error::DeriveForNonEnum(NAME.into(), "struct".into())),
+        syn::Data::Union(_) => return Err(Error::DeriveForNonEnum(NAME.into(), "union".into())),
+    };
+    // --------------------------------------------------
+    // representation: `#[repr(..)]`, defaulting to `u8`
+    // --------------------------------------------------
+    let mut repr: syn::Ident = syn::parse_quote!(u8);
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        if let Ok(ident) = attr.parse_args::<syn::Ident>() {
+            match REPRS.contains(&ident.to_string().as_str()) {
+                true => repr = ident,
+                false => return Err(Error::EnumInvalidRepr(name.to_string(), ident.to_string())),
+            }
+        }
+    }
+    // --------------------------------------------------
+    // variants: unit variants with values, and at most one fallback
+    // --------------------------------------------------
+    let mut values = Vec::new();
+    let mut fallback = None;
+    for variant in data.variants.iter() {
+        let vname = &variant.ident;
+        match &variant.fields {
+            syn::Fields::Unit => {
+                let mut value = variant.discriminant.as_ref().map(|(_, expr)| expr.clone());
+                for nv in klv_name_values(&variant.attrs, &vname.to_string())? {
+                    match (nv.path.is_ident("value"), nv.lit) {
+                        (true, lit) => value = Some(syn::Expr::Lit(syn::ExprLit { attrs: vec![], lit })),
+                        _ => return Err(Error::EnumInvalidAttr(vname.to_string())),
+                    }
+                }
+                match value {
+                    Some(value) => values.push((vname, value)),
+                    None => return Err(Error::EnumMissingValue(vname.to_string())),
+                }
+            },
+            syn::Fields::Unnamed(f) if f.unnamed.len() == 1 && fallback.is_none() => fallback = Some(vname),
+            _ => return Err(Error::EnumInvalidVariant(name.to_string(), vname.to_string())),
+        }
+    }
+    // --------------------------------------------------
+    // generate
+    // --------------------------------------------------
+    let vnames = values.iter().map(|(v, _)| v).collect::<Vec<_>>();
+    let vvalues = values.iter().map(|(_, x)| x).collect::<Vec<_>>();
+    let decoder = quote::format_ident!("be_{}", repr);
+    let (from_value_ret, from_value_fallback, value_fallback, decoded) = match fallback {
+        Some(fallback) => (
+            quote! { Self },
+            quote! { _ => #name::#fallback(value) },
+            quote! { #name::#fallback(value) => *value, },
+            quote! { Ok(#name::from_value(::tinyklv::codecs::binary::dec::#decoder(input)?)) },
+        ),
+        None => (
+            quote! { Option<Self> },
+            quote! { _ => return None },
+            quote! {},
+            quote! {
+                use ::tinyklv::reexport::winnow::stream::Stream;
+                use ::tinyklv::reexport::winnow::error::AddContext;
+                let checkpoint = input.checkpoint();
+                match #name::from_value(::tinyklv::codecs::binary::dec::#decoder(input)?) {
+                    Some(value) => Ok(value),
+                    None => {
+                        input.reset(&checkpoint);
+                        Err(::tinyklv::reexport::winnow::error::ErrMode::Backtrack(
+                            ::tinyklv::reexport::winnow::error::ContextError::new().add_context(
+                                input,
+                                &checkpoint,
+                                ::tinyklv::reexport::winnow::error::StrContext::Label(concat!("Unknown `", stringify!(#name), "` value")),
+                            )
+                        ))
+                    },
+                }
+            },
+        ),
+    };
+    let from_value_body = match fallback {
+        Some(_) => quote! { match value { #(#vvalues => #name::#vnames,)* #from_value_fallback } },
+        None => quote! { Some(match value { #(#vvalues => #name::#vnames,)* #from_value_fallback }) },
+    };
+    Ok(quote! {
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation")]
+        impl #name {
+            /// Returns the variant for a raw on-the-wire value
+            pub fn from_value(value: #repr) -> #from_value_ret {
+                #from_value_body
+            }
+
+            /// Returns the raw on-the-wire value of the variant
+            pub fn value(&self) -> #repr {
+                match self {
+                    #(#name::#vnames => #vvalues,)*
+                    #value_fallback
+                }
+            }
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`&[u8]`]")]
+        impl ::tinyklv::prelude::Decode<&[u8]> for #name {
+            fn decode(input: &mut &[u8]) -> ::tinyklv::reexport::winnow::PResult<Self> {
+                #decoded
+            }
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::EncodeValue`]")]
+        impl ::tinyklv::prelude::EncodeValue<Vec<u8>> for #name {
+            fn encode_value(&self) -> Vec<u8> {
+                self.value().to_be_bytes().to_vec()
+            }
+        }
+    })
+}
+
+/// Collects all `name = value` pairs in `#[klv(..)]` attributes
+fn klv_name_values(attrs: &[syn::Attribute], name: &str) -> Result<Vec<syn::MetaNameValue>, Error> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(crate::ATTR))
+        .map(|attr| attr
+            .parse_args_with(syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated)
+            .map_err(|_| Error::EnumInvalidAttr(name.into()))
+        )
+        .try_fold(Vec::new(), |mut acc, x| { acc.extend(x?); Ok(acc) })
+}
@@ -39,6 +39,12 @@ impl From<kst::Input> for proc_macro::TokenStream {
                 .for_each(|x| f.contents.update(&f.ty, &x));
         }
         // --------------------------------------------------
+        // transparent newtypes delegate to their inner value
+        // --------------------------------------------------
+        if input.sattr.transparent {
+            return gen_transparent_impl(&input).into()
+        }
+        // --------------------------------------------------
         // set default stream to &[u8], if not set
         // --------------------------------------------------
         // if match input.sattr.stream {
@@ -69,13 +75,62 @@ impl From<kst::Input> for proc_macro::TokenStream {
                 #encode_impl
             }
         }
-        // println!("{}", input.sattr);
-        // println!("{:?}", input.fattrs);
-        println!("{}", expanded);
+        let schema_impl = gen_schema_impl(&input);
+        expanded = quote! {
+            #expanded
+            #schema_impl
+        };
+        #[cfg(feature = "serde")]
+        {
+            let field_meta_impl = gen_field_meta_impl(&input);
+            expanded = quote! {
+                #expanded
+                #field_meta_impl
+            }
+        }
         expanded.into()
     }
 }
 
+/// Generates the tokens for the [`tinyklv::prelude::Decode`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.Decode.html)
+/// and [`tinyklv::prelude::EncodeValue`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.EncodeValue.html)
+/// implementations of a `#[klv(transparent)]` struct
+/// 
+/// Uses the `dec` / `enc` of the single field if set, otherwise delegates to
+/// the implementations of the inner type
+fn gen_transparent_impl(input: &kst::Input) -> proc_macro2::TokenStream {
+    let name = &input.name;
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    // ---- safe: `kst::Input` checks for exactly one field
+    let field = &input.fattrs[0];
+    let kst::FieldAttrSchema { member, ty, .. } = field;
+    let decoded = match field.contents.dec() {
+        Some(dec) => quote! { #dec (input)? },
+        None => quote! { <#ty as ::tinyklv::prelude::Decode<#stream>>::decode(input)? },
+    };
+    let output = owned_output(&stream);
+    let encoded = match field.contents.enc() {
+        Some(enc) => quote! { #enc (&self.#member) },
+        None => quote! { ::tinyklv::prelude::EncodeValue::<#output>::encode_value(&self.#member) },
+    };
+    quote! {
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`", stringify!(#stream), "`]")]
+        impl ::tinyklv::prelude::Decode<#stream> for #name {
+            fn decode(input: &mut #stream) -> ::tinyklv::reexport::winnow::PResult<Self> {
+                Ok(#name { #member: #decoded })
+            }
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::EncodeValue`]")]
+        impl ::tinyklv::prelude::EncodeValue<#output> for #name {
+            fn encode_value(&self) -> #output {
+                #encoded
+            }
+        }
+    }
+}
+
 /// Generates the tokens for the entire [`tinyklv::prelude::Encode`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.Encode.html) implementation
 fn gen_encode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
     let name = &input.name;
@@ -83,28 +138,31 @@ fn gen_encode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
     // default stream -> &[u8]
     // --------------------------------------------------
     let sentinel = input.sattr.sentinel.as_ref().map_or(None, |x| x.get().clone());
-    let key_encoder = input
-        .sattr.key.value.clone()
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "key".into(), "enc".into(), "encoder".into())))
-        .xcoder.enc
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "key".into(), "enc".into(), "encoder".into())));
-    let len_encoder = input
-        .sattr.len.value.clone()
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "len".into(), "enc".into(), "encoder".into())))
-        .xcoder.enc
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "len".into(), "enc".into(), "encoder".into())));
-
-    let items_encoded = gen_items_encoded(&input, &key_encoder, &len_encoder);
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let output = owned_output(&stream);
+    let items_encoded = match input.form {
+        kst::Form::DefinedPack => gen_pack_items_encoded(input, None),
+        kst::Form::VariablePack => gen_pack_items_encoded(input, Some(&struct_xcoder(input, "len", true))),
+        kst::Form::LocalSet | kst::Form::UniversalSet => gen_items_encoded(&input, &struct_xcoder(input, "key", true), &struct_xcoder(input, "len", true)),
+    };
 
     let encode_with_key_len = match sentinel {
-        Some(sentinel) => quote! {
-            #[automatically_derived]
-            impl ::tinyklv::prelude::Encode<Vec<u8>> for #name {
-                fn encode(&self) -> Vec<u8> {
-                    self.encode_value().into_klv(
-                        #key_encoder (#sentinel),
-                        #len_encoder ,
-                    )
+        Some(sentinel) => {
+            // ---- universal labels are written as-is, not as a key
+            let encoded_sentinel = match input.sentinel_ignores_version {
+                true => quote! { #sentinel.to_vec() },
+                false => {
+                    let key_encoder = struct_xcoder(input, "key", true);
+                    quote! { #key_encoder (#sentinel) }
+                },
+            };
+            let into_klv = gen_into_klv(&stream, quote! { self.encode_value() }, encoded_sentinel, &struct_xcoder(input, "len", true));
+            quote! {
+                #[automatically_derived]
+                impl ::tinyklv::prelude::Encode<#output> for #name {
+                    fn encode(&self) -> #output {
+                        #into_klv
+                    }
                 }
             }
         },
@@ -113,9 +171,9 @@ fn gen_encode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
 
     quote! {
         #[automatically_derived]
-        impl ::tinyklv::prelude::EncodeValue<Vec<u8>> for #name {
-            fn encode_value(&self) -> Vec<u8> {
-                let mut output = vec![];
+        impl ::tinyklv::prelude::EncodeValue<#output> for #name {
+            fn encode_value(&self) -> #output {
+                let mut output = <#output>::new();
                 #items_encoded
                 output
             }
@@ -125,22 +183,276 @@ fn gen_encode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
 }
 
 fn gen_items_encoded(input: &kst::Input, key_encoder: &PathLike, len_encoder: &PathLike) -> proc_macro2::TokenStream {
-    let items_encoded = input.fattrs.iter().map(|field| {
-        let name = &field.name;
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let items_encoded: Vec<_> = input.fattrs.iter().map(|field| {
+        let name = &field.member;
         let value_encoder = field
             .contents.enc()
             .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "value".into(), "enc".into(), "encoder".into())));
         let key = field
             .contents.key
-            .value.clone().unwrap_or_else(|| panic!("{}", crate::Error::MissingKey(name.to_string()))
+            .value.clone().unwrap_or_else(|| panic!("{}", crate::Error::MissingKey(field.member_str()))
         );
+        let into_klv = gen_into_klv(&stream, quote! { #value_encoder(&self.#name) }, quote! { #key_encoder(#key) }, len_encoder);
+        let extend = match crate::parse::is_str_stream(&stream) {
+            true => quote! { output.push_str(&#into_klv); },
+            false => quote! { output.extend(#into_klv); },
+        };
+        match &field.contents.skip_if {
+            Some(skip_if) => quote! {
+                if !#skip_if(&self.#name) {
+                    #extend
+                }
+            },
+            None => extend,
+        }
+    }).collect();
+    // --------------------------------------------------
+    // stable sort, to keep ties in declaration order
+    // --------------------------------------------------
+    let mut sorted: Vec<usize> = (0..input.fattrs.len()).collect();
+    sorted.sort_by_key(|index| input.fattrs[*index].contents.encode_rank());
+    match &input.order_field {
+        None => {
+            let items_encoded = sorted.iter().map(|index| &items_encoded[*index]);
+            quote! { #(#items_encoded)* }
+        },
+        // --------------------------------------------------
+        // recorded order first, then the remaining fields
+        // --------------------------------------------------
+        Some(order_field) => {
+            let num_fields = input.fattrs.len();
+            let indices = 0..num_fields;
+            quote! {
+                let mut __tinyklv_emitted = [false; #num_fields];
+                let __tinyklv_sorted: [usize; #num_fields] = [#(#sorted),*];
+                for __tinyklv_index in self.#order_field.iter().copied().chain(__tinyklv_sorted) {
+                    if __tinyklv_index >= #num_fields || __tinyklv_emitted[__tinyklv_index] { continue }
+                    __tinyklv_emitted[__tinyklv_index] = true;
+                    match __tinyklv_index {
+                        #(#indices => { #items_encoded })*
+                        _ => (),
+                    }
+                }
+            }
+        },
+    }
+}
+
+/// Generates the tokens for encoding the fields of a pack, in declaration order
+/// 
+/// Items of a `variable_pack` are prepended with their length, items of a
+/// `defined_pack` are not. An optional item which is [`None`] is not encoded,
+/// which is why only trailing items can be optional, see [`kst::Input`]
+fn gen_pack_items_encoded(input: &kst::Input, len_encoder: Option<&PathLike>) -> proc_macro2::TokenStream {
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let output = owned_output(&stream);
+    let len_encoder = match len_encoder {
+        Some(len_encoder) => quote! { #len_encoder },
+        None => quote! { |_| <#output>::new() },
+    };
+    let items_encoded = input.fattrs.iter().map(|field| {
+        let name = &field.member;
+        let value_encoder = field
+            .contents.enc()
+            .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "value".into(), "enc".into(), "encoder".into())));
         quote! {
-            output.extend(#value_encoder(&self.#name).into_klv(#key_encoder(#key), #len_encoder));
+            output.extend(::tinyklv::prelude::IntoKlv::into_klv(#value_encoder(&self.#name), <#output>::new(), #len_encoder));
         }
     });
     quote! { #(#items_encoded)* }
 }
 
+/// Returns the struct-level key / len encoder or decoder
+/// 
+/// # Panics
+/// 
+/// If it is not set
+fn struct_xcoder(input: &kst::Input, which: &str, enc: bool) -> PathLike {
+    let (xcoder, coder) = match enc {
+        true => ("enc", "encoder"),
+        false => ("dec", "decoder"),
+    };
+    let missing = || crate::Error::MissingFunc("struct".into(), which.into(), xcoder.into(), coder.into());
+    let key_len = match which {
+        "key" => input.sattr.key.value.clone(),
+        _ => input.sattr.len.value.clone(),
+    }.unwrap_or_else(|| panic!("{}", missing()));
+    match enc {
+        true => key_len.xcoder.enc,
+        false => key_len.xcoder.dec,
+    }.unwrap_or_else(|| panic!("{}", missing()))
+}
+
+/// Returns the owned output type of encoding to a stream: [`String`] for
+/// `&str`, otherwise [`Vec<u8>`]
+fn owned_output(stream: &syn::Type) -> proc_macro2::TokenStream {
+    match crate::parse::is_str_stream(stream) {
+        true => quote! { String },
+        false => quote! { Vec<u8> },
+    }
+}
+
+/// Generates the tokens for prepending an encoded value with its key and length
+/// 
+/// Uses [`tinyklv::prelude::IntoKlvString`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.IntoKlvString.html) for
+/// `&str` streams, otherwise [`tinyklv::prelude::IntoKlv`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.IntoKlv.html)
+fn gen_into_klv(stream: &syn::Type, value: proc_macro2::TokenStream, key: proc_macro2::TokenStream, len_encoder: &PathLike) -> proc_macro2::TokenStream {
+    match crate::parse::is_str_stream(stream) {
+        true => quote! { ::tinyklv::prelude::IntoKlvString::into_klv_string(#value, #key, #len_encoder) },
+        false => quote! { ::tinyklv::prelude::IntoKlv::into_klv(#value, #key, #len_encoder) },
+    }
+}
+
+/// Generates the tokens for the entire [`tinyklv::prelude::KlvSchema`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.KlvSchema.html) implementation
+fn gen_schema_impl(input: &kst::Input) -> proc_macro2::TokenStream {
+    let name = &input.name;
+    let name_str = name.to_string();
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let stream_str = crate::parse::compact_tokens(&stream);
+    let sentinel = match input.sattr.sentinel.as_ref().and_then(|x| x.get()).and_then(crate::parse::lit_bytes) {
+        Some(bytes) => quote! { Some(&[#(#bytes),*]) },
+        None => quote! { None },
+    };
+    let sentinel_ignores_version = input.sentinel_ignores_version;
+    let xcoder_str = |x: Option<&PathLike>| match x {
+        Some(x) => {
+            let x = crate::parse::compact_tokens(x);
+            quote! { Some(#x) }
+        },
+        None => quote! { None },
+    };
+    let key_encoder = xcoder_str(input.sattr.key.get().and_then(|x| x.xcoder.enc.as_ref()));
+    let key_decoder = xcoder_str(input.sattr.key.get().and_then(|x| x.xcoder.dec.as_ref()));
+    let len_encoder = xcoder_str(input.sattr.len.get().and_then(|x| x.xcoder.enc.as_ref()));
+    let len_decoder = xcoder_str(input.sattr.len.get().and_then(|x| x.xcoder.dec.as_ref()));
+    let field_by_key = gen_field_by_key(input);
+    let fields = input.fattrs.iter().map(|field| {
+        let fname = field.member_str();
+        // ---- fields of packs have no key
+        let (key_literal, key_bytes) = match (field.contents.key.get(), input.form.is_pack()) {
+            (Some(key), _) => (crate::parse::compact_tokens(key), crate::parse::lit_bytes(key).unwrap_or_default()),
+            (None, true) => (String::new(), Vec::new()),
+            (None, false) => panic!("{}", crate::Error::MissingKey(fname.clone())),
+        };
+        let ty = crate::parse::compact_tokens(&field.ty);
+        let dynlen = field.contents.dynlen().unwrap_or(false);
+        let optional = !field.contents.is_required(&field.ty);
+        let enc = xcoder_str(field.contents.enc());
+        let dec = xcoder_str(field.contents.dec());
+        quote! {
+            ::tinyklv::prelude::FieldSchema {
+                key: &[#(#key_bytes),*],
+                key_literal: #key_literal,
+                field: #fname,
+                ty: #ty,
+                dynlen: #dynlen,
+                optional: #optional,
+                enc: #enc,
+                dec: #dec,
+            }
+        }
+    });
+    quote! {
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::KlvSchema`]")]
+        impl ::tinyklv::prelude::KlvSchema for #name {
+            const NAME: &'static str = #name_str;
+            const STREAM: &'static str = #stream_str;
+            const SENTINEL: Option<&'static [u8]> = #sentinel;
+            const SENTINEL_IGNORES_VERSION: bool = #sentinel_ignores_version;
+            const KEY_ENCODER: Option<&'static str> = #key_encoder;
+            const KEY_DECODER: Option<&'static str> = #key_decoder;
+            const LEN_ENCODER: Option<&'static str> = #len_encoder;
+            const LEN_DECODER: Option<&'static str> = #len_decoder;
+            const FIELDS: &'static [::tinyklv::prelude::FieldSchema] = &[ #(#fields),* ];
+            #field_by_key
+        }
+    }
+}
+
+/// Generates the tokens for [`tinyklv::prelude::KlvSchema::field_by_key`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.KlvSchema.html#method.field_by_key),
+/// looking up fields by their key as found on the wire
+/// 
+/// The key is decoded with the struct-level key decoder and matched against
+/// the key literals, like when decoding a packet. Without a key decoder, the
+/// key literals are encoded with the key encoder instead. Without either (or
+/// for packs), the default of matching the bytes of the key literals is kept
+fn gen_field_by_key(input: &kst::Input) -> proc_macro2::TokenStream {
+    if input.form.is_pack() {
+        return quote! {}
+    }
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let keys: Vec<_> = input.fattrs.iter().filter_map(|field| field.contents.key.get().clone()).collect();
+    let indices = 0..keys.len();
+    let key_xcoder = input.sattr.key.get();
+    let body = match (key_xcoder.and_then(|x| x.xcoder.dec.as_ref()), key_xcoder.and_then(|x| x.xcoder.enc.as_ref())) {
+        (Some(key_decoder), _) => {
+            let as_stream = match crate::parse::is_str_stream(&stream) {
+                true => quote! { ::core::str::from_utf8(key).ok()? },
+                false => quote! { key },
+            };
+            quote! {
+                let mut input: #stream = #as_stream;
+                let decoded = ::tinyklv::reexport::winnow::Parser::parse_next(&mut #key_decoder, &mut input).ok()?;
+                if ::tinyklv::reexport::winnow::stream::Stream::eof_offset(&input) != 0 {
+                    return None
+                }
+                match decoded {
+                    #(#keys => Some(&Self::FIELDS[#indices]),)*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        },
+        (None, Some(key_encoder)) => quote! {
+            #(
+                if ::core::convert::AsRef::<[u8]>::as_ref(&#key_encoder(#keys)) == key {
+                    return Some(&Self::FIELDS[#indices])
+                }
+            )*
+            None
+        },
+        (None, None) => return quote! {},
+    };
+    quote! {
+        fn field_by_key(key: &[u8]) -> Option<&'static ::tinyklv::prelude::FieldSchema> {
+            #body
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Generates the tokens for the entire [`tinyklv::json::KlvFieldMeta`](https://docs.rs/tinyklv/latest/tinyklv/json/trait.KlvFieldMeta.html) implementation
+fn gen_field_meta_impl(input: &kst::Input) -> proc_macro2::TokenStream {
+    let name = &input.name;
+    let metas = input.fattrs.iter().map(|field| {
+        let fname = field.member_str();
+        let hname = match field.contents.name.get() {
+            Some(x) => quote! { Some(#x) },
+            None => quote! { None },
+        };
+        let units = match field.contents.units.get() {
+            Some(x) => quote! { Some(#x) },
+            None => quote! { None },
+        };
+        quote! {
+            ::tinyklv::json::FieldMeta {
+                field: #fname,
+                name: #hname,
+                units: #units,
+            }
+        }
+    });
+    quote! {
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::json::KlvFieldMeta`]")]
+        impl ::tinyklv::json::KlvFieldMeta for #name {
+            const FIELD_META: &'static [::tinyklv::json::FieldMeta] = &[ #(#metas),* ];
+        }
+    }
+}
+
 /// Generates the tokens for the entire [`tinyklv::prelude::Decode`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.Decode.html) implementation
 fn gen_decode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
     let name = &input.name;
@@ -150,21 +462,25 @@ fn gen_decode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
     let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
     let stream_lifetimed = crate::parse::insert_lifetime(&stream, PACKET_LIFETIME_CHAR);
     let sentinel = input.sattr.sentinel.as_ref().map_or(None, |x| x.get().clone());
-    let key_decoder = input
-        .sattr.key.value.clone()
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "key".into(), "dec".into(), "decoder".into())))
-        .xcoder.dec
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "key".into(), "dec".into(), "decoder".into())));
-    let len_decoder = input
-        .sattr.len.value.clone()
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "len".into(), "dec".into(), "decoder".into())))
-        .xcoder.dec
-        .unwrap_or_else(|| panic!("{}", crate::Error::MissingFunc("struct".into(), "len".into(), "dec".into(), "decoder".into())));
-    let items_init = gen_items_init(&input.fattrs);
-    let items_match = gen_items_match(&input.fattrs);
-    let items_set = gen_item_set(name, &input.fattrs, crate::parse::elems_without_klv_attr(&input.input));
+    // --------------------------------------------------
+    // text records are terminated, see `tinyklv::codecs::string`
+    // --------------------------------------------------
+    let skip_terminator = match crate::parse::is_str_stream(&stream) {
+        true => quote! {
+            let _ = ::tinyklv::reexport::winnow::combinator::opt::<_, _, ::tinyklv::reexport::winnow::error::ContextError, _>(
+                ::tinyklv::codecs::string::TERMINATOR
+            ).parse_next(input);
+        },
+        false => quote! {},
+    };
     let seek_if_sentinel = match sentinel {
-        Some(sentinel) => quote! {
+        Some(sentinel) => {
+            let len_decoder = struct_xcoder(input, "len", false);
+            let sentinel = match input.sentinel_ignores_version {
+                true => quote! { ::tinyklv::codecs::ul::UniversalLabel::new(*#sentinel).literal_ignoring_version() },
+                false => quote! { #sentinel },
+            };
+            quote! {
             #[automatically_derived]
             #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Seek`] for [`", stringify!(#stream), "`]")]
             impl ::tinyklv::prelude::Seek<#stream> for #name {
@@ -187,33 +503,85 @@ fn gen_decode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
                             )
                         )),
                     };
-                    ::tinyklv::reexport::winnow::token::take(packet_len).parse_next(input)
+                    let packet = ::tinyklv::reexport::winnow::token::take(packet_len).parse_next(input)?;
+                    #skip_terminator
+                    Ok(packet)
                 }
             }
+            }
         },
         None => quote! {}
     };
+    if input.form.is_pack() {
+        let pack_decode_impl = gen_pack_decode_impl(input);
+        return quote! {
+            #seek_if_sentinel
+            #pack_decode_impl
+        }
+    }
+    let struct_str = name.to_string();
+    let key_decoder = struct_xcoder(input, "key", false);
+    let len_decoder = struct_xcoder(input, "len", false);
+    let items_init = gen_items_init(&input.fattrs, input.order_field.is_some());
+    let items_match = gen_items_match(name, &input.fattrs, input.order_field.is_some());
+    let items_set = gen_item_set(name, &input.fattrs, crate::parse::elems_without_klv_attr(&input.input), input.order_field.as_ref());
     let result = quote! {
         #seek_if_sentinel
-        #[automatically_derived]
-        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`", stringify!(#stream), "`]")]
-        impl ::tinyklv::prelude::Decode<#stream> for #name {
-            fn decode(input: &mut #stream) -> ::tinyklv::reexport::winnow::PResult<Self> {
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation")]
+        impl #name {
+            /// Decodes the packet, alongside a [`tinyklv::prelude::DecodeReport`] of
+            /// the unknown keys, duplicate keys, failed fields, length mismatches
+            /// and trailing bytes skipped while decoding
+            pub fn decode_with_report(input: &mut #stream) -> ::tinyklv::reexport::winnow::PResult<(Self, ::tinyklv::prelude::DecodeReport)> {
+                use ::tinyklv::reexport::winnow::stream::Offset;
                 let checkpoint = input.checkpoint();
+                let mut __tinyklv_report = ::tinyklv::prelude::DecodeReport::default();
                 #items_init
                 loop {
+                    let __tinyklv_key_checkpoint = input.checkpoint();
+                    let __tinyklv_key_offset = input.offset_from(&checkpoint);
                     match (
                         #key_decoder,
                         #len_decoder,
                     ).parse_next(input) {
-                        Ok((key, len)) => match (key, len) {
-                            #items_match
-                            (_, len) => { let _ = ::tinyklv::reexport::winnow::token::take::<usize, #stream, ::tinyklv::reexport::winnow::error::ContextError>(len).parse_next(input); },
+                        Ok((key, len)) => {
+                            let __tinyklv_offset = input.offset_from(&checkpoint);
+                            match (key, len) {
+                                #items_match
+                                (key, len) => {
+                                    __tinyklv_report.unknown.push(::tinyklv::prelude::UnknownKey {
+                                        key: format!("{:?}", key),
+                                        offset: __tinyklv_key_offset,
+                                        len,
+                                    });
+                                    // ---- a truncated value ends the packet, and is left as trailing
+                                    if ::tinyklv::reexport::winnow::token::take::<usize, #stream, ::tinyklv::reexport::winnow::error::ContextError>(len).parse_next(input).is_err() {
+                                        __tinyklv_report.length_mismatches.push(
+                                            ::tinyklv::prelude::KlvError::new(::tinyklv::prelude::KlvErrorKind::LengthMismatch { expected: len, actual: input.eof_offset() })
+                                                .within(#struct_str, "unknown key", __tinyklv_offset)
+                                        );
+                                        input.reset(&__tinyklv_key_checkpoint);
+                                        break
+                                    }
+                                },
+                            }
+                            #skip_terminator
+                        },
+                        Err(_) => {
+                            input.reset(&__tinyklv_key_checkpoint);
+                            break
                         },
-                        Err(_) => break,
                     }
                 }
-                #items_set
+                __tinyklv_report.trailing = input.eof_offset();
+                Ok((#items_set, __tinyklv_report))
+            }
+        }
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`", stringify!(#stream), "`]")]
+        impl ::tinyklv::prelude::Decode<#stream> for #name {
+            fn decode(input: &mut #stream) -> ::tinyklv::reexport::winnow::PResult<Self> {
+                #name::decode_with_report(input).map(|(decoded, _)| decoded)
             }
         }
     };
@@ -221,35 +589,172 @@ fn gen_decode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
     result
 }
 
+/// Generates the tokens for the [`tinyklv::prelude::Decode`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.Decode.html)
+/// implementation of a `defined_pack` or `variable_pack`
+/// 
+/// Items are decoded in declaration order, without keys. Items of a
+/// `variable_pack` are decoded from a sub-slice of their decoded length, see
+/// [`tinyklv::prelude::decode_value`](https://docs.rs/tinyklv/latest/tinyklv/prelude/fn.decode_value.html),
+/// so that an optional item which fails to decode is skipped. Optional items
+/// cut off by the end of the packet are [`None`]
+/// 
+/// Packs have no keys to report on, so no `decode_with_report` is generated
+fn gen_pack_decode_impl(input: &kst::Input) -> proc_macro2::TokenStream {
+    let name = &input.name;
+    let struct_str = name.to_string();
+    let stream = input.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+    let len_decoder = match input.form {
+        kst::Form::VariablePack => Some(struct_xcoder(input, "len", false)),
+        _ => None,
+    };
+    let items_decoded = input.fattrs.iter().enumerate().map(|(index, field)| {
+        let kst::FieldAttrSchema { name, ty, contents, .. } = field;
+        let fname = field.member_str();
+        let item_label = format!("item {}", index);
+        let error = error_ident(name);
+        let dec = contents.dec().clone().unwrap_or_else(||
+            panic!("{}", crate::Error::MissingFunc(format!("field `{}`", field.member_str()), "value".into(), "dec".into(), "decoder".into()))
+        );
+        let optional_len_arg = if let Some(true) = contents.dynlen() { quote! { (len) } } else { quote! {} };
+        let decoded = match &len_decoder {
+            Some(len_decoder) => quote! {
+                #len_decoder
+                    .parse_next(input)
+                    .map_err(::tinyklv::prelude::IntoKlvError::into_klv_error)
+                    .and_then(|len| ::tinyklv::prelude::decode_value(input, len, #dec #optional_len_arg))
+            },
+            None => quote! { #dec (input).map_err(::tinyklv::prelude::IntoKlvError::into_klv_error) },
+        };
+        let required = contents.is_required(ty);
+        let (on_error, on_eof, error_initialization) = match required {
+            true => (
+                quote! { return Err(__tinyklv_err.field(#fname).within(#struct_str, #item_label, __tinyklv_offset).into_winnow(input)) },
+                quote! {
+                    return Err(::tinyklv::prelude::KlvError::new(::tinyklv::prelude::KlvErrorKind::MissingField)
+                        .field(#fname)
+                        .within(#struct_str, #item_label, __tinyklv_offset)
+                        .into_winnow(input)
+                    )
+                },
+                quote! { let #error: Option<::tinyklv::prelude::KlvError> = None; },
+            ),
+            false => (quote! { None }, quote! { None }, quote! {}),
+        };
+        let ty = crate::parse::unwrap_option_type(ty).unwrap_or(ty);
+        quote! {
+            let __tinyklv_offset = input.offset_from(&checkpoint);
+            let #name: Option<#ty> = match input.eof_offset() {
+                0 => { #on_eof },
+                _ => match #decoded {
+                    Ok(__tinyklv_value) => Some(__tinyklv_value),
+                    Err(__tinyklv_err) => { #on_error },
+                },
+            };
+            #error_initialization
+        }
+    });
+    let items_set = gen_item_set(name, &input.fattrs, crate::parse::elems_without_klv_attr(&input.input), None);
+    quote! {
+        #[automatically_derived]
+        #[doc = concat!(" [`", stringify!(#name), "`] implementation of [`tinyklv::prelude::Decode`] for [`", stringify!(#stream), "`]")]
+        impl ::tinyklv::prelude::Decode<#stream> for #name {
+            fn decode(input: &mut #stream) -> ::tinyklv::reexport::winnow::PResult<Self> {
+                use ::tinyklv::reexport::winnow::stream::Offset;
+                let checkpoint = input.checkpoint();
+                #(#items_decoded)*
+                Ok(#items_set)
+            }
+        }
+    }
+}
+
 /// Generates the tokens for initializing the field variables as optional
 /// 
 /// `let mut #name: Option<#ty> = None;`
-fn gen_items_init(fatts: &Vec<kst::FieldAttrSchema>) -> proc_macro2::TokenStream {
+/// 
+/// Required fields also record the error of their last failed decoding, see [`error_ident`]
+/// 
+/// Also initializes the flags of the fields seen so far, to report duplicate
+/// keys, and when recording the order of keys, the recorded order
+fn gen_items_init(fatts: &Vec<kst::FieldAttrSchema>, record_order: bool) -> proc_macro2::TokenStream {
     let field_initializations = fatts.iter().map(|field| {
-        let kst::FieldAttrSchema { name, ty, .. } = field;
+        let kst::FieldAttrSchema { name, ty, contents, .. } = field;
+        let error = error_ident(name);
+        let error_initialization = match contents.is_required(ty) {
+            true => quote! { let mut #error: Option<::tinyklv::prelude::KlvError> = None; },
+            false => quote! {},
+        };
         let ty = crate::parse::unwrap_option_type(ty).unwrap_or(ty);
-        quote! { let mut #name: Option<#ty> = None; }
+        quote! { let mut #name: Option<#ty> = None; #error_initialization }
     });
-    quote! { #(#field_initializations)* }
+    let order_initialization = match record_order {
+        true => quote! { let mut __tinyklv_order: Vec<usize> = Vec::new(); },
+        false => quote! {},
+    };
+    let num_fields = fatts.len();
+    quote! {
+        #(#field_initializations)*
+        #order_initialization
+        let mut __tinyklv_seen = [false; #num_fields];
+    }
 }
 
 /// Generates the tokens for matching the key/len's with fields and parsers
 /// 
-/// `(#key, #optional_len) => #name = #dec (input #optional_len_arg).ok(),`
-fn gen_items_match(fatts: &Vec<kst::FieldAttrSchema>) -> proc_macro2::TokenStream {
-    let arms = fatts.iter().map(|field| {
-        let name = &field.name;
-        let key = &field.contents.key.value.clone().unwrap_or_else(||
-            panic!("{}", crate::Error::MissingKey(name.to_string()))
+/// `(#key, len) => match decode_value(input, len, #dec #optional_len_arg) { .. }`
+/// 
+/// Values are decoded from a sub-slice of `len`, see [`tinyklv::prelude::decode_value`](https://docs.rs/tinyklv/latest/tinyklv/prelude/fn.decode_value.html).
+/// Errors are placed at the key within the packet, and recorded in the
+/// [`tinyklv::prelude::DecodeReport`](https://docs.rs/tinyklv/latest/tinyklv/prelude/struct.DecodeReport.html),
+/// as are duplicate keys. The error of a required field is also kept, to be
+/// returned if no later occurrence of the key decodes
+/// 
+/// When recording the order of keys, the index of the field is also pushed
+/// to the recorded order
+fn gen_items_match(struct_name: &syn::Ident, fatts: &Vec<kst::FieldAttrSchema>, record_order: bool) -> proc_macro2::TokenStream {
+    let struct_str = struct_name.to_string();
+    let arms = fatts.iter().enumerate().map(|(index, field)| {
+        let kst::FieldAttrSchema { name, ty, contents, .. } = field;
+        let fname = field.member_str();
+        let key = &contents.key.value.clone().unwrap_or_else(||
+            panic!("{}", crate::Error::MissingKey(field.member_str()))
         );
-        let dec = field.contents.dec().clone().unwrap_or_else(||
-            panic!("{}", crate::Error::MissingFunc(format!("field `{}`", name), "value".into(), "dec".into(), "decoder".into()))
+        let key_label = key_label(key);
+        let dec = contents.dec().clone().unwrap_or_else(||
+            panic!("{}", crate::Error::MissingFunc(format!("field `{}`", field.member_str()), "value".into(), "dec".into(), "decoder".into()))
         );
-        let dynlen = field.contents.dynlen();
-        let optional_len = if let Some(true) = dynlen { quote! { len } } else { quote! { _ } };
-        let optional_len_arg = if let Some(true) = dynlen { quote! { (len) } } else { quote! {} };
+        let optional_len_arg = if let Some(true) = contents.dynlen() { quote! { (len) } } else { quote! {} };
+        let order = match record_order {
+            true => quote! { __tinyklv_order.push(#index); },
+            false => quote! {},
+        };
+        let on_error = match contents.is_required(ty) {
+            true => {
+                let error = error_ident(name);
+                quote! { #error = Some(__tinyklv_err.clone()); }
+            },
+            false => quote! {},
+        };
         quote! {
-            (#key, #optional_len) => #name = #dec #optional_len_arg (input).ok(),
+            (#key, len) => {
+                #order
+                if ::core::mem::replace(&mut __tinyklv_seen[#index], true) {
+                    __tinyklv_report.duplicates.push(::tinyklv::prelude::DuplicateKey {
+                        field: #fname,
+                        key: #key_label,
+                        offset: __tinyklv_key_offset,
+                    });
+                }
+                match ::tinyklv::prelude::decode_value(input, len, #dec #optional_len_arg) {
+                    Ok(__tinyklv_value) => #name = Some(__tinyklv_value),
+                    Err(__tinyklv_err) => {
+                        #name = None;
+                        let __tinyklv_err = __tinyklv_err.field(#fname).within(#struct_str, #key_label, __tinyklv_offset);
+                        #on_error
+                        __tinyklv_report.record(__tinyklv_err);
+                    },
+                }
+            },
         }
     });
     quote! {
@@ -257,27 +762,50 @@ fn gen_items_match(fatts: &Vec<kst::FieldAttrSchema>) -> proc_macro2::TokenStrea
     }
 }
 
+/// Returns the identifier of the variable recording the error of a required field
+fn error_ident(name: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("__tinyklv_error_{}", name)
+}
+
+/// Returns the label of a key in the path of a [`tinyklv::prelude::KlvError`](https://docs.rs/tinyklv/latest/tinyklv/prelude/struct.KlvError.html)
+/// 
+/// `tag 48` for integer keys, otherwise `key ..`
+fn key_label(key: &syn::Lit) -> String {
+    match key {
+        syn::Lit::Int(x) => match x.base10_parse::<u128>() {
+            Ok(value) => format!("tag {}", value),
+            Err(_) => format!("tag {}", x),
+        },
+        syn::Lit::Str(x) => format!("key {}", x.value()),
+        x => format!("key {}", crate::parse::compact_tokens(x)),
+    }
+}
+
 /// Generates the tokens for setting the field variables upon returning of the output struct
 /// 
-/// `Ok(#struct_name { #(#field_set_on_return)* })`
-fn gen_item_set(struct_name: &syn::Ident, fatts: &Vec<kst::FieldAttrSchema>, elem_name_type_without_klv: Vec<(syn::Ident, syn::Type)>) -> proc_macro2::TokenStream {
+/// `#struct_name { #(#field_set_on_return)* }`
+fn gen_item_set(struct_name: &syn::Ident, fatts: &Vec<kst::FieldAttrSchema>, elem_name_type_without_klv: Vec<(syn::Member, syn::Type)>, order_field: Option<&syn::Member>) -> proc_macro2::TokenStream {
+    let struct_str = struct_name.to_string();
     let field_set_on_return = fatts.iter().map(|field| {
-        let kst::FieldAttrSchema { name, ty, .. } = field;
-        match crate::parse::is_option(ty) {
-            false => quote! {
-                #name: #name.ok_or_else(|| {
-                    ::tinyklv::reexport::winnow::error::ErrMode::Backtrack(
-                        ::tinyklv::reexport::winnow::error::ContextError::new().add_context(
-                            input,
-                            &checkpoint,
-                            ::tinyklv::reexport::winnow::error::StrContext::Label(
-                                concat!("`", stringify!(#name), "` is a required value missing from the `", stringify!(#struct_name), "` packet. To prevent this, set this field as optional.")
-                            )
-                        )
-                    )
-                })?,
-            },
-            true => quote! { #name, },
+        let kst::FieldAttrSchema { name, member, ty, contents } = field;
+        let fname = field.member_str();
+        let key_label = contents.key.get().map(key_label).unwrap_or_default();
+        let error = error_ident(name);
+        let missing = quote! {
+            #error
+                .unwrap_or_else(|| ::tinyklv::prelude::KlvError::new(::tinyklv::prelude::KlvErrorKind::MissingField)
+                    .field(#fname)
+                    .within(#struct_str, #key_label, 0)
+                )
+                .into_winnow(input)
+        };
+        match (crate::parse::is_option(ty), contents.default.get(), contents.is_required(ty)) {
+            (false, _, true) => quote! { #member: #name.ok_or_else(|| { #missing })?, },
+            (false, Some(default), false) => quote! { #member: #name.unwrap_or_else(|| #default), },
+            (false, None, false) => quote! { #member: #name.unwrap_or_default(), },
+            (true, _, true) => quote! { #member: Some(#name.ok_or_else(|| { #missing })?), },
+            (true, Some(default), false) => quote! { #member: #name.or_else(|| Some(#default)), },
+            (true, None, false) => quote! { #member: #name, },
         }
     });
     // --------------------------------------------------
@@ -286,17 +814,19 @@ fn gen_item_set(struct_name: &syn::Ident, fatts: &Vec<kst::FieldAttrSchema>, ele
     // --------------------------------------------------
     // if the default does not exist, then this will not compile
     // --------------------------------------------------
+    let order_set_on_return = order_field.map(|member| quote! { #member: __tinyklv_order, });
     match elem_name_type_without_klv.len() != 0 {
-        false => quote! { Ok(#struct_name { #(#field_set_on_return)* }) },
+        false => quote! { #struct_name { #(#field_set_on_return)* #order_set_on_return } },
         true => {
             let names: Vec<_> = elem_name_type_without_klv.iter().map(|(name, _)| name.clone()).collect();
             let types: Vec<_> = elem_name_type_without_klv.iter().map(|(_, ty)| crate::parse::type2fish(ty)).collect();
-            let individual_defaults = quote! { #(#names: #types::default())*, };
+            let individual_defaults = quote! { #(#names: #types::default(),)* };
             quote! {
-                Ok(#struct_name {
+                #struct_name {
                     #(#field_set_on_return)* 
+                    #order_set_on_return
                     #individual_defaults
-                })
+                }
             }
         },
     }
@@ -24,18 +24,23 @@ use crate::kst::xcoder::{
 /// Field Attributes
 /// 
 /// See the [`FieldNames`] enum for the different attribute names.
+/// 
+/// * `name` - The identifier used for the field's local variable when decoding.
+/// For named fields, this is the field name. For tuple fields, this is `field_<index>`.
+/// * `member` - The field accessor, e.g. `self.<member>`, which is either the
+/// field name or the field index for tuple structs
 pub(crate) struct FieldAttrSchema {
     pub name: syn::Ident,
+    pub member: syn::Member,
     pub ty: syn::Type,
     pub contents: FieldAttrContents,
 }
 /// [`FieldAttrSchema`] implementation
 impl FieldAttrSchema {
-    pub fn from_field(input: &syn::Field) -> Option<Self> {
-        // --------------------------------------------------
-        // can now safely unwrap
-        // --------------------------------------------------
-        if let None = input.ident { return None }
+    /// Parses the `#[klv(..)]` attribute of the field at `index` of a struct
+    /// 
+    /// Returns [`None`] if the field has no `#[klv(..)]` attribute
+    pub fn from_field(index: usize, input: &syn::Field) -> Option<Self> {
         // --------------------------------------------------
         // parse as `symple::MetaTuple`
         // --------------------------------------------------
@@ -49,19 +54,48 @@ impl FieldAttrSchema {
             .next()
             .map(|attr| MetaTuple::from(format!("{}{}", ATTR, attr.tokens.to_string())));
         match parsed {
-            Some(parsed) => Some(FieldAttrSchema {
-                name: input.ident.clone().unwrap(),
-                ty: input.ty.clone(),
-                contents: parsed.into(),
-            }),
+            Some(parsed) => {
+                let mut output = Self::from_field_without_attr(index, input);
+                output.contents = parsed.into();
+                Some(output)
+            },
             None => None,
         }
     }
+
+    /// Creates the schema of the field at `index` of a struct, ignoring any
+    /// `#[klv(..)]` attribute
+    /// 
+    /// Used for `#[klv(transparent)]` structs, where the single field does not
+    /// require a `#[klv(..)]` attribute
+    pub fn from_field_without_attr(index: usize, input: &syn::Field) -> Self {
+        let (name, member) = match &input.ident {
+            Some(ident) => (ident.clone(), syn::Member::Named(ident.clone())),
+            None => (
+                quote::format_ident!("field_{}", index),
+                syn::Member::Unnamed(syn::Index::from(index)),
+            ),
+        };
+        FieldAttrSchema {
+            name,
+            member,
+            ty: input.ty.clone(),
+            contents: FieldAttrContents::default(),
+        }
+    }
+
+    /// Returns the field name, or the field index for tuple structs, as a string
+    pub fn member_str(&self) -> String {
+        match &self.member {
+            syn::Member::Named(ident) => ident.to_string(),
+            syn::Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
 }
 /// [`FieldAttrSchema`] implementation of [`std::fmt::Display`]
 impl std::fmt::Display for FieldAttrSchema {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "name: {}, contents: {}", self.name, self.contents)
+        write!(f, "name: {}, contents: {}", self.member_str(), self.contents)
     }
 }
 // symple::debug_from_display!(FieldAttrSchema);
@@ -71,6 +105,21 @@ tinyklv_common::debug_from_display!(FieldAttrSchema);
 pub(crate) struct FieldAttrContents {
     pub key: NameValue<syn::Lit>,
     pub xcoder: Tuple<ValueXcoder>,
+    pub name: NameValue<syn::Lit>,
+    pub units: NameValue<syn::Lit>,
+    pub default: NameValue<syn::Expr>,
+    pub required: Option<bool>,
+    pub skip_if: Option<crate::kst::xcoder::PathLike>,
+    pub order: Option<EncodeOrder>,
+    pub record_order: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Position of a field when encoding, see [`FieldNames::Order`]
+pub(crate) enum EncodeOrder {
+    First,
+    Nth(u64),
+    Last,
 }
 /// [`FieldAttrContents`] implementation
 impl FieldAttrContents {
@@ -154,6 +203,30 @@ impl FieldAttrContents {
     pub fn dynlen(&self) -> Option<bool> {
         self.xcoder.get().map_or(None, |x| x.dynlen)
     }
+
+    /// Returns whether the field must be present in a packet for decoding
+    /// to succeed
+    /// 
+    /// Unless explicitly set, [`Option`] fields and fields with a `default`
+    /// are not required.
+    pub fn is_required(&self, ty: &syn::Type) -> bool {
+        match self.required {
+            Some(required) => required,
+            None => !crate::parse::is_option(ty) && self.default.get().is_none(),
+        }
+    }
+
+    /// Returns the sort key of the field when encoding, see [`FieldNames::Order`]
+    /// 
+    /// Sorting must be stable, to keep ties in declaration order
+    pub fn encode_rank(&self) -> (u8, u64) {
+        match self.order {
+            Some(EncodeOrder::First) => (0, 0),
+            Some(EncodeOrder::Nth(n)) => (1, n),
+            None => (2, 0),
+            Some(EncodeOrder::Last) => (3, 0),
+        }
+    }
 }
 /// [`FieldAttrContents`] implementation of [`From`] for [`MetaTuple`]
 impl From<MetaTuple> for FieldAttrContents {
@@ -163,12 +236,25 @@ impl From<MetaTuple> for FieldAttrContents {
         let oxcoder = ValueXcoder::from(symple::MetaContents::from(input.clone()));
         input
             .into_iter()
-            .for_each(|item| if let MetaItem::NameValue(x) = item.clone() {
-                match FieldNames::try_from(x.name.to_string().as_str()) {
+            .for_each(|item| match item.clone() {
+                MetaItem::Value(x) => match FieldNames::try_from(x.to_string().as_str()) {
+                    Ok(FieldNames::First) => output.order = Some(EncodeOrder::First),
+                    Ok(FieldNames::Last) => output.order = Some(EncodeOrder::Last),
+                    Ok(FieldNames::RecordOrder) => output.record_order = true,
+                    _ => (),
+                },
+                MetaItem::NameValue(x) => match FieldNames::try_from(x.name.to_string().as_str()) {
                     Ok(FieldNames::Key) => output.key = x.into(),
+                    Ok(FieldNames::Name) => output.name = x.into(),
+                    Ok(FieldNames::Units) => output.units = x.into(),
+                    Ok(FieldNames::Default) => output.default = x.into(),
+                    Ok(FieldNames::Required) => output.required = if let symple::MetaValue::Lit(syn::Lit::Bool(syn::LitBool { value: v, .. })) = x.value { Some(v) } else { None },
+                    Ok(FieldNames::SkipIf) => output.skip_if = Some(x.value.into()),
                     Ok(FieldNames::DynLen) => dynlen = if let symple::MetaValue::Lit(syn::Lit::Bool(syn::LitBool { value: v, .. })) = x.value { Some(v) } else { None },
+                    Ok(FieldNames::Order) => output.order = if let symple::MetaValue::Lit(syn::Lit::Int(n)) = x.value { n.base10_parse().ok().map(EncodeOrder::Nth) } else { None },
                     _ => (),
-                }
+                },
+                MetaItem::Tuple(_) => (),
             });
         if let Some(dynlen) = dynlen { output.set_dynlen(dynlen) }
         if let Some(enc) = oxcoder.enc { output.set_enc(enc) }
@@ -179,9 +265,16 @@ impl From<MetaTuple> for FieldAttrContents {
 /// [`FieldAttrContents`] implementation of [`std::fmt::Display`]
 impl std::fmt::Display for FieldAttrContents {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "key: {}, {}",
+        write!(f, "key: {}, {}, name: {}, units: {}, default: {}, required: {:?}, skip_if: {}, order: {:?}, record_order: {}",
             self.key.value.to_token_stream().to_string(),
             self.xcoder,
+            self.name,
+            self.units,
+            self.default,
+            self.required,
+            self.skip_if.to_token_stream().to_string(),
+            self.order,
+            self.record_order,
         )
     }
 }
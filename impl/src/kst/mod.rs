@@ -3,18 +3,26 @@
 // --------------------------------------------------
 pub(crate) use crate::kst::{
     field::FieldAttrSchema,
-    strct::StructAttrSchema,
+    strct::{StructAttrSchema, Form},
 };
+use tinyklv_common::symple::prelude::*;
+use crate::kst::xcoder::PathLike;
 pub(crate) mod field;
 pub(crate) mod strct;
 pub(crate) mod xcoder;
 
+/// Prefix of a sentinel which is a universal label, see [`Input::check_sentinel`]
+const UL_URN: &str = "urn:smpte:ul:";
+
 /// [`Input`] of the [`crate::Klv`] derive macro
 pub(crate) struct Input {
     pub input: syn::DeriveInput,
     pub name: syn::Ident,
     pub sattr: StructAttrSchema,
     pub fattrs: Vec<FieldAttrSchema>,
+    pub order_field: Option<syn::Member>,
+    pub form: Form,
+    pub sentinel_ignores_version: bool,
 }
 
 /// [`Input`] implementation of [`From`] for [`syn::DeriveInput`]
@@ -38,10 +46,142 @@ impl Input {
             syn::Data::Struct(syn::DataStruct { fields, .. }) => fields,
             _ => return Err(crate::Error::DeriveForNonStruct(crate::NAME.into(), name.to_string())),
         };
-        let fattrs = fields
-            .iter()
-            .filter_map(|field| FieldAttrSchema::from_field(field))
-            .collect::<Vec<_>>();
-        Ok(Self { input: input.clone(), name, sattr, fattrs })
+        let fattrs = match sattr.transparent {
+            // --------------------------------------------------
+            // transparent: the single field is always used
+            // --------------------------------------------------
+            true => match fields.len() {
+                1 => fields
+                    .iter()
+                    .map(|field| FieldAttrSchema::from_field(0, field).unwrap_or_else(|| FieldAttrSchema::from_field_without_attr(0, field)))
+                    .collect::<Vec<_>>(),
+                n => return Err(crate::Error::TransparentFieldCount(name.to_string(), n)),
+            },
+            false => fields
+                .iter()
+                .enumerate()
+                .filter_map(|(index, field)| FieldAttrSchema::from_field(index, field))
+                .collect::<Vec<_>>(),
+        };
+        // --------------------------------------------------
+        // the `record_order` field is not a klv field
+        // --------------------------------------------------
+        let (order_fields, fattrs): (Vec<_>, Vec<_>) = fattrs
+            .into_iter()
+            .partition(|field| field.contents.record_order);
+        let order_field = match order_fields.len() {
+            0 | 1 => order_fields.into_iter().next().map(|field| field.member),
+            _ => return Err(crate::Error::MultipleRecordOrder(name.to_string())),
+        };
+        let mut output = Self { input: input.clone(), name, sattr, fattrs, order_field, form: Form::default(), sentinel_ignores_version: false };
+        output.form = output.check_form()?;
+        output.sentinel_ignores_version = output.check_sentinel()?;
+        output.check_literals()?;
+        Ok(output)
+    }
+
+    /// Returns the coding form of the struct, checking that the struct and its
+    /// fields are supported by it
+    /// 
+    /// Also sets the default key / length xcoders of universal sets
+    fn check_form(&mut self) -> Result<Form, crate::Error> {
+        let name = self.name.to_string();
+        let form = match self.sattr.form.get().map(|x| crate::parse::compact_tokens(x)) {
+            Some(x) => Form::try_from(x.as_str()).map_err(|_| crate::Error::InvalidForm(name.clone(), x))?,
+            None => Form::default(),
+        };
+        let unsupported = |what: &str| crate::Error::FormUnsupported(name.clone(), form.to_string(), what.into());
+        if form.is_pack() {
+            let stream = self.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+            if crate::parse::is_str_stream(&stream) {
+                return Err(unsupported("text streams"))
+            }
+            if self.order_field.is_some() {
+                return Err(unsupported("`record_order`"))
+            }
+            if self.fattrs.iter().any(|f| f.contents.skip_if.is_some()) {
+                return Err(unsupported("`skip_if`"))
+            }
+            // --------------------------------------------------
+            // an omitted item would shift the items after it: a
+            // `default` or `required = false` one may be missing
+            // when decoding, and an `Option` one when encoding
+            // --------------------------------------------------
+            let is_optional = |f: &&FieldAttrSchema| !f.contents.is_required(&f.ty) || crate::parse::is_option(&f.ty);
+            let trailing = self.fattrs.iter().rev().take_while(is_optional).count();
+            if let Some(f) = self.fattrs[..self.fattrs.len() - trailing].iter().find(is_optional) {
+                return Err(unsupported(&format!("optional fields before required ones (`{}`)", f.member_str())))
+            }
+        }
+        match form {
+            Form::DefinedPack if self.fattrs.iter().any(|f| f.contents.dynlen() == Some(true)) => {
+                return Err(unsupported("fields of dynamic length (`dyn = true`)"))
+            },
+            Form::UniversalSet => {
+                for f in self.fattrs.iter() {
+                    match f.contents.key.get() {
+                        Some(syn::Lit::ByteStr(x)) if x.value().len() == 16 => (),
+                        _ => return Err(crate::Error::UniversalSetKey(f.member_str())),
+                    }
+                }
+                let key = self.sattr.key.value.get_or_insert_with(Default::default);
+                key.xcoder.enc.get_or_insert_with(|| PathLike::Path(syn::parse_quote!(::tinyklv::codecs::binary::enc::universal_key)));
+                key.xcoder.dec.get_or_insert_with(|| PathLike::Path(syn::parse_quote!(::tinyklv::codecs::binary::dec::universal_key)));
+                let len = self.sattr.len.value.get_or_insert_with(Default::default);
+                len.xcoder.enc.get_or_insert_with(|| PathLike::Path(syn::parse_quote!(::tinyklv::codecs::ber::enc::ber_length_from_usize)));
+                len.xcoder.dec.get_or_insert_with(|| PathLike::Path(syn::parse_quote!(::tinyklv::codecs::ber::dec::ber_length)));
+            },
+            _ => (),
+        }
+        Ok(form)
     }
-}
\ No newline at end of file
+
+    /// Returns `true` if the sentinel of a byte stream is a universal label
+    /// URN, e.g. `"urn:smpte:ul:060e2b34..."`, to be recognized regardless of
+    /// its version byte
+    /// 
+    /// Only the `urn:smpte:ul:` prefix opts in, so that other strings of hex
+    /// digits and dots (e.g. `"12.34"`) remain plain sentinels. The sentinel
+    /// is replaced by the byte string of the label
+    fn check_sentinel(&mut self) -> Result<bool, crate::Error> {
+        let stream = self.sattr.stream.value.clone().unwrap_or(crate::parse::u8_slice());
+        if crate::parse::is_str_stream(&stream) {
+            return Ok(false)
+        }
+        let sentinel = match self.sattr.sentinel.as_mut() {
+            Some(sentinel) => sentinel,
+            None => return Ok(false),
+        };
+        let label = match sentinel.get() {
+            Some(syn::Lit::Str(x)) if x.value().starts_with(UL_URN) => x.clone(),
+            _ => return Ok(false),
+        };
+        let hex = &label.value()[UL_URN.len()..];
+        let digits: Vec<u8> = hex.chars().filter_map(|c| c.to_digit(16)).map(|d| d as u8).collect();
+        if digits.len() != 32 || !hex.chars().all(|c| c == '.' || c.is_ascii_hexdigit()) {
+            return Err(crate::Error::InvalidUniversalLabel(self.name.to_string(), label.value()))
+        }
+        let bytes: Vec<u8> = digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect();
+        sentinel.value = Some(syn::Lit::ByteStr(syn::LitByteStr::new(&bytes, label.span())));
+        Ok(true)
+    }
+
+    /// Returns an error if the sentinel, or the key of a field, has no bytes
+    /// to be reported in the [`tinyklv::prelude::KlvSchema`](https://docs.rs/tinyklv/latest/tinyklv/prelude/trait.KlvSchema.html),
+    /// see [`crate::parse::lit_bytes`]
+    fn check_literals(&self) -> Result<(), crate::Error> {
+        if let Some(sentinel) = self.sattr.sentinel.as_ref().and_then(|x| x.get()) {
+            if crate::parse::lit_bytes(sentinel).is_none() {
+                return Err(crate::Error::UnrepresentableSentinel(self.name.to_string(), crate::parse::compact_tokens(sentinel)))
+            }
+        }
+        for f in self.fattrs.iter() {
+            if let Some(key) = f.contents.key.get() {
+                if crate::parse::lit_bytes(key).is_none() {
+                    return Err(crate::Error::UnrepresentableKey(f.member_str(), crate::parse::compact_tokens(key)))
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -32,6 +32,8 @@ pub(crate) struct StructAttrSchema {
     pub defaults: HashSet<Tuple<DefaultXcoder>>,
     pub allow_unimplemented_decode: bool,
     pub allow_unimplemented_encode: bool,
+    pub transparent: bool,
+    pub form: NameValue<syn::Path>,
 }
 /// [`StructAttrSchema`] implementation
 impl StructAttrSchema {
@@ -67,23 +69,24 @@ impl From<MetaTuple> for StructAttrSchema {
                 MetaItem::NameValue(x) => match StructNames::try_from(x.name.to_string().as_str()) {
                     Ok(StructNames::Stream) => output.stream = x.into(),
                     Ok(StructNames::Sentinel) => output.sentinel = Some(x.into()),
+                    Ok(StructNames::Form) => output.form = x.into(),
                     _ => (),
                 },
                 MetaItem::Value(x) => match StructNames::try_from(x.to_string().as_str()) {
                     Ok(StructNames::AllowUnimplementedDecode) => output.allow_unimplemented_decode = true,
                     Ok(StructNames::AllowUnimplementedEncode) => output.allow_unimplemented_encode = true,
+                    Ok(StructNames::Transparent) => output.transparent = true,
                     _ => (),
                 },
             }
         );
-        println!("{:#?}", output);
         output
     }
 }
 /// [`StructAttrSchema`] implementation of [`std::fmt::Display`]
 impl std::fmt::Display for StructAttrSchema {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "StructAttrSchema {{ stream: {}, sentinel: {}, key: {}, len: {}, defaults: {:#?}, allow_unimplemented_decode: {}, allow_unimplemented_encode: {} }}",
+        write!(f, "StructAttrSchema {{ stream: {}, sentinel: {}, key: {}, len: {}, defaults: {:#?}, allow_unimplemented_decode: {}, allow_unimplemented_encode: {}, transparent: {}, form: {} }}",
             self.stream.get().to_token_stream().to_string(),
             self.sentinel.clone().map_or("None".to_string(), |v| v.get().to_token_stream().to_string()),
             self.key,
@@ -91,8 +94,51 @@ impl std::fmt::Display for StructAttrSchema {
             self.defaults,
             self.allow_unimplemented_decode,
             self.allow_unimplemented_encode,
+            self.transparent,
+            self.form,
         )
     }
 }
 // symple::debug_from_display!(StructAttrSchema);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// SMPTE 336M coding form of a packet, see [`StructNames::Form`]
+pub(crate) enum Form {
+    #[default]
+    LocalSet,
+    UniversalSet,
+    DefinedPack,
+    VariablePack,
+}
+/// [`Form`] implementation
+impl Form {
+    /// Returns `true` for packs, where items have no keys
+    pub fn is_pack(&self) -> bool {
+        matches!(self, Form::DefinedPack | Form::VariablePack)
+    }
+}
+/// [`Form`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for Form {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Form::LocalSet => write!(f, "local_set"),
+            Form::UniversalSet => write!(f, "universal_set"),
+            Form::DefinedPack => write!(f, "defined_pack"),
+            Form::VariablePack => write!(f, "variable_pack"),
+        }
+    }
+}
+/// [`Form`] implementation of [`TryFrom`] for [`&str`]
+impl TryFrom<&str> for Form {
+    type Error = ();
+    fn try_from(x: &str) -> Result<Self, Self::Error> {
+        match x {
+            "local_set" => Ok(Form::LocalSet),
+            "universal_set" => Ok(Form::UniversalSet),
+            "defined_pack" => Ok(Form::DefinedPack),
+            "variable_pack" => Ok(Form::VariablePack),
+            _ => Err(()),
+        }
+    }
+}
 tinyklv_common::debug_from_display!(StructAttrSchema);
\ No newline at end of file
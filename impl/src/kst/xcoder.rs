@@ -9,7 +9,7 @@ use tinyklv_common::symple::{
 };
 use quote::ToTokens;
 
-#[derive(Eq, Hash, Clone, PartialEq)]
+#[derive(Eq, Hash, Clone, Default, PartialEq)]
 /// [`OptionalXcoder`]
 /// 
 /// This is an encoder/decoder pair where **either** is optional.
@@ -60,7 +60,7 @@ impl std::fmt::Display for OptionalXcoder {
 }
 // symple::debug_from_display!(OptionalXcoder);
 tinyklv_common::debug_from_display!(OptionalXcoder);
-#[derive(Clone)]
+#[derive(Clone, Default)]
 /// [`KeyLenXcoder`]
 /// 
 /// An encoder/decoder pair for keys/lengths
@@ -13,6 +13,8 @@ use thiserror::Error;
 mod kst;
 mod parse;
 mod expand;
+mod bits;
+mod enumeration;
 
 #[derive(Error, Debug)]
 enum Error {
@@ -31,7 +33,41 @@ enum Error {
     #[error("Missing required key for field `{0}`: `#[key = ?]`.")]
     MissingKey(String),
     #[error("Unable to parse path-like type for enc/dec.")]
-    XcoderIsNotPathLike
+    XcoderIsNotPathLike,
+    #[error("`#[klv(transparent)]` struct `{0}` must have exactly one field, got {1}.")]
+    TransparentFieldCount(String, usize),
+    #[error("Struct `{0}` can only have one `#[klv(record_order)]` field.")]
+    MultipleRecordOrder(String),
+    #[error("Invalid bit range for field `{0}`: expected `#[bits(N)]`, `#[bits(A..B)]` or `#[bits(A..=B)]` within the first 128 bits.")]
+    BitsInvalidRange(String),
+    #[error("Invalid struct attribute for `{0}`: expected `#[bits(bytes = N)]`.")]
+    BitsInvalidAttr(String),
+    #[error("Bit ranges of fields `{0}` and `{1}` overlap.")]
+    BitsOverlap(String, String),
+    #[error("Struct `{0}` is {1} byte(s) wide, which must be within 1..=16 and hold all {2} bit(s) in use.")]
+    BitsWidth(String, u32, u32),
+    #[error("`{0}` can only be derived for enums, got `{1}`.")]
+    DeriveForNonEnum(String, String),
+    #[error("Unsupported representation for enum `{0}`: `#[repr({1})]`, expected one of u8, u16, u32, u64, i8, i16, i32 or i64.")]
+    EnumInvalidRepr(String, String),
+    #[error("Invalid attribute for variant `{0}`: expected `#[klv(value = ?)]`.")]
+    EnumInvalidAttr(String),
+    #[error("Missing value for variant `{0}`: `#[klv(value = ?)]` or an explicit discriminant is required.")]
+    EnumMissingValue(String),
+    #[error("Invalid form for struct `{0}`: `{1}`, expected one of local_set, universal_set, defined_pack or variable_pack.")]
+    InvalidForm(String, String),
+    #[error("`#[klv(form = {1})]` struct `{0}` does not support {2}.")]
+    FormUnsupported(String, String, String),
+    #[error("Key of field `{0}` in a universal set must be a 16-byte byte string, e.g. `b\"\\x06\\x0E\\x2B\\x34...\"`.")]
+    UniversalSetKey(String),
+    #[error("Key of field `{0}` can not be represented as bytes: `{1}`, expected an integer, byte, char, string or byte string literal.")]
+    UnrepresentableKey(String, String),
+    #[error("Sentinel of struct `{0}` can not be represented as bytes: `{1}`, expected an integer, byte, char, string or byte string literal.")]
+    UnrepresentableSentinel(String, String),
+    #[error("Invalid universal label sentinel for struct `{0}`: `{1}`, expected 16 bytes in hex after `urn:smpte:ul:`, e.g. `\"urn:smpte:ul:060e2b34.020b0101.0e010301.01000000\"`.")]
+    InvalidUniversalLabel(String, String),
+    #[error("Invalid variant `{1}` of enum `{0}`: expected unit variants and at most one single-field fallback variant, e.g. `Unknown(u8)`.")]
+    EnumInvalidVariant(String, String),
 }
 
 const NAME: &str = "Klv";
@@ -40,4 +76,14 @@ const ATTR: &str = "klv";
 pub fn klv(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     expand::derive(&input)
-}
\ No newline at end of file
+}
+#[proc_macro_derive(KlvBits, attributes(bits))]
+pub fn klv_bits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    bits::derive(&input)
+}
+#[proc_macro_derive(KlvEnum, attributes(klv))]
+pub fn klv_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    enumeration::derive(&input)
+}
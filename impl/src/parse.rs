@@ -35,6 +35,18 @@ fn is_option_helper(ty: &syn::Type) -> (bool, Option<&syn::Type>) {
     (false, None)
 }
 
+/// Determines if a stream type is a text stream, `&str`, which encodes to [`String`]
+/// instead of [`Vec<u8>`]
+pub(crate) fn is_str_stream(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(syn::TypeReference { elem, .. }) => matches!(
+            elem.as_ref(),
+            syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("str")
+        ),
+        _ => false,
+    }
+}
+
 /// Inserts a lifetime into a type
 pub(crate) fn insert_lifetime(ty: &syn::Type, lifetime_char: char) -> syn::Type {
     let lifetime = syn::Lifetime::new(&format!("'{lifetime_char}"), proc_macro2::Span::call_site());
@@ -70,49 +82,25 @@ pub(crate) fn u8_slice() -> syn::Type {
     })
 }
 
-/// Returns the element names + types of a struct without the `#[klv(..)]` attribute
-pub(crate) fn elems_without_klv_attr(input: &syn::DeriveInput) -> Vec<(syn::Ident, syn::Type)> {
-    // --------------------------------------------------
-    // extract all field names
-    // --------------------------------------------------
-    let all_fields: Vec<(syn::Ident, syn::Type)> = match &input.data {
-        syn::Data::Struct(data_struct) => {
-            match data_struct.fields {
-                syn::Fields::Named(ref fields) => fields.named.iter()
-                    .map(|f| (
-                        f.ident.as_ref().unwrap().clone(),
-                        f.ty.clone(),
-                    ))
-                    .collect(),
-                syn::Fields::Unnamed(_) => Vec::new(),
-                syn::Fields::Unit => Vec::new(),
-            }
-        }
-        _ => Vec::new(),
-    };
-    // --------------------------------------------------
-    // extract all field names with klv attr
-    // --------------------------------------------------
-    let attr_fields: Vec<syn::Ident> = match &input.data {
-        syn::Data::Struct(data_struct) => {
-            match data_struct.fields {
-                syn::Fields::Named(ref fields) => fields.named.iter()
-                    .filter(|f| f.attrs.iter().any(|attr| attr.path.is_ident(crate::ATTR)))
-                    .map(|f| f.ident.as_ref().unwrap().clone())
-                    .collect(),
-                syn::Fields::Unnamed(_) => Vec::new(),
-                syn::Fields::Unit => Vec::new(),
-            }
-        }
-        _ => Vec::new(),
+/// Returns the element members + types of a struct without the `#[klv(..)]` attribute
+/// 
+/// Members are the field names, or the field indices for tuple structs
+pub(crate) fn elems_without_klv_attr(input: &syn::DeriveInput) -> Vec<(syn::Member, syn::Type)> {
+    let fields = match &input.data {
+        syn::Data::Struct(data_struct) => &data_struct.fields,
+        _ => return Vec::new(),
     };
-    // --------------------------------------------------
-    // return non-attr fields by finding the difference
-    // --------------------------------------------------
-    all_fields
+    fields
         .iter()
-        .filter(|(name, _)| !attr_fields.contains(name))
-        .cloned()
+        .enumerate()
+        .filter(|(_, f)| !f.attrs.iter().any(|attr| attr.path.is_ident(crate::ATTR)))
+        .map(|(index, f)| (
+            match &f.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(index)),
+            },
+            f.ty.clone(),
+        ))
         .collect()
 }
 
@@ -187,4 +175,61 @@ pub(crate) fn type2fish(ty: &syn::Type) -> proc_macro2::TokenStream {
         // extend this match to handle other [`syn::Type`] variants as needed
         _ => quote::quote!(#ty),
     }
-}
\ No newline at end of file
+}
+/// Converts tokens to a compact, human-readable [`String`]
+/// 
+/// Whitespace is only kept between two word-like characters, so that
+/// the output reads as the source would have been written:
+/// 
+/// * `Option < Vec < u8 > >` -> `Option<Vec<u8>>`
+/// * `tinyklv :: dec :: binary :: u8` -> `tinyklv::dec::binary::u8`
+/// * `& 'a mut str` -> `&'a mut str`
+pub(crate) fn compact_tokens(tokens: &impl quote::ToTokens) -> String {
+    let raw = tokens.to_token_stream().to_string();
+    let chars: Vec<char> = raw.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let mut output = String::with_capacity(raw.len());
+    for (i, c) in chars.iter().enumerate() {
+        if *c != ' ' {
+            output.push(*c);
+            if *c == ',' { output.push(' '); }
+            continue;
+        }
+        let prev = i.checked_sub(1).and_then(|i| chars.get(i));
+        let next = chars.get(i + 1);
+        if prev.is_some_and(is_word) && next.is_some_and(is_word) { output.push(' '); }
+    }
+    output.trim_end().to_string()
+}
+
+/// Converts a literal into the bytes it represents, as written
+/// 
+/// * Byte strings / strings: their contents, e.g. `b"\x06\x0E"` -> `[0x06, 0x0E]`
+/// * Bytes / chars: their value, e.g. `b'A'` -> `[0x41]`
+/// * Integers: the minimal big-endian representation, or the width of their
+///   suffix if one is given, e.g. `0x0102` -> `[0x01, 0x02]`, `0x01u16` -> `[0x00, 0x01]`
+/// 
+/// Returns [`None`] for any other literal, which is rejected by the derive
+/// macro, see [`crate::kst::Input`]
+pub(crate) fn lit_bytes(lit: &syn::Lit) -> Option<Vec<u8>> {
+    match lit {
+        syn::Lit::ByteStr(x) => Some(x.value()),
+        syn::Lit::Str(x) => Some(x.value().into_bytes()),
+        syn::Lit::Byte(x) => Some(vec![x.value()]),
+        syn::Lit::Char(x) => Some(x.value().to_string().into_bytes()),
+        syn::Lit::Int(x) => {
+            let value = x.base10_parse::<u128>().ok()?;
+            let bytes = value.to_be_bytes();
+            let width = match x.suffix() {
+                "u8" | "i8" => 1,
+                "u16" | "i16" => 2,
+                "u32" | "i32" => 4,
+                "u64" | "i64" => 8,
+                "u128" | "i128" => 16,
+                _ => bytes.iter().skip_while(|b| **b == 0).count().max(1),
+            };
+            Some(bytes[bytes.len() - width..].to_vec())
+        },
+        _ => None,
+    }
+}
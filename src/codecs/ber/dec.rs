@@ -5,10 +5,34 @@
 use crate::prelude::*;
 
 /// See [`crate::codecs::ber::BerLength::decode`]
+/// 
+/// Fails with [`super::BerLengthError::Overflow`] if the length does not fit in a [`usize`]
 pub fn ber_length(input: &mut &[u8]) -> winnow::PResult<usize> {
-    super::BerLength::<u128>::decode
+    ber_length_with(input, &super::BerLengthConfig::new())
+}
+
+/// See [`crate::codecs::ber::BerLength::decode_with`], in DER-strict mode
+pub fn ber_length_der(input: &mut &[u8]) -> winnow::PResult<usize> {
+    ber_length_with(input, &super::BerLengthConfig::new().der())
+}
+
+/// See [`crate::codecs::ber::BerLength::decode_with`], with a maximum length of `MAX`
+/// 
+/// # Example
+/// 
+/// ```
+/// let input: &[u8] = &[0x82, 0x10, 0x00];
+/// assert_eq!(tinyklv::dec::ber::ber_length_max::<4096>(&mut &input[..]).unwrap(), 4096);
+/// assert!(tinyklv::dec::ber::ber_length_max::<4095>(&mut &input[..]).is_err());
+/// ```
+pub fn ber_length_max<const MAX: usize>(input: &mut &[u8]) -> winnow::PResult<usize> {
+    ber_length_with(input, &super::BerLengthConfig::new().max(MAX as u128))
+}
+
+/// See [`crate::codecs::ber::BerLength::decode_with`]
+pub fn ber_length_with(input: &mut &[u8], config: &super::BerLengthConfig) -> winnow::PResult<usize> {
+    super::BerLength::<usize>::decode_with(input, config)
         .map(|value| value.as_u128() as usize)
-        .parse_next(input)
 }
 
 /// See [`crate::codecs::ber::BerOid::decode`]
@@ -12,4 +12,19 @@ pub fn ber_length<T: super::OfBerLength>(input: &T) -> Vec<u8> {
 /// See [`crate::codecs::ber::BerOid::encode_value`]
 pub fn ber_oid<T: super::OfBerOid>(input: &T) -> Vec<u8> {
     super::BerOid::<T>::encode_value(input)
-}
\ No newline at end of file
+}
+#[inline(always)]
+/// Encodes a length as a [`crate::codecs::ber::BerLength`], for use as a
+/// length encoder
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::codecs::ber::enc::ber_length_from_usize;
+/// 
+/// assert_eq!(ber_length_from_usize(4), vec![0x04]);
+/// assert_eq!(ber_length_from_usize(201), vec![0x81, 0xC9]);
+/// ```
+pub fn ber_length_from_usize(len: usize) -> Vec<u8> {
+    ber_length(&len)
+}
@@ -11,6 +11,8 @@ use num_traits::{
 use winnow::error::{
     Needed,
     ErrMode,
+    ErrorKind,
+    FromExternalError,
 };
 use winnow::token::{
     take,
@@ -34,6 +36,86 @@ impl<T> OfBerLength for T where T: OfBerCommon {}
 pub trait OfBerOid: OfBerCommon {}
 impl<T> OfBerOid for T where T: OfBerCommon {}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Errors which can occur when decoding a [`BerLength`]
+/// 
+/// These are attached as the cause of the [`winnow::error::ContextError`]
+/// (see [`winnow::error::ContextError::cause`]), and can be recovered by downcasting.
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::prelude::*;
+/// use tinyklv::codecs::ber::{BerLength, BerLengthError};
+/// 
+/// let err = BerLength::<u64>::decode(&mut &[0x80][..]).unwrap_err();
+/// let cause = err.into_inner().unwrap().cause().unwrap().downcast_ref::<BerLengthError>().copied();
+/// assert_eq!(cause, Some(BerLengthError::Indefinite));
+/// ```
+pub enum BerLengthError {
+    /// The indefinite form (`0x80`), which has no meaning for KLV
+    Indefinite,
+    /// The reserved initial byte `0xFF` (see ITU-T X.690 8.1.3.5)
+    Reserved,
+    /// The length does not fit in the target type
+    Overflow,
+    /// The length is not minimally encoded, which is rejected in DER-strict mode
+    NonCanonical,
+    /// The length exceeds the configured maximum
+    TooLong {
+        len: u128,
+        max: u128,
+    },
+}
+/// [`BerLengthError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for BerLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BerLengthError::Indefinite => write!(f, "indefinite BER length form (0x80) is not supported"),
+            BerLengthError::Reserved => write!(f, "reserved BER length byte (0xFF)"),
+            BerLengthError::Overflow => write!(f, "BER length does not fit in the target type"),
+            BerLengthError::NonCanonical => write!(f, "BER length is not minimally encoded"),
+            BerLengthError::TooLong { len, max } => write!(f, "BER length {} exceeds the maximum of {}", len, max),
+        }
+    }
+}
+/// [`BerLengthError`] implementation of [`std::error::Error`]
+impl std::error::Error for BerLengthError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Configuration for decoding a [`BerLength`], see [`BerLength::decode_with`]
+/// 
+/// * `der` - Rejects lengths which are not minimally encoded (DER-strict mode),
+///   i.e. long forms for lengths below 128, or long forms with leading zero bytes
+/// * `max` - Rejects lengths above this value, to protect against absurd
+///   allocations from hostile input
+/// 
+/// Regardless of the configuration, the indefinite form, the reserved form,
+/// and lengths which overflow the target type are always rejected.
+pub struct BerLengthConfig {
+    pub der: bool,
+    pub max: Option<u128>,
+}
+/// [`BerLengthConfig`] implementation
+impl BerLengthConfig {
+    /// Creates a new lenient [`BerLengthConfig`], without a maximum length
+    pub const fn new() -> Self {
+        Self { der: false, max: None }
+    }
+
+    /// Enables DER-strict mode
+    pub const fn der(mut self) -> Self {
+        self.der = true;
+        self
+    }
+
+    /// Sets the maximum length
+    pub const fn max(mut self, max: u128) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
 #[derive(Debug, PartialEq)]
 /// Enum representing Basic-Encoding-Rules (BER) Length Encoding.
 /// 
@@ -87,6 +169,69 @@ impl<T: OfBerLength> BerLength<T> {
             BerLength::Long(len) => len.as_(),
         }
     }
+
+    /// Decodes a [`BerLength`] from a [`&[u8]`], using a [`BerLengthConfig`]
+    /// 
+    /// On failure, the cause of the error is a [`BerLengthError`]
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use tinyklv::prelude::*;
+    /// use tinyklv::codecs::ber::{BerLength, BerLengthConfig};
+    /// 
+    /// let der = BerLengthConfig::new().der();
+    /// // non-minimal, but valid BER
+    /// assert!(BerLength::<u64>::decode(&mut &[0x82, 0x00, 0x05][..]).is_ok());
+    /// assert!(BerLength::<u64>::decode_with(&mut &[0x82, 0x00, 0x05][..], &der).is_err());
+    /// assert!(BerLength::<u64>::decode_with(&mut &[0x81, 0x05][..], &der).is_err());
+    /// assert!(BerLength::<u64>::decode_with(&mut &[0x81, 0x85][..], &der).is_ok());
+    /// 
+    /// let max = BerLengthConfig::new().max(1024);
+    /// assert!(BerLength::<u64>::decode_with(&mut &[0x84, 0xFF, 0xFF, 0xFF, 0xFF][..], &max).is_err());
+    /// ```
+    pub fn decode_with(input: &mut &[u8], config: &BerLengthConfig) -> winnow::PResult<Self> {
+        // --------------------------------------------------
+        // err if no bytes
+        // --------------------------------------------------
+        let first_byte = take_one(input)?;
+        let first_byte = first_byte[0];
+        // --------------------------------------------------
+        // if MSB is not set, it's a short length (single byte)
+        // --------------------------------------------------
+        if first_byte & 0x80 == 0 { return check_max(input, BerLength::Short(first_byte), config); }
+        // --------------------------------------------------
+        // extract the number of bytes used for length encoding
+        // --------------------------------------------------
+        let num_bytes = (first_byte & 0x7F) as usize;
+        match num_bytes {
+            0 => return Err(ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::Indefinite)),
+            127 => return Err(ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::Reserved)),
+            _ => (),
+        }
+        // --------------------------------------------------
+        // ensure there are enough bytes in the stream
+        // --------------------------------------------------
+        // since 1 was taken from input, this should be
+        // `input.len() + 1 < num_bytes + 1`
+        // but can be shortened
+        // --------------------------------------------------
+        if input.len() < num_bytes { return Err(ErrMode::Incomplete(Needed::Size(std::num::NonZero::new(num_bytes + 1).unwrap()))); }
+        // --------------------------------------------------
+        // DER: no leading zero bytes, and no long form for
+        // lengths which fit in the short form
+        // --------------------------------------------------
+        if config.der && (input[0] == 0 || (num_bytes == 1 && input[0] < 0x80)) {
+            return Err(ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::NonCanonical));
+        }
+        // --------------------------------------------------
+        // decode the length from the specified number of bytes
+        // --------------------------------------------------
+        let output = parse_length_u128(input, num_bytes)?;
+        let output = T::from_u128(output)
+            .ok_or_else(|| ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::Overflow))?;
+        check_max(input, BerLength::Long(output), config)
+    }
 }
 /// [`BerLength`] implementation of [`EncodeValue`]
 impl<T: OfBerLength> EncodeValue<Vec<u8>> for BerLength<T> {
@@ -166,33 +311,11 @@ impl<T: OfBerLength> Decode<&[u8]> for BerLength<T> {
     /// assert_eq!(BerLength::decode(&mut &value1[..]).unwrap(), BerLength::new(&201_u64));
     /// assert_eq!(BerLength::decode(&mut &value2[..]).unwrap(), BerLength::new(&123891829038102_u64));
     /// ```
+    /// 
+    /// This is lenient: non-minimal encodings are accepted. See
+    /// [`BerLength::decode_with`] for DER-strict decoding and a maximum length.
     fn decode(input: &mut &[u8]) -> winnow::PResult<Self> {
-        // --------------------------------------------------
-        // err if no bytes
-        // --------------------------------------------------
-        let first_byte = take_one(input)?;
-        let first_byte = first_byte[0];
-        // --------------------------------------------------
-        // if MSB is not set, it's a short length (single byte)
-        // --------------------------------------------------
-        if first_byte & 0x80 == 0 { return Ok(BerLength::Short(first_byte)); }
-        // --------------------------------------------------
-        // extract the number of bytes used for length encoding
-        // --------------------------------------------------
-        let num_bytes = (first_byte & 0x7F) as usize;
-        // --------------------------------------------------
-        // ensure there are enough bytes in the stream
-        // --------------------------------------------------
-        // since 1 was taken from input, this should be
-        // `input.len() + 1 < num_bytes + 1`
-        // but can be shortened
-        // --------------------------------------------------
-        if input.len() < num_bytes { return Err(ErrMode::Incomplete(Needed::Size(std::num::NonZero::new(num_bytes + 1).unwrap()))); }
-        // --------------------------------------------------
-        // decode the length from the specified number of bytes
-        // --------------------------------------------------
-        let output = parse_length_u128(input, num_bytes)?;
-        Ok(BerLength::Long(T::from_u128(output).unwrap()))
+        Self::decode_with(input, &BerLengthConfig::new())
     }
 }
 
@@ -211,7 +334,7 @@ impl<T: OfBerLength> Decode<&[u8]> for BerLength<T> {
 /// use tinyklv::codecs::ber::BerOid;
 /// 
 /// assert_eq!(vec![129, 182, 2], BerOid::encode_value(&23298_u64));
-/// assert_eq!(23298_u64, BerOid::decode(&mut &vec![129, 182, 2][..]).unwrap().value);
+/// assert_eq!(23298_u64, BerOid::<u64>::decode(&mut &vec![129, 182, 2][..]).unwrap().value);
 /// ```
 pub struct BerOid<T: OfBerOid> {
     pub value: T,
@@ -245,8 +368,12 @@ impl<T: OfBerOid> EncodeValue<Vec<u8>> for BerOid<T> {
     /// all parsing needs. This struct is meant to be used as a development
     /// tool for encoding values to BER format.
     fn encode_value(&self) -> Vec<u8> {
-        let mut output = Vec::new();
+        // --------------------------------------------------
+        // zero is encoded as a single byte
+        // --------------------------------------------------
         let mut value = self.value.as_();
+        if value == 0 { return vec![0]; }
+        let mut output = Vec::new();
         let mut first_byte = true;
         while value > 0 {
             // --------------------------------------------------
@@ -282,13 +409,17 @@ impl<T: OfBerOid> Decode<&[u8]> for BerOid<T> {
     /// use tinyklv::prelude::*;
     /// use tinyklv::codecs::ber::BerOid;
     /// 
-    /// assert_eq!(23298_u64, BerOid::decode(&mut &vec![129, 182, 2][..]).unwrap().value);
+    /// assert_eq!(23298_u64, BerOid::<u64>::decode(&mut &vec![129, 182, 2][..]).unwrap().value);
+    /// 
+    /// // values which do not fit in the target type fail to decode
+    /// assert!(BerOid::<u8>::decode(&mut &vec![129, 182, 2][..]).is_err());
     /// ```
     /// 
     /// Please use [`crate::codecs::ber::dec::ber_oid`] instead for
     /// all parsing needs. This struct is meant to be used as a development
     /// tool for parsing BER encoded values.
     fn decode(input: &mut &[u8]) -> winnow::PResult<Self> {
+        let checkpoint = input.checkpoint();
         // --------------------------------------------------
         // take while MSB = 1, then take last byte and exit
         // if fails, it means it's a single byte with no
@@ -300,12 +431,23 @@ impl<T: OfBerOid> Decode<&[u8]> for BerOid<T> {
                 .chain(take_one(input)?)
                 // --------------------------------------------------
                 // extract the 7 bits from the byte (ignoring the MSB)
-                // and insert to correct position
+                // and insert to correct position, unless the value
+                // no longer fits in a u128
                 // --------------------------------------------------
-                .fold(0u128, |acc, &b| (acc << 7) | (b & 0x7F) as u128),
-            Err(_) => winnow::binary::be_u8(input)? as u128,
+                .try_fold(0u128, |acc, &b| match acc >> (128 - 7) {
+                    0 => Some((acc << 7) | (b & 0x7F) as u128),
+                    _ => None,
+                }),
+            Err(_) => Some(winnow::binary::be_u8(input)? as u128),
         };
-        Ok(BerOid::new(&T::from_u128(output).unwrap()))
+        match output.and_then(T::from_u128) {
+            Some(output) => Ok(BerOid::new(&output)),
+            None => Err(ErrMode::Backtrack(winnow::error::ContextError::new().add_context(
+                input,
+                &checkpoint,
+                winnow::error::StrContext::Label("BER-OID value does not fit in the target type"),
+            ))),
+        }
     }
 }
 
@@ -330,8 +472,23 @@ fn msb_is_set(b: u8) -> bool {
 
 #[inline(always)]
 /// Parses out a specified number of bytes and combines them into a [`u128`] value
+/// 
+/// Leading zero bytes are skipped, and errors with [`BerLengthError::Overflow`]
+/// if the remaining bytes do not fit in a [`u128`]
 fn parse_length_u128(input: &mut &[u8], num_bytes: usize) -> winnow::PResult<u128> {
-    take(num_bytes)
-        .map(|bytes: &[u8]| bytes.iter().fold(0u128, |acc, &byte| (acc << 8) | byte as u128))
-        .parse_next(input)
+    let bytes: &[u8] = take(num_bytes).parse_next(input)?;
+    let significant = bytes.iter().skip_while(|&&byte| byte == 0);
+    if significant.clone().count() > std::mem::size_of::<u128>() {
+        return Err(ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::Overflow));
+    }
+    Ok(significant.fold(0u128, |acc, &byte| (acc << 8) | byte as u128))
+}
+
+#[inline(always)]
+/// Checks a decoded [`BerLength`] against the maximum length of a [`BerLengthConfig`]
+fn check_max<T: OfBerLength>(input: &mut &[u8], len: BerLength<T>, config: &BerLengthConfig) -> winnow::PResult<BerLength<T>> {
+    match config.max {
+        Some(max) if len.as_u128() > max => Err(ErrMode::from_external_error(input, ErrorKind::Verify, BerLengthError::TooLong { len: len.as_u128(), max })),
+        _ => Ok(len),
+    }
 }
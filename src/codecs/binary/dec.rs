@@ -398,4 +398,21 @@ lengthed_le!(i128, 16, B128_PADDED);
 lengthed_be!(f32, 4, B32_PADDED);
 lengthed_be!(f64, 8, B64_PADDED);
 lengthed_le!(f32, 4, B32_PADDED);
-lengthed_le!(f64, 8, B64_PADDED);
\ No newline at end of file
+lengthed_le!(f64, 8, B64_PADDED);
+#[inline(always)]
+/// Decodes a 16-byte SMPTE universal label key
+/// 
+/// Key decoder of a `#[klv(form = universal_set)]`
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::codecs::binary::dec::universal_key;
+/// 
+/// let mut input: &[u8] = &[0x06, 0x0E, 0x2B, 0x34, 0x01, 0x01, 0x01, 0x01, 0x07, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, 0x00, 0x2A];
+/// assert_eq!(universal_key(&mut input).map(<[u8]>::len), Ok(16));
+/// assert_eq!(input, &[0x2A]);
+/// ```
+pub fn universal_key<'a>(input: &mut &'a [u8]) -> winnow::PResult<&'a [u8]> {
+    take(16usize).parse_next(input)
+}
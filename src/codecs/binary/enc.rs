@@ -79,6 +79,119 @@ impl_encode!(i128);
 impl_encode!(f32);
 impl_encode!(f64);
 
+#[inline(always)]
+/// Encodes a string as UTF-8 bytes
+/// 
+/// Inverse of [`crate::codecs::binary::dec::to_string_utf8`]
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::codecs::binary::enc::from_string_utf8;
+/// 
+/// assert_eq!(from_string_utf8("AF-101"), vec![0x41, 0x46, 0x2D, 0x31, 0x30, 0x31]);
+/// ```
+pub fn from_string_utf8(input: &str) -> Vec<u8> {
+    input.as_bytes().to_vec()
+}
+
+#[inline(always)]
+/// Encodes a string as little-endian UTF-16 bytes
+/// 
+/// Inverse of [`crate::codecs::binary::dec::to_string_utf16`]
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::codecs::binary::enc::from_string_utf16;
+/// 
+/// assert_eq!(from_string_utf16("AF"), vec![0x41, 0x00, 0x46, 0x00]);
+/// ```
+pub fn from_string_utf16(input: &str) -> Vec<u8> {
+    input.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+#[inline(always)]
+#[cfg(feature = "ascii")]
+/// Encodes a string as ASCII bytes, replacing any non-ASCII character with `?`
+/// 
+/// Inverse of [`crate::codecs::binary::dec::to_string_ascii`]
+/// 
+/// # Example
+/// 
+/// ```
+/// use tinyklv::codecs::binary::enc::from_string_ascii;
+/// 
+/// assert_eq!(from_string_ascii("AF-101"), vec![0x41, 0x46, 0x2D, 0x31, 0x30, 0x31]);
+/// assert_eq!(from_string_ascii("π"), vec![b'?']);
+/// ```
+pub fn from_string_ascii(input: &str) -> Vec<u8> {
+    input
+        .chars()
+        .map(|c| match c.is_ascii() {
+            true => c as u8,
+            false => b'?',
+        })
+        .collect()
+}
+
+#[inline(always)]
+#[cfg(feature = "chrono")]
+/// Formats a date as UTF-8 bytes, using [`chrono::NaiveDate::format`]
+/// 
+/// Inverse of [`crate::as_date`]. Panics if `fmt` is not a valid format string
+/// 
+/// # Example
+/// 
+/// ```
+/// let date = chrono::NaiveDate::from_ymd_opt(2020, 12, 31).unwrap();
+/// assert_eq!(tinyklv::enc::binary::from_date("%Y-%m-%d")(&date), b"2020-12-31");
+/// ```
+pub fn from_date(fmt: &str) -> impl Fn(&chrono::NaiveDate) -> Vec<u8> + '_ {
+    move |input| input.format(fmt).to_string().into_bytes()
+}
+
+#[inline(always)]
+#[cfg(feature = "chrono")]
+/// Formats a time as UTF-8 bytes, using [`chrono::NaiveTime::format`]
+/// 
+/// Inverse of [`crate::as_time`]. Panics if `fmt` is not a valid format string
+/// 
+/// # Example
+/// 
+/// ```
+/// let time = chrono::NaiveTime::from_hms_opt(12, 34, 56).unwrap();
+/// assert_eq!(tinyklv::enc::binary::from_time("%H:%M:%S")(&time), b"12:34:56");
+/// ```
+pub fn from_time(fmt: &str) -> impl Fn(&chrono::NaiveTime) -> Vec<u8> + '_ {
+    move |input| input.format(fmt).to_string().into_bytes()
+}
+
+#[inline(always)]
+#[cfg(feature = "chrono")]
+/// Formats a datetime as UTF-8 bytes, using [`chrono::NaiveDateTime::format`]
+/// 
+/// Inverse of [`crate::as_datetime`]. Panics if `fmt` is not a valid format string
+/// 
+/// # Example
+/// 
+/// ```
+/// let datetime = chrono::NaiveDate::from_ymd_opt(2020, 12, 31).unwrap().and_hms_opt(12, 34, 56).unwrap();
+/// assert_eq!(tinyklv::enc::binary::from_datetime("%Y-%m-%d %H:%M:%S")(&datetime), b"2020-12-31 12:34:56");
+/// ```
+pub fn from_datetime(fmt: &str) -> impl Fn(&chrono::NaiveDateTime) -> Vec<u8> + '_ {
+    move |input| input.format(fmt).to_string().into_bytes()
+}
+
+#[inline(always)]
+/// Encodes a 16-byte SMPTE universal label key, as-is
+/// 
+/// Key encoder of a `#[klv(form = universal_set)]`, inverse of
+/// [`crate::codecs::binary::dec::universal_key`]
+pub fn universal_key(input: &[u8]) -> Vec<u8> {
+    input.to_vec()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,51 +7,253 @@ pub mod enc;
 // --------------------------------------------------
 // external
 // --------------------------------------------------
-use num_traits::ToBytes;
 use winnow::Parser;
-use std::convert::AsRef;
+use winnow::error::{ErrMode, ErrorKind, FromExternalError};
 
-/// [`FixedLength`] encoder / decoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Byte order of a [`FixedLength`] integer
+pub enum Endian {
+    /// Most significant byte first
+    Big,
+    /// Least significant byte first
+    Little,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Errors of encoding / decoding a [`FixedLength`] integer
+///
+/// When decoding, this is the cause of the [`winnow`] error:
+///
+/// ```
+/// use tinyklv::codecs::binary::{FixedLength, FixedLengthError};
+///
+/// let err = FixedLength::be(2).decode::<u8>(&mut &[0x01, 0x00][..]).unwrap_err();
+/// let cause = err.into_inner().unwrap().cause().unwrap().downcast_ref::<FixedLengthError>().copied();
+/// assert_eq!(cause, Some(FixedLengthError::Overflow { len: 2 }));
+/// ```
+pub enum FixedLengthError {
+    /// The width is not within `1..=16` bytes
+    InvalidLength(usize),
+    /// The value does not fit in the width (encoding), or in the target type (decoding)
+    Overflow {
+        /// The width, in bytes
+        len: usize,
+    },
+}
+/// [`FixedLengthError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for FixedLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FixedLengthError::InvalidLength(len) => write!(f, "fixed length of {} bytes is not within 1..=16", len),
+            FixedLengthError::Overflow { len } => write!(f, "value does not fit in {} bytes / the target type", len),
+        }
+    }
+}
+/// [`FixedLengthError`] implementation of [`std::error::Error`]
+impl std::error::Error for FixedLengthError {}
+
+/// An integer which can be encoded / decoded by [`FixedLength`]
+///
+/// Implemented for all primitive integers
+pub trait FixedInt: Copy {
+    /// Whether the integer is signed (two's-complement)
+    const SIGNED: bool;
+
+    /// Returns the value, sign-extended to 128 bits
+    fn to_raw(self) -> u128;
+
+    /// Returns the value from 128 sign-extended bits, if it fits
+    fn from_raw(raw: u128) -> Option<Self>;
+}
+macro_rules! fixed_int {
+    (unsigned $($ty:ty),*) => { $(
+        #[doc = concat!(" [`", stringify!($ty), "`] implementation of [`FixedInt`]")]
+        impl FixedInt for $ty {
+            const SIGNED: bool = false;
+            #[inline(always)]
+            fn to_raw(self) -> u128 { self as u128 }
+            #[inline(always)]
+            fn from_raw(raw: u128) -> Option<Self> { <$ty>::try_from(raw).ok() }
+        }
+    )* };
+    (signed $($ty:ty),*) => { $(
+        #[doc = concat!(" [`", stringify!($ty), "`] implementation of [`FixedInt`]")]
+        impl FixedInt for $ty {
+            const SIGNED: bool = true;
+            #[inline(always)]
+            fn to_raw(self) -> u128 { self as i128 as u128 }
+            #[inline(always)]
+            fn from_raw(raw: u128) -> Option<Self> { <$ty>::try_from(raw as i128).ok() }
+        }
+    )* };
+}
+fixed_int!(unsigned u8, u16, u32, u64, u128, usize);
+fixed_int!(signed i8, i16, i32, i64, i128, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Fixed-width integer encoder / decoder, of any width from 1 to 16 bytes
+///
+/// * Decoding sign-extends signed integers, and fails if the value does not
+///   fit in the target type
+/// * Encoding fails if the value does not fit in the width
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::codecs::binary::FixedLength;
+///
+/// // 24-bit integers
+/// let be = FixedLength::be(3);
+/// assert_eq!(be.decode::<u32>(&mut &[0x01, 0x02, 0x03][..]), Ok(0x010203));
+/// assert_eq!(be.decode::<i32>(&mut &[0xFF, 0xFF, 0xFE][..]), Ok(-2));
+/// assert_eq!(be.encode(&-2i32), Ok(vec![0xFF, 0xFF, 0xFE]));
+/// assert!(be.encode(&0x01000000u32).is_err());
+///
+/// let le = FixedLength::le(3);
+/// assert_eq!(le.encode(&0x010203u64), Ok(vec![0x03, 0x02, 0x01]));
+/// ```
 pub struct FixedLength {
     pub len: usize,
+    pub endian: Endian,
 }
 /// [`FixedLength`] implementation
 impl FixedLength {
-    #[inline(always)]
-    pub fn decode<P>(&self, input: &mut &[u8]) -> winnow::PResult<P>
-    where
-        P: From<u128>,
-    {
-        crate::codecs::binary::dec::be_u128_lengthed(self.len)
-            .parse_next(input)
-            .map(|res| res.into())
+    /// Big-endian integers of `len` bytes
+    pub const fn be(len: usize) -> Self {
+        Self { len, endian: Endian::Big }
+    }
+
+    /// Little-endian integers of `len` bytes
+    pub const fn le(len: usize) -> Self {
+        Self { len, endian: Endian::Little }
+    }
+
+    /// Returns an error if the width is not within `1..=16` bytes
+    const fn check(&self) -> Result<(), FixedLengthError> {
+        match self.len {
+            1..=16 => Ok(()),
+            len => Err(FixedLengthError::InvalidLength(len)),
+        }
+    }
+
+    /// Decodes a `P` from `len` bytes
+    ///
+    /// On failure, the cause of the error is a [`FixedLengthError`]
+    pub fn decode<P: FixedInt>(&self, input: &mut &[u8]) -> winnow::PResult<P> {
+        if let Err(err) = self.check() {
+            return Err(ErrMode::from_external_error(input, ErrorKind::Verify, err));
+        }
+        let bytes: &[u8] = winnow::token::take(self.len).parse_next(input)?;
+        let mut raw = match self.endian {
+            Endian::Big => bytes.iter().fold(0u128, |acc, &byte| (acc << 8) | byte as u128),
+            Endian::Little => bytes.iter().rev().fold(0u128, |acc, &byte| (acc << 8) | byte as u128),
+        };
+        // --------------------------------------------------
+        // sign extend, if the highest bit of the width is set
+        // --------------------------------------------------
+        let bits = 8 * self.len as u32;
+        if P::SIGNED && bits < u128::BITS && raw >> (bits - 1) & 1 == 1 {
+            raw |= u128::MAX << bits;
+        }
+        P::from_raw(raw).ok_or_else(|| ErrMode::from_external_error(input, ErrorKind::Verify, FixedLengthError::Overflow { len: self.len }))
+    }
+
+    /// Returns whether the sign-extended `raw` fits in the width
+    const fn fits(&self, raw: u128, signed: bool) -> bool {
+        let bits = 8 * self.len as u32;
+        // --------------------------------------------------
+        // the bits above the width must all equal the sign
+        // --------------------------------------------------
+        match (bits < u128::BITS, signed) {
+            (false, _) => true,
+            (true, false) => raw >> bits == 0,
+            (true, true) => {
+                let upper = (raw as i128) >> (bits - 1);
+                upper == 0 || upper == -1
+            },
+        }
+    }
+
+    /// Returns the `len` least significant bytes of `raw`
+    fn bytes(&self, raw: u128) -> Vec<u8> {
+        match self.endian {
+            Endian::Big => raw.to_be_bytes()[16 - self.len..].to_vec(),
+            Endian::Little => raw.to_le_bytes()[..self.len].to_vec(),
+        }
+    }
+
+    /// Encodes a `P` into `len` bytes
+    pub fn encode<P: FixedInt>(&self, input: &P) -> Result<Vec<u8>, FixedLengthError> {
+        self.check()?;
+        let raw = input.to_raw();
+        match self.fits(raw, P::SIGNED) {
+            true => Ok(self.bytes(raw)),
+            false => Err(FixedLengthError::Overflow { len: self.len }),
+        }
+    }
+
+    /// Encodes a `P` into `len` bytes, saturating values which do not fit
+    /// to the lowest / highest value of the width
+    ///
+    /// ```
+    /// use tinyklv::codecs::binary::FixedLength;
+    ///
+    /// assert_eq!(FixedLength::be(1).encode_saturating(&300u16), Ok(vec![0xFF]));
+    /// assert_eq!(FixedLength::be(1).encode_saturating(&-300i16), Ok(vec![0x80]));
+    /// ```
+    pub fn encode_saturating<P: FixedInt>(&self, input: &P) -> Result<Vec<u8>, FixedLengthError> {
+        self.check()?;
+        let raw = input.to_raw();
+        if self.fits(raw, P::SIGNED) {
+            return Ok(self.bytes(raw))
+        }
+        // --------------------------------------------------
+        // the width is below 128 bits, or the value would fit
+        // --------------------------------------------------
+        let bits = 8 * self.len as u32;
+        Ok(self.bytes(match P::SIGNED && (raw as i128) < 0 {
+            true => u128::MAX << (bits - 1),
+            false if P::SIGNED => (1 << (bits - 1)) - 1,
+            false => (1 << bits) - 1,
+        }))
     }
 
     #[inline(always)]
-    pub fn encode<P>(&self, input: &P) -> Vec<u8>
-    where
-        P: ToBytes
-    {
-        input.to_be_bytes().as_ref()[..self.len].to_vec()
+    /// Returns a big-endian decoder of `len` bytes, usable in `#[klv(dec = ...)]`
+    pub fn decode_lengthed<P: FixedInt>(len: usize) -> impl Fn(&mut &[u8]) -> winnow::PResult<P> {
+        move |input: &mut &[u8]| FixedLength::be(len).decode(input)
     }
-    
+
     #[inline(always)]
-    pub fn decode_lengthed<P>(len: usize) -> impl Fn(&mut &[u8]) -> winnow::PResult<P>
-    where
-        P: From<u128>,
-    {
-        move |input: &mut &[u8]| {
-            crate::codecs::binary::dec::be_u128_lengthed(len)
-                .parse_next(input)
-                .map(|res| res.into())
-        }
+    /// Returns a big-endian encoder of `len` bytes, usable in `#[klv(enc = ...)]`
+    ///
+    /// Values which do not fit, and widths outside of `1..=16` bytes, return
+    /// [`None`], which leaves the field out of the encoded packet, see
+    /// [`FixedLength::encode`]
+    ///
+    /// ```
+    /// use tinyklv::codecs::binary::FixedLength;
+    ///
+    /// assert_eq!(FixedLength::encode_lengthed(1)(&200u16), Some(vec![0xC8]));
+    /// assert_eq!(FixedLength::encode_lengthed(1)(&300u16), None);
+    /// assert_eq!(FixedLength::encode_lengthed(17)(&0u16), None);
+    /// ```
+    pub fn encode_lengthed<P: FixedInt>(len: usize) -> impl Fn(&P) -> Option<Vec<u8>> {
+        move |input: &P| FixedLength::be(len).encode(input).ok()
     }
 
     #[inline(always)]
-    pub fn encode_lengthed<P>(len: usize) -> impl Fn(&P) -> Vec<u8>
-    where
-        P: ToBytes
-    {
-        move |input: &P| input.to_be_bytes().as_ref()[..len].to_vec()
+    /// Returns a big-endian encoder of `len` bytes, usable in `#[klv(enc = ...)]`,
+    /// saturating values which do not fit, see [`FixedLength::encode_saturating`]
+    ///
+    /// Widths outside of `1..=16` bytes return [`None`]
+    ///
+    /// ```
+    /// use tinyklv::codecs::binary::FixedLength;
+    ///
+    /// assert_eq!(FixedLength::encode_lengthed_saturating(1)(&300u16), Some(vec![0xFF]));
+    /// ```
+    pub fn encode_lengthed_saturating<P: FixedInt>(len: usize) -> impl Fn(&P) -> Option<Vec<u8>> {
+        move |input: &P| FixedLength::be(len).encode_saturating(input).ok()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,132 @@
+//! Bit-packed flag words
+//!
+//! Many KLV standards pack several small values into a single big-endian
+//! word (e.g. the MISB ST 0601 *Generic Flag Data* or *Sensor Control Mode*
+//! items). This module provides the building blocks used by
+//! [`#[derive(KlvBits)]`](crate::KlvBits), which turns such a word into a
+//! plain struct.
+//!
+//! Bits are numbered from the least significant bit of the big-endian word,
+//! so bit `0` is the lowest bit of the last byte.
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::KlvBits;
+//! use tinyklv::prelude::*;
+//!
+//! #[derive(KlvBits, Debug, PartialEq)]
+//! struct Flags {
+//!     #[bits(0)]
+//!     laser_on: bool,
+//!     #[bits(1..=2)]
+//!     mode: u8,
+//!     #[bits(7)]
+//!     ir_polarity: bool,
+//! }
+//!
+//! let flags = Flags::decode(&mut &[0b1000_0101][..]).unwrap();
+//! assert_eq!(flags, Flags { laser_on: true, mode: 2, ir_polarity: true });
+//! assert_eq!(flags.encode_value(), vec![0b1000_0101]);
+//! ```
+//!
+//! The width defaults to the smallest number of bytes holding the highest
+//! bit, and can be set explicitly with `#[bits(bytes = N)]` on the struct
+//! (up to 16 bytes).
+
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::Parser;
+
+/// The maximum width of a bit-packed word, in bytes
+pub const MAX_BYTES: usize = 16;
+
+/// A value which can be stored in a range of bits
+///
+/// Implemented for [`bool`] and the unsigned integers. Implement it for your
+/// own types (e.g. small enums) to use them as [`KlvBits`](crate::KlvBits)
+/// fields.
+pub trait BitField: Sized {
+    /// The number of bits which `Self` can hold, which no field range of
+    /// `Self` may exceed
+    const BITS: u32 = u128::BITS;
+
+    /// Converts the (already shifted and masked) bits into `Self`
+    fn from_bits(bits: u128) -> Self;
+
+    /// Converts `Self` into bits, which are masked to the field width
+    fn to_bits(&self) -> u128;
+}
+/// [`bool`] implementation of [`BitField`]
+impl BitField for bool {
+    const BITS: u32 = 1;
+    #[inline(always)]
+    fn from_bits(bits: u128) -> Self { bits != 0 }
+    #[inline(always)]
+    fn to_bits(&self) -> u128 { *self as u128 }
+}
+macro_rules! impl_bit_field {
+    ($($ty:ty),*) => { $(
+        #[doc = concat!(" [`", stringify!($ty), "`] implementation of [`BitField`]")]
+        impl BitField for $ty {
+            const BITS: u32 = <$ty>::BITS;
+            #[inline(always)]
+            fn from_bits(bits: u128) -> Self { bits as $ty }
+            #[inline(always)]
+            fn to_bits(&self) -> u128 { *self as u128 }
+        }
+    )* };
+}
+impl_bit_field!(u8, u16, u32, u64, u128, usize);
+
+#[inline(always)]
+/// Returns the mask for a field of `width` bits
+pub const fn mask(width: u32) -> u128 {
+    match width >= u128::BITS {
+        true => u128::MAX,
+        false => (1 << width) - 1,
+    }
+}
+
+#[inline(always)]
+/// Reads bits `lo..lo + width` of `word`
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(tinyklv::codecs::bits::get(0b0110, 1, 2), 0b11);
+/// ```
+pub const fn get(word: u128, lo: u32, width: u32) -> u128 {
+    (word >> lo) & mask(width)
+}
+
+#[inline(always)]
+/// Writes `value` into bits `lo..lo + width` of `word`, discarding any
+/// bits of `value` which do not fit
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(tinyklv::codecs::bits::set(0b1001, 1, 2, 0b11), 0b1111);
+/// assert_eq!(tinyklv::codecs::bits::set(0b0000, 1, 2, 0b111), 0b0110);
+/// ```
+pub const fn set(word: u128, lo: u32, width: u32, value: u128) -> u128 {
+    (word & !(mask(width) << lo)) | ((value & mask(width)) << lo)
+}
+
+#[inline(always)]
+/// Decodes a `len` byte big-endian word
+///
+/// `len` must be at most [`MAX_BYTES`]
+pub fn decode_word(len: usize) -> impl Fn(&mut &[u8]) -> winnow::PResult<u128> {
+    move |input| crate::codecs::binary::dec::be_u128_lengthed(len).parse_next(input)
+}
+
+#[inline(always)]
+/// Encodes `word` as a `len` byte big-endian word
+///
+/// `len` must be at most [`MAX_BYTES`]
+pub fn encode_word(len: usize) -> impl Fn(u128) -> Vec<u8> {
+    move |word| crate::codecs::binary::enc::be_u128_lengthed(len)(word)
+}
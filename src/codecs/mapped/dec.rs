@@ -0,0 +1,99 @@
+//! Decoders for linearly mapped values
+//!
+//! See [`crate::codecs::mapped`]
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::Linear;
+use crate::prelude::*;
+
+/// Decodes a raw integer with `parser`, and maps it onto `linear`
+///
+/// Fails with a backtrack error, after consuming the input, on the reserved value
+pub fn linear<P, T>(mut parser: P, linear: Linear) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64>
+where
+    P: FnMut(&mut &[u8]) -> winnow::PResult<T>,
+    T: Into<i128>,
+{
+    move |input| match linear.to_value(parser(input)?.into()) {
+        Some(value) => Ok(value),
+        None => Err(winnow::error::ErrMode::Backtrack(winnow::error::ContextError::new().add_context(
+            input,
+            &input.checkpoint(),
+            winnow::error::StrContext::Label("Reserved value"),
+        ))),
+    }
+}
+
+/// Decodes a raw integer with `parser`, and maps it onto `linear`
+///
+/// Returns [`f64::NAN`] on the reserved value
+pub fn linear_nan<P, T>(mut parser: P, linear: Linear) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64>
+where
+    P: FnMut(&mut &[u8]) -> winnow::PResult<T>,
+    T: Into<i128>,
+{
+    move |input| Ok(linear.to_value(parser(input)?.into()).unwrap_or(f64::NAN))
+}
+
+macro_rules! linear {
+    (unsigned $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian [`", stringify!($ty), "`] mapped onto `min..=max`, without a reserved value")]
+        pub fn [<linear_ $ty>](min: f64, max: f64) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear(crate::codecs::binary::dec::[<be_ $ty>], Linear::unsigned($ty::BITS, min, max))
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian [`", stringify!($ty), "`] mapped onto `min..=max`, failing on the raw `reserved` value")]
+        pub fn [<linear_ $ty _reserved>](min: f64, max: f64, reserved: $ty) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear(crate::codecs::binary::dec::[<be_ $ty>], Linear::unsigned($ty::BITS, min, max).reserved(Some(reserved as i128)))
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian [`", stringify!($ty), "`] mapped onto `min..=max`, returning [`f64::NAN`] on the raw `reserved` value")]
+        pub fn [<linear_ $ty _reserved_nan>](min: f64, max: f64, reserved: $ty) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear_nan(crate::codecs::binary::dec::[<be_ $ty>], Linear::unsigned($ty::BITS, min, max).reserved(Some(reserved as i128)))
+        }
+    }};
+    (signed $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian [`", stringify!($ty), "`] mapped onto `min..=max`, failing on [`", stringify!($ty), "::MIN`]")]
+        pub fn [<linear_ $ty>](min: f64, max: f64) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear(crate::codecs::binary::dec::[<be_ $ty>], Linear::signed($ty::BITS, min, max))
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian [`", stringify!($ty), "`] mapped onto `min..=max`, returning [`f64::NAN`] on [`", stringify!($ty), "::MIN`]")]
+        pub fn [<linear_ $ty _nan>](min: f64, max: f64) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear_nan(crate::codecs::binary::dec::[<be_ $ty>], Linear::signed($ty::BITS, min, max))
+        }
+    }};
+    (offset $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian offset-binary [`", stringify!($ty), "`] mapped onto `min..=max`, failing on `0`")]
+        pub fn [<linear_offset_ $ty>](min: f64, max: f64) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear([<offset_ $ty>], Linear::signed($ty::BITS, min, max))
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Decodes a big-endian offset-binary [`", stringify!($ty), "`] mapped onto `min..=max`, returning [`f64::NAN`] on `0`")]
+        pub fn [<linear_offset_ $ty _nan>](min: f64, max: f64) -> impl FnMut(&mut &[u8]) -> winnow::PResult<f64> {
+            linear_nan([<offset_ $ty>], Linear::signed($ty::BITS, min, max))
+        }
+
+        /// Decodes an offset-binary integer into its signed value
+        fn [<offset_ $ty>](input: &mut &[u8]) -> winnow::PResult<i128> {
+            crate::codecs::binary::dec::[<be_ $ty>](input).map(|raw| i128::from(raw) - (1 << ($ty::BITS - 1)))
+        }
+    }};
+}
+linear!(unsigned u8);
+linear!(unsigned u16);
+linear!(unsigned u32);
+linear!(signed i8);
+linear!(signed i16);
+linear!(signed i32);
+linear!(offset u8);
+linear!(offset u16);
+linear!(offset u32);
@@ -0,0 +1,82 @@
+//! Encoders for linearly mapped values
+//!
+//! See [`crate::codecs::mapped`]
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::Linear;
+
+/// Maps `value` onto the raw integer of `linear`, which has a reserved value
+/// to encode [`f64::NAN`] with
+fn raw(linear: &Linear, value: f64) -> i128 {
+    debug_assert!(linear.reserved.is_some());
+    linear.to_raw(value).unwrap_or_default()
+}
+
+macro_rules! linear {
+    (unsigned $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Encodes a value in `min..=max` as a big-endian [`", stringify!($ty), "`], without a reserved value")]
+        ///
+        /// [`f64::NAN`] has no raw value to be encoded with, and is omitted
+        pub fn [<linear_ $ty>](min: f64, max: f64) -> impl Fn(&f64) -> Option<Vec<u8>> {
+            let linear = Linear::unsigned($ty::BITS, min, max);
+            move |input| linear.to_raw(*input).map(|raw| (raw as $ty).to_be_bytes().to_vec())
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Encodes a value in `min..=max` as a big-endian [`", stringify!($ty), "`], writing the raw `reserved` value for [`f64::NAN`]")]
+        pub fn [<linear_ $ty _reserved>](min: f64, max: f64, reserved: $ty) -> impl Fn(&f64) -> Vec<u8> {
+            let linear = Linear::unsigned($ty::BITS, min, max).reserved(Some(reserved as i128));
+            move |input| (raw(&linear, *input) as $ty).to_be_bytes().to_vec()
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Optional variant of [`linear_", stringify!($ty), "`], for `Option<f64>` fields")]
+        ///
+        /// [`f64::NAN`] is omitted, like [`None`]
+        pub fn [<linear_ $ty _opt>](min: f64, max: f64) -> impl Fn(&Option<f64>) -> Option<Vec<u8>> {
+            let encoder = [<linear_ $ty>](min, max);
+            move |input| input.as_ref().and_then(&encoder)
+        }
+    }};
+    (signed $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Encodes a value in `min..=max` as a big-endian [`", stringify!($ty), "`], writing [`", stringify!($ty), "::MIN`] for [`f64::NAN`]")]
+        pub fn [<linear_ $ty>](min: f64, max: f64) -> impl Fn(&f64) -> Vec<u8> {
+            let linear = Linear::signed($ty::BITS, min, max);
+            move |input| (raw(&linear, *input) as $ty).to_be_bytes().to_vec()
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Optional variant of [`linear_", stringify!($ty), "`], for `Option<f64>` fields")]
+        pub fn [<linear_ $ty _opt>](min: f64, max: f64) -> impl Fn(&Option<f64>) -> Option<Vec<u8>> {
+            let encoder = [<linear_ $ty>](min, max);
+            move |input| input.as_ref().map(&encoder)
+        }
+    }};
+    (offset $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Encodes a value in `min..=max` as a big-endian offset-binary [`", stringify!($ty), "`], writing `0` for [`f64::NAN`]")]
+        pub fn [<linear_offset_ $ty>](min: f64, max: f64) -> impl Fn(&f64) -> Vec<u8> {
+            let linear = Linear::signed($ty::BITS, min, max);
+            move |input| ((raw(&linear, *input) + (1 << ($ty::BITS - 1))) as $ty).to_be_bytes().to_vec()
+        }
+
+        #[inline(always)]
+        #[doc = concat!(" Optional variant of [`linear_offset_", stringify!($ty), "`], for `Option<f64>` fields")]
+        pub fn [<linear_offset_ $ty _opt>](min: f64, max: f64) -> impl Fn(&Option<f64>) -> Option<Vec<u8>> {
+            let encoder = [<linear_offset_ $ty>](min, max);
+            move |input| input.as_ref().map(&encoder)
+        }
+    }};
+}
+linear!(unsigned u8);
+linear!(unsigned u16);
+linear!(unsigned u32);
+linear!(signed i8);
+linear!(signed i16);
+linear!(signed i32);
+linear!(offset u8);
+linear!(offset u16);
+linear!(offset u32);
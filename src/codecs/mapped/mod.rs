@@ -0,0 +1,131 @@
+//! Linear mappings between integers on the wire and floating point values,
+//! as used throughout MISB ST 0601 / ST 0102
+//!
+//! * Unsigned integers of `n` bits map `0..=2^n - 1` onto `min..=max`:
+//!   `value = min + raw * (max - min) / (2^n - 1)`
+//! * Signed (two's-complement) integers of `n` bits map
+//!   `-(2^(n-1) - 1)..=2^(n-1) - 1` symmetrically onto `min..=max`:
+//!   `value = (min + max) / 2 + raw * (max - min) / (2^n - 2)`. The lowest
+//!   value, `-2^(n-1)` (e.g. `0x80000000`), is reserved as the "error"
+//!   indicator
+//! * Offset-binary integers of `n` bits (`linear_offset_*`) store the signed
+//!   mapping's raw value plus `2^(n-1)`, so `2^(n-1)` maps onto the middle of
+//!   `min..=max` and `0` is reserved as the "error" indicator
+//!
+//! Encoding inverts the mapping and rounds to the nearest integer, with
+//! halves rounded away from zero, then clamps to the valid range. A value
+//! landing on a reserved raw value inside the range (e.g. `0xFFFF` for an
+//! unsigned 16-bit mapping) is moved one step towards the inside of the range.
+//!
+//! Reserved values decode to [`f64::NAN`] (`linear_*_nan`) or, for the plain
+//! `linear_*` decoders, fail *after* consuming the value. The latter makes an
+//! `Option<f64>` field of a derived struct decode to [`None`]. Encoders write
+//! the reserved value for [`f64::NAN`]. Without a reserved value, [`f64::NAN`]
+//! can not be encoded, and is omitted, which leaves the field out of a derived
+//! struct's encoding.
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::codecs::mapped::{dec, enc};
+//!
+//! // MISB ST 0601 sensor latitude (tag 13)
+//! let lat = enc::linear_i32(-90.0, 90.0)(&60.176822966978335);
+//! assert_eq!(lat, vec![0x55, 0x95, 0xB6, 0x6D]);
+//! let decoded = dec::linear_i32(-90.0, 90.0)(&mut &lat[..]).unwrap();
+//! assert!((decoded - 60.176822966978335).abs() < 1e-7);
+//!
+//! // the reserved error indicator
+//! let reserved: &[u8] = &[0x80, 0x00, 0x00, 0x00];
+//! assert!(dec::linear_i32(-90.0, 90.0)(&mut &reserved[..]).is_err());
+//! assert!(dec::linear_i32_nan(-90.0, 90.0)(&mut &reserved[..]).unwrap().is_nan());
+//! assert_eq!(enc::linear_i32(-90.0, 90.0)(&f64::NAN), reserved);
+//!
+//! // MISB ST 0601 platform heading (tag 5)
+//! assert_eq!(enc::linear_u16(0.0, 360.0)(&159.9744), Some(vec![0x71, 0xC2]));
+//! assert_eq!(enc::linear_u16(0.0, 360.0)(&f64::NAN), None);
+//! ```
+pub mod dec;
+pub mod enc;
+
+/// A linear mapping between an `n`-bit integer and `min..=max`
+///
+/// See the [module documentation](self) for the exact formulas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Linear {
+    /// The lowest value of the range
+    pub min: f64,
+    /// The highest value of the range
+    pub max: f64,
+    /// The width of the integer, in bits
+    pub bits: u32,
+    /// Whether the integer is signed (two's-complement)
+    pub signed: bool,
+    /// The raw value reserved as an error indicator, if any
+    pub reserved: Option<i128>,
+}
+/// [`Linear`] implementation
+impl Linear {
+    /// Creates an unsigned mapping, without a reserved value
+    pub const fn unsigned(bits: u32, min: f64, max: f64) -> Self {
+        Self { min, max, bits, signed: false, reserved: None }
+    }
+
+    /// Creates a signed mapping, with `-2^(bits - 1)` reserved
+    pub const fn signed(bits: u32, min: f64, max: f64) -> Self {
+        Self { min, max, bits, signed: true, reserved: Some(-(1 << (bits - 1))) }
+    }
+
+    /// Sets the raw value reserved as an error indicator
+    pub const fn reserved(mut self, reserved: Option<i128>) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
+    /// The lowest and highest raw values of the mapping
+    pub const fn raw_range(&self) -> (i128, i128) {
+        match self.signed {
+            true => (-(1 << (self.bits - 1)) + 1, (1 << (self.bits - 1)) - 1),
+            false => (0, (1 << self.bits) - 1),
+        }
+    }
+
+    /// The number of steps across `min..=max`
+    const fn steps(&self) -> f64 {
+        let (lo, hi) = self.raw_range();
+        (hi - lo) as f64
+    }
+
+    /// The value which raw `0` maps onto
+    fn offset(&self) -> f64 {
+        match self.signed {
+            true => (self.min + self.max) / 2.0,
+            false => self.min,
+        }
+    }
+
+    /// Maps a raw integer onto the range, returning [`None`] for the reserved value
+    pub fn to_value(&self, raw: i128) -> Option<f64> {
+        match self.reserved == Some(raw) {
+            true => None,
+            false => Some(self.offset() + raw as f64 * (self.max - self.min) / self.steps()),
+        }
+    }
+
+    /// Maps a value onto the raw integer, which is never the reserved value
+    /// unless `value` is [`f64::NAN`]
+    ///
+    /// Returns [`None`] for [`f64::NAN`] when no value is reserved
+    pub fn to_raw(&self, value: f64) -> Option<i128> {
+        let (lo, hi) = self.raw_range();
+        if value.is_nan() {
+            return self.reserved
+        }
+        let raw = (((value - self.offset()) * self.steps() / (self.max - self.min)).round() as i128).clamp(lo, hi);
+        match self.reserved == Some(raw) {
+            true if raw > lo => Some(raw - 1),
+            true => Some(raw + 1),
+            false => Some(raw),
+        }
+    }
+}
@@ -1,6 +1,10 @@
 pub mod ber;
 pub mod binary;
+pub mod bits;
+pub mod mapped;
 pub mod string;
+pub mod time;
+pub mod ul;
 
 /// Re-exports path from `codecs::name::dec/enc` -> `codecs::dec/enc::name`
 /// 
@@ -17,6 +21,8 @@ macro_rules! re_export {
 }
 re_export! {
     ber,
+    mapped,
     string,
+    time,
     binary
 }
\ No newline at end of file
@@ -1,36 +1,104 @@
+//! Decoders for text (`&str`) streams
+//!
+//! See [`crate::codecs::string`]
 // --------------------------------------------------
 // external
 // --------------------------------------------------
 use winnow::Parser;
+use winnow::token::{take, take_till};
 
-macro_rules! wrap {
-    ($ty:ty) => { paste::paste! {
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::SEPARATOR;
+
+macro_rules! decimal {
+    (unsigned $ty:ident) => {
         #[inline(always)]
-        #[doc = concat!(" Wrapper for [`winnow::binary::be_", stringify!($ty), "`] with implied generics `<&str, winnow::error::ContextError>`")]
-        pub fn [<be_ $ty>](input: &mut &str) -> winnow::PResult<$ty> {
-            crate::codecs::binary::dec::[<be_ $ty>].parse_next(&mut input.as_bytes())
+        #[doc = concat!(" Decodes a decimal [`", stringify!($ty), "`], using [`winnow::ascii::dec_uint`]")]
+        pub fn $ty(input: &mut &str) -> winnow::PResult<$ty> {
+            winnow::ascii::dec_uint.parse_next(input)
         }
+    };
+    (signed $ty:ident) => {
         #[inline(always)]
-        #[doc = concat!(" Wrapper for [`winnow::binary::le_", stringify!($ty), "`] with implied generics `<&str, winnow::error::ContextError>`")]
-        pub fn [<le_ $ty>](input: &mut &str) -> winnow::PResult<$ty> {
-            crate::codecs::binary::dec::[<le_ $ty>].parse_next(&mut input.as_bytes())
+        #[doc = concat!(" Decodes a decimal [`", stringify!($ty), "`], with an optional sign, using [`winnow::ascii::dec_int`]")]
+        pub fn $ty(input: &mut &str) -> winnow::PResult<$ty> {
+            winnow::ascii::dec_int.parse_next(input)
         }
+    };
+    (float $ty:ident) => {
         #[inline(always)]
-        #[doc = concat!(" Wrapper for [`winnow::binary::", stringify!($ty), "`] with implied native-endianness generics `<&str, winnow::error::ContextError>`")]
-        pub fn [<$ty>](input: &mut &str) -> winnow::PResult<$ty> {
-            crate::codecs::binary::dec::$ty.parse_next(&mut input.as_bytes())
+        #[doc = concat!(" Decodes a decimal [`", stringify!($ty), "`], using [`winnow::ascii::float`]")]
+        pub fn $ty(input: &mut &str) -> winnow::PResult<$ty> {
+            winnow::ascii::float.parse_next(input)
+        }
+    };
+    (hex $ty:ty) => { paste::paste! {
+        #[inline(always)]
+        #[doc = concat!(" Decodes a hexadecimal [`", stringify!($ty), "`] (without a `0x` prefix), using [`winnow::ascii::hex_uint`]")]
+        pub fn [<hex_ $ty>](input: &mut &str) -> winnow::PResult<$ty> {
+            winnow::ascii::hex_uint.parse_next(input)
         }
     }};
 }
-wrap!(u8);
-wrap!(u16);
-wrap!(u32);
-wrap!(u64);
-wrap!(u128);
-wrap!(i8);
-wrap!(i16);
-wrap!(i32);
-wrap!(i64);
-wrap!(i128);
-wrap!(f32);
-wrap!(f64);
\ No newline at end of file
+decimal!(unsigned u8);
+decimal!(unsigned u16);
+decimal!(unsigned u32);
+decimal!(unsigned u64);
+decimal!(unsigned u128);
+decimal!(unsigned usize);
+decimal!(signed i8);
+decimal!(signed i16);
+decimal!(signed i32);
+decimal!(signed i64);
+decimal!(signed i128);
+decimal!(float f32);
+decimal!(float f64);
+decimal!(hex u8);
+decimal!(hex u16);
+decimal!(hex u32);
+decimal!(hex u64);
+decimal!(hex u128);
+
+#[inline(always)]
+/// Decodes a key: everything up to the [`SEPARATOR`]
+///
+/// # Example
+///
+/// ```
+/// let mut input = "HDG:3:270;";
+/// assert_eq!(tinyklv::codecs::string::dec::key(&mut input), Ok("HDG"));
+/// assert_eq!(input, ":3:270;");
+/// ```
+pub fn key<'a>(input: &mut &'a str) -> winnow::PResult<&'a str> {
+    take_till(1.., SEPARATOR).parse_next(input)
+}
+
+#[inline(always)]
+/// Decodes a decimal length, surrounded by the [`SEPARATOR`]: `:LEN:`
+///
+/// # Example
+///
+/// ```
+/// let mut input = ":3:270;";
+/// assert_eq!(tinyklv::codecs::string::dec::len(&mut input), Ok(3));
+/// assert_eq!(input, "270;");
+/// ```
+pub fn len(input: &mut &str) -> winnow::PResult<usize> {
+    winnow::combinator::delimited(SEPARATOR, usize, SEPARATOR).parse_next(input)
+}
+
+#[inline(always)]
+/// Decodes `len` characters into a [`String`]
+///
+/// # Example
+///
+/// ```
+/// let mut input = "MISSION01;";
+/// assert_eq!(tinyklv::codecs::string::dec::to_string(9)(&mut input), Ok(String::from("MISSION01")));
+/// assert_eq!(input, ";");
+/// ```
+pub fn to_string(len: usize) -> impl Fn(&mut &str) -> winnow::PResult<String> {
+    move |input| take(len).map(String::from).parse_next(input)
+}
@@ -0,0 +1,67 @@
+//! Encoders for text (`&str`) streams
+//!
+//! See [`crate::codecs::string`]
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::SEPARATOR;
+
+#[inline(always)]
+/// Encodes a key
+///
+/// Inverse of [`super::dec::key`]
+pub fn key<K: std::fmt::Display>(key: K) -> String {
+    key.to_string()
+}
+
+#[inline(always)]
+/// Encodes a decimal length, surrounded by the [`SEPARATOR`]: `:LEN:`
+///
+/// Inverse of [`super::dec::len`]
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(tinyklv::codecs::string::enc::len(3), ":3:");
+/// ```
+pub fn len(len: usize) -> String {
+    format!("{SEPARATOR}{len}{SEPARATOR}")
+}
+
+#[inline(always)]
+/// Encodes a value in decimal, using its [`std::fmt::Display`] implementation
+///
+/// Inverse of the decimal decoders in [`super::dec`], such as [`super::dec::u16`]
+/// or [`super::dec::f64`]
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(tinyklv::codecs::string::enc::decimal(&-12i8), "-12");
+/// assert_eq!(tinyklv::codecs::string::enc::decimal(&0.5f64), "0.5");
+/// ```
+pub fn decimal<T: std::fmt::Display>(input: &T) -> String {
+    input.to_string()
+}
+
+#[inline(always)]
+/// Encodes an integer in upper-case hexadecimal, without a `0x` prefix
+///
+/// Inverse of the hexadecimal decoders in [`super::dec`], such as [`super::dec::hex_u8`]
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(tinyklv::codecs::string::enc::hex(&0x1Fu8), "1F");
+/// ```
+pub fn hex<T: std::fmt::UpperHex>(input: &T) -> String {
+    format!("{input:X}")
+}
+
+#[inline(always)]
+/// Encodes a string as-is
+///
+/// Inverse of [`super::dec::to_string`]
+pub fn from_string(input: &str) -> String {
+    input.to_string()
+}
@@ -1,2 +1,45 @@
+//! Codecs for text (`&str`) streams
+//!
+//! Text KLV records are written as `KEY:LEN:VALUE;`, where `LEN` is the
+//! number of characters of `VALUE`, in decimal. The key decoder
+//! [`dec::key`] reads up to the first [`SEPARATOR`], and the length decoder
+//! [`dec::len`] reads the `:LEN:` in between. The [`TERMINATOR`] is written
+//! after every record by [`crate::prelude::IntoKlvString`], and skipped by
+//! structs deriving [`crate::Klv`] with `stream = &str`.
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//! use tinyklv::codecs::string::{dec, enc};
+//!
+//! #[derive(Klv, Debug, PartialEq)]
+//! #[klv(
+//!     stream = &str,
+//!     sentinel = "UAS",
+//!     key(enc = enc::key, dec = dec::key),
+//!     len(enc = enc::len, dec = dec::len),
+//! )]
+//! struct Uas {
+//!     #[klv(key = "ID", dyn = true, enc = enc::from_string, dec = dec::to_string)]
+//!     mission: String,
+//!     #[klv(key = "HDG", enc = enc::decimal, dec = dec::u16)]
+//!     heading: u16,
+//!     #[klv(key = "FLG", enc = enc::hex, dec = dec::hex_u8)]
+//!     flags: u8,
+//! }
+//!
+//! let uas = Uas { mission: "MISSION01".into(), heading: 270, flags: 0x1F };
+//! let encoded = uas.encode();
+//! assert_eq!(encoded, "UAS:34:ID:9:MISSION01;HDG:3:270;FLG:2:1F;;");
+//! assert_eq!(Uas::extract(&mut encoded.as_str()), Ok(uas));
+//! ```
 pub mod dec;
-pub mod enc;
\ No newline at end of file
+pub mod enc;
+
+/// Separates the key, length and value of a text record
+pub const SEPARATOR: char = ':';
+
+/// Terminates a text record
+pub const TERMINATOR: char = ';';
@@ -0,0 +1,94 @@
+//! Decoders for timestamps
+//!
+//! See [`crate::codecs::time`]
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+/// Returns a backtrack error with a label, for out-of-range timestamps
+fn out_of_range(input: &mut &[u8], checkpoint: &<&[u8] as winnow::stream::Stream>::Checkpoint) -> winnow::error::ErrMode<winnow::error::ContextError> {
+    winnow::error::ErrMode::Backtrack(winnow::error::ContextError::new().add_context(
+        input,
+        checkpoint,
+        winnow::error::StrContext::Label("Timestamp is out of range"),
+    ))
+}
+
+#[cfg(feature = "chrono")]
+/// Decodes a big-endian [`u64`] of microseconds since the Unix epoch
+/// 
+/// # Example
+/// 
+/// ```
+/// // MISB ST 0601 tag 2: 2008-10-24T00:13:29.913Z
+/// let mut input: &[u8] = &[0x00, 0x04, 0x59, 0xF4, 0xA6, 0xAA, 0x4A, 0xA8];
+/// let timestamp = tinyklv::dec::time::timestamp_us(&mut input).unwrap();
+/// assert_eq!(timestamp.to_rfc3339(), "2008-10-24T00:13:29.913+00:00");
+/// ```
+pub fn timestamp_us(input: &mut &[u8]) -> winnow::PResult<chrono::DateTime<chrono::Utc>> {
+    let checkpoint = input.checkpoint();
+    let micros = crate::codecs::binary::dec::be_u64(input)?;
+    i64::try_from(micros)
+        .ok()
+        .and_then(chrono::DateTime::from_timestamp_micros)
+        .ok_or_else(|| out_of_range(input, &checkpoint))
+}
+
+/// Decodes a big-endian [`u64`] of microseconds since the Unix epoch
+/// 
+/// # Example
+/// 
+/// ```
+/// let mut input: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x42, 0x40];
+/// let timestamp = tinyklv::dec::time::systemtime_us(&mut input).unwrap();
+/// assert_eq!(timestamp, std::time::UNIX_EPOCH + std::time::Duration::from_secs(1));
+/// ```
+pub fn systemtime_us(input: &mut &[u8]) -> winnow::PResult<std::time::SystemTime> {
+    let checkpoint = input.checkpoint();
+    let micros = crate::codecs::binary::dec::be_u64(input)?;
+    std::time::UNIX_EPOCH
+        .checked_add(std::time::Duration::from_micros(micros))
+        .ok_or_else(|| out_of_range(input, &checkpoint))
+}
+
+#[cfg(feature = "chrono")]
+/// Decodes a big-endian [`u64`] of nanoseconds of MISB ST 0603 MISP Time,
+/// removing the [`TAI_OFFSET`](super::TAI_OFFSET) and the leap seconds
+/// inserted since 1972
+/// 
+/// See [`crate::codecs::time`] for how leap seconds are handled
+/// 
+/// # Example
+/// 
+/// ```
+/// // 2017-01-01T00:00:00Z, 10 + 27 leap seconds ahead of Unix time
+/// let misp: u64 = (1_483_228_800 + 37) * 1_000_000_000;
+/// let timestamp = tinyklv::dec::time::misp_timestamp_ns(&mut &misp.to_be_bytes()[..]).unwrap();
+/// assert_eq!(timestamp.to_rfc3339(), "2017-01-01T00:00:00+00:00");
+/// ```
+pub fn misp_timestamp_ns(input: &mut &[u8]) -> winnow::PResult<chrono::DateTime<chrono::Utc>> {
+    let checkpoint = input.checkpoint();
+    let nanos = crate::codecs::binary::dec::be_u64(input)?;
+    let misp_secs = (nanos / 1_000_000_000) as i64 - super::TAI_OFFSET;
+    let subsec = (nanos % 1_000_000_000) as u32;
+    // --------------------------------------------------
+    // past the TAI offset, the i-th leap second is at MISP
+    // time `leap + i`, and all MISP times after it are
+    // `i + 1` seconds ahead
+    // --------------------------------------------------
+    let mut leaps = 0;
+    let mut in_leap_second = false;
+    for (i, leap) in super::LEAP_SECONDS.iter().enumerate() {
+        let leap_misp = leap + i as i64;
+        if misp_secs < leap_misp { break }
+        if misp_secs == leap_misp { in_leap_second = true; break }
+        leaps = i as i64 + 1;
+    }
+    let timestamp = match in_leap_second {
+        // ---- 23:59:60, represented by chrono as 23:59:59 + 1s of nanoseconds
+        true => chrono::DateTime::from_timestamp(misp_secs - leaps - 1, 1_000_000_000 + subsec),
+        false => chrono::DateTime::from_timestamp(misp_secs - leaps, subsec),
+    };
+    timestamp.ok_or_else(|| out_of_range(input, &checkpoint))
+}
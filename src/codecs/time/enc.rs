@@ -0,0 +1,59 @@
+//! Encoders for timestamps
+//!
+//! See [`crate::codecs::time`]
+
+#[cfg(feature = "chrono")]
+/// Encodes a timestamp as a big-endian [`u64`] of microseconds since the Unix epoch
+/// 
+/// Timestamps before the epoch are clamped to the epoch
+/// 
+/// # Example
+/// 
+/// ```
+/// let timestamp = chrono::DateTime::parse_from_rfc3339("2008-10-24T00:13:29.913Z").unwrap().to_utc();
+/// assert_eq!(tinyklv::enc::time::timestamp_us(&timestamp), vec![0x00, 0x04, 0x59, 0xF4, 0xA6, 0xAA, 0x4A, 0xA8]);
+/// ```
+pub fn timestamp_us(input: &chrono::DateTime<chrono::Utc>) -> Vec<u8> {
+    (input.timestamp_micros().max(0) as u64).to_be_bytes().to_vec()
+}
+
+/// Encodes a timestamp as a big-endian [`u64`] of microseconds since the Unix epoch
+/// 
+/// Timestamps before the epoch are clamped to the epoch, and timestamps
+/// after the year ~586,000 saturate
+pub fn systemtime_us(input: &std::time::SystemTime) -> Vec<u8> {
+    let micros = input
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_micros()).unwrap_or(u64::MAX));
+    micros.to_be_bytes().to_vec()
+}
+
+#[cfg(feature = "chrono")]
+/// Encodes a timestamp as a big-endian [`u64`] of nanoseconds of MISB ST 0603
+/// MISP Time, adding the [`TAI_OFFSET`](super::TAI_OFFSET) and the leap
+/// seconds inserted since 1972
+/// 
+/// Timestamps before the epoch are clamped to the epoch. See [`crate::codecs::time`]
+/// for how leap seconds are handled
+/// 
+/// # Example
+/// 
+/// ```
+/// let timestamp = chrono::DateTime::parse_from_rfc3339("2017-01-01T00:00:00Z").unwrap().to_utc();
+/// let misp: u64 = (1_483_228_800 + 37) * 1_000_000_000;
+/// assert_eq!(tinyklv::enc::time::misp_timestamp_ns(&timestamp), misp.to_be_bytes());
+/// ```
+pub fn misp_timestamp_ns(input: &chrono::DateTime<chrono::Utc>) -> Vec<u8> {
+    let secs = input.timestamp();
+    let nanos = input.timestamp_subsec_nanos();
+    // ---- chrono represents 23:59:60 as 23:59:59 + 1s of nanoseconds
+    let (secs, nanos, in_leap_second) = match nanos >= 1_000_000_000 {
+        true => (secs + 1, nanos - 1_000_000_000, 1),
+        false => (secs, nanos, 0),
+    };
+    let misp_secs = secs + super::TAI_OFFSET + super::leap_seconds_before(secs) - in_leap_second;
+    match misp_secs < 0 {
+        true => 0u64,
+        false => misp_secs as u64 * 1_000_000_000 + nanos as u64,
+    }.to_be_bytes().to_vec()
+}
@@ -0,0 +1,154 @@
+//! Time codecs
+//!
+//! * [`dec::timestamp_us`] / [`enc::timestamp_us`]: a big-endian [`u64`] of
+//!   microseconds since the Unix epoch, e.g. MISB ST 0601 tag 2, as a
+//!   [`chrono::DateTime<chrono::Utc>`] (requires the `chrono` feature)
+//! * [`dec::systemtime_us`] / [`enc::systemtime_us`]: the same, as a
+//!   [`std::time::SystemTime`]
+//! * [`dec::misp_timestamp_ns`] / [`enc::misp_timestamp_ns`]: a big-endian
+//!   [`u64`] of nanoseconds of MISB ST 0603 MISP Time, which counts leap
+//!   seconds, unlike Unix time (requires the `chrono` feature)
+//!
+//! String formatted dates and times are parsed with the [`as_date!`](crate::as_date),
+//! [`as_time!`](crate::as_time) and [`as_datetime!`](crate::as_datetime) macros.
+//!
+//! # Leap seconds
+//!
+//! MISP Time is the number of SI seconds elapsed since the MISP epoch
+//! (1970-01-01T00:00:00 TAI), and follows TAI: it is GPS time plus
+//! 315 964 819 seconds. TAI was already [`TAI_OFFSET`] seconds ahead of UTC
+//! when leap seconds began in 1972, and each leap second inserted into UTC
+//! since then adds one more second, so MISP Time is Unix time plus 37
+//! seconds from 2017 onward. [`LEAP_SECONDS`] lists the Unix times at which
+//! each leap second took effect. A timestamp within an inserted leap second
+//! decodes to `23:59:60` (a nanosecond value of at least one second in
+//! [`chrono`]), and encodes back to the same MISP time.
+pub mod dec;
+pub mod enc;
+
+/// Seconds by which TAI, and so MISP Time, was ahead of UTC on 1972-01-01,
+/// before the first leap second
+pub const TAI_OFFSET: i64 = 10;
+
+/// Unix times (in seconds) of the instant following each leap second
+/// inserted into UTC, i.e. midnight after each `23:59:60`
+/// 
+/// Last updated for the leap second of 2016-12-31. No leap second has been
+/// announced since.
+pub const LEAP_SECONDS: [i64; 27] = [
+    78_796_800,     // 1972-07-01
+    94_694_400,     // 1973-01-01
+    126_230_400,    // 1974-01-01
+    157_766_400,    // 1975-01-01
+    189_302_400,    // 1976-01-01
+    220_924_800,    // 1977-01-01
+    252_460_800,    // 1978-01-01
+    283_996_800,    // 1979-01-01
+    315_532_800,    // 1980-01-01
+    362_793_600,    // 1981-07-01
+    394_329_600,    // 1982-07-01
+    425_865_600,    // 1983-07-01
+    489_024_000,    // 1985-07-01
+    567_993_600,    // 1988-01-01
+    631_152_000,    // 1990-01-01
+    662_688_000,    // 1991-01-01
+    709_948_800,    // 1992-07-01
+    741_484_800,    // 1993-07-01
+    773_020_800,    // 1994-07-01
+    820_454_400,    // 1996-01-01
+    867_715_200,    // 1997-07-01
+    915_148_800,    // 1999-01-01
+    1_136_073_600,  // 2006-01-01
+    1_230_768_000,  // 2009-01-01
+    1_341_100_800,  // 2012-07-01
+    1_435_708_800,  // 2015-07-01
+    1_483_228_800,  // 2017-01-01
+];
+
+/// Returns the number of leap seconds inserted before the Unix time `secs`
+pub fn leap_seconds_before(secs: i64) -> i64 {
+    LEAP_SECONDS.iter().take_while(|leap| **leap <= secs).count() as i64
+}
+
+#[macro_export]
+#[cfg(feature = "chrono")]
+/// Parses a string as a date, using [`chrono::NaiveDate::parse_from_str`]
+/// 
+/// Can be used directly in a `#[klv(dec = ...)]` attribute
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use tinyklv::prelude::*;
+/// 
+/// let mut input: &[u8] = b"2020-12-31";
+/// let input = &mut input;
+/// let len = 10;
+/// let date = tinyklv::as_date!(tinyklv::dec::binary::to_string_utf8, "%Y-%m-%d", len)(input);
+/// assert_eq!(date, Ok(chrono::NaiveDate::from_ymd_opt(2020, 12, 31).unwrap()));
+/// ```
+macro_rules! as_date {
+    ($str_parser:path, $date_fmt:tt, $len:expr $(,)*) => {
+        |input| -> winnow::PResult<chrono::NaiveDate> {
+            chrono::NaiveDate::parse_from_str(
+                &$str_parser($len)(input)?,
+                $date_fmt,
+            ).map_err(|_| tinyklv::err!())
+        }
+    };
+}
+
+#[macro_export]
+#[cfg(feature = "chrono")]
+/// Parses a string as a time, using [`chrono::NaiveTime::parse_from_str`]
+/// 
+/// Can be used directly in a `#[klv(dec = ...)]` attribute
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use tinyklv::prelude::*;
+/// 
+/// let mut input: &[u8] = b"12:34:56";
+/// let input = &mut input;
+/// let time = tinyklv::as_time!(tinyklv::dec::binary::to_string_utf8, "%H:%M:%S", 8)(input);
+/// assert_eq!(time, Ok(chrono::NaiveTime::from_hms_opt(12, 34, 56).unwrap()));
+/// ```
+macro_rules! as_time {
+    ($str_parser:path, $time_fmt:tt, $len:expr $(,)*) => {
+        |input| -> winnow::PResult<chrono::NaiveTime> {
+            chrono::NaiveTime::parse_from_str(
+                &$str_parser($len)(input)?,
+                $time_fmt,
+            ).map_err(|_| tinyklv::err!())
+        }
+    };
+}
+
+#[macro_export]
+#[cfg(feature = "chrono")]
+/// Parses a string as a datetime, using [`chrono::NaiveDateTime::parse_from_str`]
+/// 
+/// Can be used directly in a `#[klv(dec = ...)]` attribute
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use std::str::FromStr;
+/// use tinyklv::prelude::*;
+/// 
+/// let mut input: &[u8] = b"2020-12-31 12:34:56";
+/// let input = &mut input;
+/// let datetime = tinyklv::as_datetime!(tinyklv::dec::binary::to_string_utf8, "%Y-%m-%d %H:%M:%S", input.len())(input);
+/// assert_eq!(datetime, Ok(chrono::NaiveDate::from_ymd_opt(2020, 12, 31).unwrap().and_hms_opt(12, 34, 56).unwrap()));
+/// ```
+macro_rules! as_datetime {
+    ($str_parser:path, $datetime_fmt:tt, $len:expr $(,)*) => {
+        |input| -> winnow::PResult<chrono::NaiveDateTime> {
+            chrono::NaiveDateTime::parse_from_str(
+                &$str_parser($len)(input)?,
+                $datetime_fmt,
+            ).map_err(|_| tinyklv::err!())
+        }
+    };
+}
@@ -0,0 +1,110 @@
+//! Lookup of universal labels in a subset of the SMPTE Metadata Registers
+//!
+//! ***Requires the `dictionary` feature.***
+//!
+//! The registers (elements, groups and labels) are compiled at build time from
+//! the XML files in the `dictionary` directory of the crate.
+//!
+//! **Incomplete:** the published RP 210 / RP 224 registers are not shipped.
+//! The checked-in files are a small, hand-written sample of them: the MXF
+//! partition packs, and a few common elements and labels. Labels missing from
+//! it are not unknown to SMPTE, only to this table. Replace the files with the
+//! published registers to look up every label, see `dictionary/README.md`. Useful for annotating decoded output, e.g. the
+//! [`Routed::Unmatched`](crate::router::Routed::Unmatched) packets of a router.
+//!
+//! The table is not compressed: its strings are deduplicated into a single
+//! pool, which entries refer to by span.
+//!
+//! Labels are looked up ignoring their version byte and, for groups, their
+//! registry designator (byte 5), see [`normalize`].
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::codecs::ul::{dictionary, UniversalLabel};
+//!
+//! let ul: UniversalLabel = "06.0E.2B.34.02.05.01.01.0D.01.02.01.01.05.01.00".parse().unwrap();
+//! let entry = dictionary::lookup(&ul).unwrap();
+//! assert_eq!(entry.name, "Primer Pack");
+//! assert_eq!(entry.register, "Groups");
+//! assert_eq!(ul.name(), Some("Primer Pack"));
+//! ```
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::{UniversalLabel, VERSION_INDEX};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An entry of the SMPTE Metadata Registers
+pub struct Entry {
+    /// The name of the register, e.g. `Elements`, `Groups` or `Labels`
+    pub register: &'static str,
+    /// The name of the entry
+    pub name: &'static str,
+    /// The type of the value, for elements
+    pub ty: Option<&'static str>,
+    /// The definition of the entry
+    pub description: &'static str,
+}
+
+/// An entry of the generated table, with spans into [`STRINGS`]
+struct RawEntry {
+    key: [u8; 16],
+    register: (u32, u32),
+    name: (u32, u32),
+    ty: Option<(u32, u32)>,
+    description: (u32, u32),
+}
+
+include!(concat!(env!("OUT_DIR"), "/dictionary.rs"));
+
+/// Returns the string of a span of [`STRINGS`]
+fn string((offset, len): (u32, u32)) -> &'static str {
+    &STRINGS[offset as usize..(offset + len) as usize]
+}
+
+/// Returns the label as looked up in the table
+///
+/// The version byte is zeroed. For groups (category designator `0x02`), the
+/// registry designator encodes the coding form of the group, which the Groups
+/// Register leaves open with the wildcard `0x7F`, so it is set to `0x7F`
+pub fn normalize(ul: &UniversalLabel) -> [u8; 16] {
+    let mut key = *ul.as_bytes();
+    key[VERSION_INDEX] = 0;
+    if ul.category_designator() == 0x02 {
+        key[5] = 0x7F;
+    }
+    key
+}
+
+/// Returns the register entry of a universal label, see [`normalize`]
+pub fn lookup(ul: &UniversalLabel) -> Option<Entry> {
+    let key = normalize(ul);
+    let index = ENTRIES.binary_search_by_key(&key, |entry| entry.key).ok()?;
+    let entry = &ENTRIES[index];
+    Some(Entry {
+        register: string(entry.register),
+        name: string(entry.name),
+        ty: entry.ty.map(string),
+        description: string(entry.description),
+    })
+}
+
+/// Returns the number of entries in the table
+pub fn len() -> usize {
+    ENTRIES.len()
+}
+
+/// [`UniversalLabel`] implementation
+impl UniversalLabel {
+    /// Returns the register entry of the label, see [`lookup`]
+    pub fn entry(&self) -> Option<Entry> {
+        lookup(self)
+    }
+
+    /// Returns the name of the label in the registers, see [`lookup`]
+    pub fn name(&self) -> Option<&'static str> {
+        lookup(self).map(|entry| entry.name)
+    }
+}
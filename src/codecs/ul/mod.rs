@@ -0,0 +1,255 @@
+//! SMPTE universal labels
+//!
+//! A universal label (SMPTE ST 298 / ST 336) is a 16-byte key with a fixed
+//! structure:
+//!
+//! | Byte    | Meaning                                         |
+//! | ------- | ----------------------------------------------- |
+//! | `0..4`  | The prefix, always `06.0E.2B.34`                |
+//! | `4`     | The category designator (e.g. `02` for sets)    |
+//! | `5`     | The registry designator (e.g. `0B` for a local set with BER lengths) |
+//! | `6`     | The structure designator                        |
+//! | `7`     | The version of the registry                     |
+//! | `8..16` | The item designator                             |
+//!
+//! Receivers are supposed to ignore the version byte when matching a label,
+//! see [`UniversalLabel::matches_ignoring_version`]. A [`#[derive(Klv)]`](crate::Klv)
+//! struct on a byte stream may set its sentinel to a universal label URN,
+//! prefixed with `urn:smpte:ul:`, which is then recognized ignoring the
+//! version byte (other strings, even of hex digits, are plain sentinels):
+//!
+//! ```
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//!
+//! #[derive(Klv, Debug, PartialEq)]
+//! #[klv(
+//!     sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000",
+//!     key(dec = tinyklv::dec::binary::u8),
+//!     len(dec = tinyklv::dec::ber::ber_length),
+//! )]
+//! struct Uas {
+//!     #[klv(key = 0x02, dec = tinyklv::dec::binary::u8)]
+//!     version: u8,
+//! }
+//!
+//! // ---- version byte of 0x7F, instead of 0x01
+//! let data = [
+//!     0x06, 0x0E, 0x2B, 0x34, 0x02, 0x0B, 0x01, 0x7F, 0x0E, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00,
+//!     0x03, 0x02, 0x01, 0x13,
+//! ];
+//! assert_eq!(Uas::extract(&mut &data[..]), Ok(Uas { version: 19 }));
+//! assert!(Uas::SENTINEL_IGNORES_VERSION);
+//! ```
+
+#[cfg(feature = "dictionary")]
+pub mod dictionary;
+
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::Parser;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+/// The index of the version byte of a universal label
+pub const VERSION_INDEX: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Error of parsing a [`UniversalLabel`] from a string
+pub enum UniversalLabelError {
+    /// A character is not a hex digit or a separator
+    InvalidChar(char),
+    /// The label is not 16 bytes (32 hex digits) long
+    InvalidLength(usize),
+}
+/// [`UniversalLabelError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for UniversalLabelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UniversalLabelError::InvalidChar(c) => write!(f, "invalid character {:?} in universal label", c),
+            UniversalLabelError::InvalidLength(len) => write!(f, "universal label of {} hex digits, expected 32", len),
+        }
+    }
+}
+/// [`UniversalLabelError`] implementation of [`std::error::Error`]
+impl std::error::Error for UniversalLabelError {}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A 16-byte SMPTE universal label, see the [module documentation](self)
+///
+/// Parsed from hex, with the bytes optionally separated by `.` (and
+/// optionally prefixed with `urn:smpte:ul:`), and displayed in dotted hex.
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::codecs::ul::UniversalLabel;
+///
+/// let ul: UniversalLabel = "06.0E.2B.34.02.0B.01.01.0E.01.03.01.01.00.00.00".parse().unwrap();
+/// assert_eq!(ul.category_designator(), 0x02);
+/// assert_eq!(ul.registry_designator(), 0x0B);
+/// assert_eq!(ul.version(), 0x01);
+/// assert_eq!(ul.item_designator(), &[0x0E, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00]);
+/// assert_eq!(ul.to_string(), "06.0E.2B.34.02.0B.01.01.0E.01.03.01.01.00.00.00");
+///
+/// let other: UniversalLabel = "urn:smpte:ul:060e2b34.020b010a.0e010301.01000000".parse().unwrap();
+/// assert_ne!(ul, other);
+/// assert!(ul.matches_ignoring_version(&other));
+/// ```
+pub struct UniversalLabel([u8; 16]);
+/// [`UniversalLabel`] implementation
+impl UniversalLabel {
+    /// The first bytes of every SMPTE universal label
+    pub const PREFIX: [u8; 4] = [0x06, 0x0E, 0x2B, 0x34];
+
+    /// The length of a universal label, in bytes
+    pub const LEN: usize = 16;
+
+    /// Creates a universal label from its bytes
+    pub const fn new(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates a universal label from a slice of exactly 16 bytes
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    /// Returns the bytes of the label
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Returns `true` if the label starts with [`UniversalLabel::PREFIX`]
+    pub fn is_smpte(&self) -> bool {
+        self.0.starts_with(&Self::PREFIX)
+    }
+
+    /// Returns the category designator (byte 4), e.g. `0x01` for dictionary
+    /// items, `0x02` for groups (sets and packs)
+    pub const fn category_designator(&self) -> u8 {
+        self.0[4]
+    }
+
+    /// Returns the registry designator (byte 5), which for groups encodes
+    /// the coding form and length encoding
+    pub const fn registry_designator(&self) -> u8 {
+        self.0[5]
+    }
+
+    /// Returns the structure designator (byte 6)
+    pub const fn structure_designator(&self) -> u8 {
+        self.0[6]
+    }
+
+    /// Returns the version of the registry (byte 7)
+    pub const fn version(&self) -> u8 {
+        self.0[VERSION_INDEX]
+    }
+
+    /// Returns the item designator (bytes 8 to 15)
+    pub fn item_designator(&self) -> &[u8] {
+        &self.0[8..]
+    }
+
+    /// Returns the label, with its version byte set to `version`
+    pub const fn with_version(mut self, version: u8) -> Self {
+        self.0[VERSION_INDEX] = version;
+        self
+    }
+
+    /// Returns `true` if both labels are equal, except for their version byte
+    pub fn matches_ignoring_version(&self, other: &UniversalLabel) -> bool {
+        eq_ignoring_version(&self.0, &other.0)
+    }
+
+    /// Returns a parser of this label, which accepts any version byte
+    ///
+    /// Used as the sentinel parser of a [`#[derive(Klv)]`](crate::Klv) struct
+    /// whose sentinel is a universal label
+    pub fn literal_ignoring_version(self) -> impl Fn(&mut &[u8]) -> winnow::PResult<UniversalLabel> {
+        move |input| winnow::token::take(Self::LEN)
+            .verify(|bytes: &[u8]| eq_ignoring_version(bytes, &self.0))
+            .map(|bytes: &[u8]| UniversalLabel(bytes.try_into().unwrap_or_default()))
+            .parse_next(input)
+    }
+}
+/// [`UniversalLabel`] implementation of [`std::str::FromStr`]
+impl std::str::FromStr for UniversalLabel {
+    type Err = UniversalLabelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("urn:smpte:ul:").unwrap_or(s);
+        let mut digits = Vec::with_capacity(32);
+        for c in s.chars().filter(|c| *c != '.') {
+            match c.to_digit(16) {
+                Some(digit) => digits.push(digit as u8),
+                None => return Err(UniversalLabelError::InvalidChar(c)),
+            }
+        }
+        if digits.len() != 2 * Self::LEN {
+            return Err(UniversalLabelError::InvalidLength(digits.len()))
+        }
+        let mut bytes = [0; 16];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        Ok(Self(bytes))
+    }
+}
+/// [`UniversalLabel`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for UniversalLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, byte) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+/// [`UniversalLabel`] implementation of [`std::fmt::Debug`]
+impl std::fmt::Debug for UniversalLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UniversalLabel({})", self)
+    }
+}
+/// [`UniversalLabel`] implementation of [`From<[u8; 16]>`]
+impl From<[u8; 16]> for UniversalLabel {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+}
+/// [`UniversalLabel`] implementation of [`AsRef<[u8]>`]
+impl AsRef<[u8]> for UniversalLabel {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+/// [`UniversalLabel`] implementation of [`Decode`] for `&[u8]`
+impl Decode<&[u8]> for UniversalLabel {
+    fn decode(input: &mut &[u8]) -> winnow::PResult<Self> {
+        winnow::token::take(Self::LEN)
+            .map(|bytes: &[u8]| UniversalLabel(bytes.try_into().unwrap_or_default()))
+            .parse_next(input)
+    }
+}
+/// [`UniversalLabel`] implementation of [`EncodeValue`] for [`Vec<u8>`]
+impl EncodeValue<Vec<u8>> for UniversalLabel {
+    fn encode_value(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+/// Returns `true` if `bytes` is a universal label equal to `label`, except
+/// for the version byte
+pub(crate) fn eq_ignoring_version(bytes: &[u8], label: &[u8]) -> bool {
+    bytes.len() == label.len()
+        && bytes.len() == UniversalLabel::LEN
+        && bytes.iter().zip(label).enumerate().all(|(index, (a, b))| index == VERSION_INDEX || a == b)
+}
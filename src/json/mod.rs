@@ -0,0 +1,216 @@
+//! JSON conversion for KLV packets, using [`serde_json`]
+//!
+//! Requires the `serde` feature.
+//!
+//! Any type which implements [`serde::Serialize`] can be converted to a
+//! [`serde_json::Value`] keyed by field name, and any type which implements
+//! [`serde::de::DeserializeOwned`] can be converted back. Combined with the
+//! [`Decode`] and [`Encode`] implementations generated by [`crate::Klv`], this
+//! allows for the following round trip:
+//!
+//! ```text
+//! bytes --Decode--> T --Serialize--> JSON --Deserialize--> T --Encode--> bytes
+//! ```
+//!
+//! Fields annotated with `#[klv(name = "..", units = "..")]` additionally carry
+//! their metadata through [`KlvFieldMeta`], which is used by [`to_annotated_json`]
+//! and [`from_annotated_json`].
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//!
+//! #[derive(Klv, serde::Serialize, serde::Deserialize)]
+//! #[klv(
+//!     key(enc = tinyklv::enc::binary::u8, dec = tinyklv::dec::binary::u8),
+//!     len(enc = tinyklv::enc::binary::u8_from_usize, dec = tinyklv::dec::binary::u8_as_usize),
+//! )]
+//! struct Heading {
+//!     #[klv(
+//!         key = 0x05,
+//!         name = "Platform Heading Angle",
+//!         units = "deg",
+//!         enc = heading_encoder,
+//!         dec = tinyklv::dec::binary::be_u16,
+//!     )]
+//!     heading: u16,
+//! }
+//!
+//! fn heading_encoder(input: &u16) -> Vec<u8> {
+//!     tinyklv::enc::binary::be_u16(*input)
+//! }
+//!
+//! let json = tinyklv::json::decode_to_json::<_, Heading>(&mut &[0x05, 0x02, 0x01, 0x00][..]).unwrap();
+//! assert_eq!(json, serde_json::json!({ "heading": 256 }));
+//!
+//! let annotated = tinyklv::json::to_annotated_json(&Heading { heading: 256 }).unwrap();
+//! assert_eq!(annotated, serde_json::json!({
+//!     "heading": { "value": 256, "name": "Platform Heading Angle", "units": "deg" },
+//! }));
+//!
+//! let encoded: Vec<u8> = tinyklv::json::encode_value_from_json::<Heading, _>(json).unwrap();
+//! assert_eq!(encoded, vec![0x05, 0x02, 0x01, 0x00]);
+//! ```
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+// --------------------------------------------------
+// constants
+// --------------------------------------------------
+const VALUE_KEY: &str = "value";
+const NAME_KEY: &str = "name";
+const UNITS_KEY: &str = "units";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Metadata of a single field of a struct deriving [`crate::Klv`]
+///
+/// * `field` - The name of the field in the struct
+/// * `name` - The human-readable name, set using `#[klv(name = "..")]`
+/// * `units` - The units, set using `#[klv(units = "..")]`
+pub struct FieldMeta {
+    pub field: &'static str,
+    pub name: Option<&'static str>,
+    pub units: Option<&'static str>,
+}
+
+/// Trait for accessing the field metadata of a struct
+///
+/// Automatically implemented for structs deriving [`crate::Klv`] when the
+/// `serde` feature is enabled.
+pub trait KlvFieldMeta {
+    /// The metadata of every `#[klv(..)]` field, in declaration order
+    const FIELD_META: &'static [FieldMeta];
+
+    /// Returns the metadata of a field, by its name in the struct
+    fn field_meta(field: &str) -> Option<&'static FieldMeta> {
+        Self::FIELD_META.iter().find(|meta| meta.field == field)
+    }
+}
+
+#[derive(Debug)]
+/// Errors which can occur when converting to/from JSON
+pub enum JsonError {
+    /// The packet could not be decoded
+    Decode(winnow::error::ErrMode<winnow::error::ContextError>),
+    /// The value could not be converted to/from JSON
+    Json(serde_json::Error),
+    /// The JSON value was not of the expected shape
+    Shape(String),
+}
+/// [`JsonError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonError::Decode(e) => write!(f, "unable to decode packet: {}", e),
+            JsonError::Json(e) => write!(f, "unable to convert JSON: {}", e),
+            JsonError::Shape(e) => write!(f, "unexpected JSON shape: {}", e),
+        }
+    }
+}
+/// [`JsonError`] implementation of [`std::error::Error`]
+impl std::error::Error for JsonError {}
+/// [`JsonError`] implementation of [`From`] for [`serde_json::Error`]
+impl From<serde_json::Error> for JsonError {
+    fn from(e: serde_json::Error) -> Self {
+        JsonError::Json(e)
+    }
+}
+
+/// Converts a value to a [`serde_json::Value`], keyed by field name
+pub fn to_json<T: Serialize>(value: &T) -> Result<Value, JsonError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Decodes a `T` from the stream, and converts it to a [`serde_json::Value`]
+pub fn decode_to_json<S, T>(input: &mut S) -> Result<Value, JsonError>
+where
+    S: winnow::stream::Stream,
+    T: Decode<S> + Serialize,
+{
+    let value = T::decode(input).map_err(JsonError::Decode)?;
+    to_json(&value)
+}
+
+/// Extracts a `T` from the stream (see [`Extract`]), and converts it to a [`serde_json::Value`]
+pub fn extract_to_json<S, T>(input: &mut S) -> Result<Value, JsonError>
+where
+    S: winnow::stream::Stream,
+    T: Extract<S> + Serialize,
+{
+    let value = T::extract(input).map_err(JsonError::Decode)?;
+    to_json(&value)
+}
+
+/// Converts a value to a [`serde_json::Value`], where every field with
+/// metadata is wrapped as `{ "value": .., "name": .., "units": .. }`
+///
+/// Fields are matched by their name in the struct, so renaming fields
+/// using `serde` attributes will skip the annotation for those fields.
+pub fn to_annotated_json<T: Serialize + KlvFieldMeta>(value: &T) -> Result<Value, JsonError> {
+    let mut json = to_json(value)?;
+    let object = json
+        .as_object_mut()
+        .ok_or_else(|| JsonError::Shape("expected a JSON object".into()))?;
+    for meta in T::FIELD_META {
+        if meta.name.is_none() && meta.units.is_none() { continue }
+        if let Some(inner) = object.remove(meta.field) {
+            let mut annotated = serde_json::Map::new();
+            annotated.insert(VALUE_KEY.into(), inner);
+            if let Some(name) = meta.name { annotated.insert(NAME_KEY.into(), name.into()); }
+            if let Some(units) = meta.units { annotated.insert(UNITS_KEY.into(), units.into()); }
+            object.insert(meta.field.into(), Value::Object(annotated));
+        }
+    }
+    Ok(json)
+}
+
+/// Converts a [`serde_json::Value`], keyed by field name, back to a value
+pub fn from_json<T: DeserializeOwned>(json: Value) -> Result<T, JsonError> {
+    Ok(serde_json::from_value(json)?)
+}
+
+/// Converts a [`serde_json::Value`] produced by [`to_annotated_json`] back to a value
+pub fn from_annotated_json<T: DeserializeOwned + KlvFieldMeta>(mut json: Value) -> Result<T, JsonError> {
+    let object = json
+        .as_object_mut()
+        .ok_or_else(|| JsonError::Shape("expected a JSON object".into()))?;
+    for meta in T::FIELD_META {
+        if meta.name.is_none() && meta.units.is_none() { continue }
+        if let Some(Value::Object(annotated)) = object.get_mut(meta.field) {
+            let inner = annotated
+                .remove(VALUE_KEY)
+                .ok_or_else(|| JsonError::Shape(format!("missing `{}` for field `{}`", VALUE_KEY, meta.field)))?;
+            object.insert(meta.field.into(), inner);
+        }
+    }
+    from_json(json)
+}
+
+/// Converts a [`serde_json::Value`] back to a `T`, and encodes it using [`EncodeValue`]
+pub fn encode_value_from_json<T, O>(json: Value) -> Result<O, JsonError>
+where
+    O: EncodedOutput,
+    T: DeserializeOwned + EncodeValue<O>,
+{
+    Ok(from_json::<T>(json)?.encode_value())
+}
+
+/// Converts a [`serde_json::Value`] back to a `T`, and encodes it using [`Encode`]
+pub fn encode_from_json<T, O>(json: Value) -> Result<O, JsonError>
+where
+    O: EncodedOutput,
+    T: DeserializeOwned + Encode<O>,
+{
+    Ok(from_json::<T>(json)?.encode())
+}
@@ -0,0 +1,562 @@
+//! Walking the KLV triplets of MXF (SMPTE ST 377) files
+//!
+//! An MXF file is a sequence of KLV triplets, each keyed by a 16-byte
+//! universal label with a BER length: partition packs, primer packs, header
+//! metadata sets, index table segments, fill items and essence elements.
+//!
+//! * [`triplets`] iterates over the top-level triplets of a file
+//! * [`PartitionPack`] and [`Primer`] decode the packs of the same name
+//! * [`MetadataSet`] decodes a header metadata set, resolving its 2-byte
+//!   local tags to full universal labels through the [`Primer`]
+//! * [`MxfWalker`] does all of the above, keeping track of the current primer
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::prelude::*;
+//! use tinyklv::codecs::ul::UniversalLabel;
+//! use tinyklv::mxf::{MxfItem, MxfWalker, PartitionKind, PartitionPack, Primer, PrimerEntry};
+//!
+//! let instance_uid: UniversalLabel = "06.0E.2B.34.01.01.01.01.01.01.15.02.00.00.00.00".parse().unwrap();
+//! let primer = Primer { entries: vec![PrimerEntry { tag: 0x3C0A, ul: instance_uid }] };
+//!
+//! let mut file = Vec::new();
+//! file.extend(PartitionPack::default().encode_triplet(PartitionKind::Header, true, true));
+//! file.extend(primer.encode_triplet());
+//! // ---- a preface set, with an instance UID
+//! file.extend([0x06, 0x0E, 0x2B, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0D, 0x01, 0x01, 0x01, 0x01, 0x01, 0x2F, 0x00]);
+//! file.extend([0x14, 0x3C, 0x0A, 0x00, 0x10]);
+//! file.extend([0xAB; 16]);
+//!
+//! let items: Vec<_> = MxfWalker::new(&file).collect::<Result<_, _>>().unwrap();
+//! assert!(matches!(&items[0], MxfItem::Partition(partition) if partition.kind == PartitionKind::Header));
+//! assert_eq!(items[1], MxfItem::Primer(primer));
+//! match &items[2] {
+//!     MxfItem::Set(set) => assert_eq!(set.get(&instance_uid), Some(&[0xAB; 16][..])),
+//!     item => panic!("expected a metadata set, found {:?}", item),
+//! }
+//! ```
+
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::Parser;
+use winnow::error::ContextError;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+use crate::codecs::ul::{UniversalLabel, VERSION_INDEX};
+
+/// The name of the outermost packet of errors, see [`KlvError::packet`]
+const PACKET: &str = "MXF";
+
+/// The key of a partition pack, up to its kind and status (bytes 13 and 14)
+const PARTITION_PACK_KEY: [u8; 16] = [0x06, 0x0E, 0x2B, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0D, 0x01, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00];
+
+/// The key of a primer pack
+pub const PRIMER_PACK_KEY: UniversalLabel = UniversalLabel::new([0x06, 0x0E, 0x2B, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0D, 0x01, 0x02, 0x01, 0x01, 0x05, 0x01, 0x00]);
+
+/// The key of a random index pack
+pub const RANDOM_INDEX_PACK_KEY: UniversalLabel = UniversalLabel::new([0x06, 0x0E, 0x2B, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0D, 0x01, 0x02, 0x01, 0x01, 0x11, 0x01, 0x00]);
+
+/// The key of an index table segment
+pub const INDEX_TABLE_SEGMENT_KEY: UniversalLabel = UniversalLabel::new([0x06, 0x0E, 0x2B, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0D, 0x01, 0x02, 0x01, 0x01, 0x10, 0x01, 0x00]);
+
+/// The key of a KLV fill item
+pub const FILL_KEY: UniversalLabel = UniversalLabel::new([0x06, 0x0E, 0x2B, 0x34, 0x01, 0x01, 0x01, 0x02, 0x03, 0x01, 0x02, 0x10, 0x01, 0x00, 0x00, 0x00]);
+
+// --------------------------------------------------
+// triplets
+// --------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A top-level KLV triplet of an MXF file
+pub struct Triplet<'a> {
+    /// The offset of the key, into the iterated input
+    pub offset: usize,
+    /// The offset of the value, into the iterated input
+    pub value_offset: usize,
+    /// The key
+    pub key: UniversalLabel,
+    /// The value
+    pub value: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of a [`Triplet`], recognized by its key regardless of the version byte
+pub enum TripletKind {
+    /// A partition pack
+    Partition(PartitionKind),
+    /// A primer pack
+    Primer,
+    /// A header metadata set: a local set with 2-byte tags and 2-byte lengths
+    MetadataSet,
+    /// An index table segment
+    IndexTableSegment,
+    /// A random index pack
+    RandomIndexPack,
+    /// A KLV fill item
+    Fill,
+    /// A generic container essence element
+    Essence,
+    /// Anything else
+    Other,
+}
+
+/// [`Triplet`] implementation
+impl<'a> Triplet<'a> {
+    /// Returns the kind of the triplet, by its key
+    pub fn kind(&self) -> TripletKind {
+        let key = &self.key;
+        if let Some((kind, _)) = partition_of(key) {
+            return TripletKind::Partition(kind)
+        }
+        match key {
+            key if key.matches_ignoring_version(&PRIMER_PACK_KEY) => TripletKind::Primer,
+            key if key.matches_ignoring_version(&INDEX_TABLE_SEGMENT_KEY) => TripletKind::IndexTableSegment,
+            key if key.matches_ignoring_version(&RANDOM_INDEX_PACK_KEY) => TripletKind::RandomIndexPack,
+            key if key.matches_ignoring_version(&FILL_KEY) => TripletKind::Fill,
+            key if key.is_smpte() && key.category_designator() == 0x02 && key.registry_designator() == 0x53 => TripletKind::MetadataSet,
+            key if key.is_smpte() && key.as_bytes()[4..6] == [0x01, 0x02] && key.as_bytes()[8..12] == [0x0D, 0x01, 0x03, 0x01] => TripletKind::Essence,
+            _ => TripletKind::Other,
+        }
+    }
+
+    /// Decodes the triplet as a partition pack, or returns [`None`] if it is not one
+    pub fn partition(&self) -> Option<Result<Partition, KlvError>> {
+        let (kind, status) = partition_of(&self.key)?;
+        Some(self.decode_value("partition pack").map(|pack| Partition { kind, status, pack }))
+    }
+
+    /// Decodes the triplet as a primer pack, or returns [`None`] if it is not one
+    pub fn primer(&self) -> Option<Result<Primer, KlvError>> {
+        match self.kind() {
+            TripletKind::Primer => Some(self.decode_value("primer pack")),
+            _ => None,
+        }
+    }
+
+    /// Decodes the triplet as a header metadata set, resolving its local tags
+    /// through `primer`, or returns [`None`] if it is not one
+    pub fn metadata_set(&self, primer: &Primer) -> Option<Result<MetadataSet<'a>, KlvError>> {
+        match self.kind() {
+            TripletKind::MetadataSet => Some(
+                MetadataSet::decode(self.key, self.value, primer)
+                    .map_err(|err| err.within(PACKET, "metadata set", self.value_offset))
+            ),
+            _ => None,
+        }
+    }
+
+    /// Decodes the whole value as a `T`, placing errors at the value
+    fn decode_value<T: for<'b> Decode<&'b [u8]>>(&self, label: &'static str) -> Result<T, KlvError> {
+        decode_value(&mut &self.value[..], self.value.len(), T::decode)
+            .map_err(|err| err.within(PACKET, label, self.value_offset))
+    }
+}
+
+/// Returns an iterator over the top-level KLV triplets of an MXF file
+///
+/// Stops after the first error, e.g. a truncated triplet
+pub fn triplets(input: &[u8]) -> Triplets<'_> {
+    Triplets { input, position: 0 }
+}
+
+/// Iterator over the top-level KLV triplets of an MXF file, see [`triplets`]
+pub struct Triplets<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+/// [`Triplets`] implementation
+impl Triplets<'_> {
+    /// Returns the offset of the next byte to be read, into the iterated input
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+/// [`Triplets`] implementation of [`Iterator`]
+impl<'a> Iterator for Triplets<'a> {
+    type Item = Result<Triplet<'a>, KlvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.position;
+        let start = self.input.get(offset..).filter(|rest| !rest.is_empty())?;
+        let mut rest = start;
+        let triplet = key_len(&mut rest)
+            .map_err(|mut err| {
+                err.offset += offset;
+                err
+            })
+            .and_then(|(key, len)| {
+                let value_offset = offset + (start.len() - rest.len());
+                decode_value(&mut rest, len, winnow::token::rest::<_, ContextError>)
+                    .map(|value| Triplet { offset, value_offset, key, value })
+                    .map_err(|err| err.within(PACKET, "value", value_offset))
+            });
+        // ---- stop after an error
+        self.position = match triplet {
+            Ok(_) => offset + (start.len() - rest.len()),
+            Err(_) => self.input.len(),
+        };
+        Some(triplet)
+    }
+}
+
+/// Decodes the key and BER length of a triplet
+fn key_len(input: &mut &[u8]) -> Result<(UniversalLabel, usize), KlvError> {
+    let start = *input;
+    let key = UniversalLabel::decode(input).map_err(|err| KlvError::from(err).within(PACKET, "key", 0))?;
+    let len = crate::codecs::ber::dec::ber_length(input)
+        .map_err(|err| KlvError::from(err).within(PACKET, "length", start.len() - input.len()))?;
+    Ok((key, len))
+}
+
+// --------------------------------------------------
+// partitions
+// --------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of a partition, byte 13 of the key of its pack
+pub enum PartitionKind {
+    Header,
+    Body,
+    Footer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The status of a partition, byte 14 of the key of its pack
+pub struct PartitionStatus {
+    /// Whether the header metadata of the partition is finalized
+    pub closed: bool,
+    /// Whether the header metadata of the partition is complete
+    pub complete: bool,
+}
+
+/// Returns the kind and status of a partition pack key
+fn partition_of(key: &UniversalLabel) -> Option<(PartitionKind, PartitionStatus)> {
+    let bytes = key.as_bytes();
+    let prefix_matches = bytes[..13]
+        .iter()
+        .zip(&PARTITION_PACK_KEY[..13])
+        .enumerate()
+        .all(|(index, (a, b))| index == VERSION_INDEX || a == b);
+    if !prefix_matches || bytes[15] != 0x00 {
+        return None
+    }
+    let kind = match bytes[13] {
+        0x02 => PartitionKind::Header,
+        0x03 => PartitionKind::Body,
+        0x04 => PartitionKind::Footer,
+        _ => return None,
+    };
+    let status = match bytes[14] {
+        status @ 0x01..=0x04 => PartitionStatus { closed: status % 2 == 0, complete: status >= 0x03 },
+        _ => return None,
+    };
+    Some((kind, status))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A decoded partition pack, with the kind and status of its key
+pub struct Partition {
+    pub kind: PartitionKind,
+    pub status: PartitionStatus,
+    pub pack: PartitionPack,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The value of a partition pack (SMPTE ST 377-1, section 7.1)
+pub struct PartitionPack {
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// The KLV alignment grid size
+    pub kag_size: u32,
+    /// The offset of this partition, from the start of the header partition
+    pub this_partition: u64,
+    pub previous_partition: u64,
+    pub footer_partition: u64,
+    pub header_byte_count: u64,
+    pub index_byte_count: u64,
+    /// The stream id of the index table segments, or `0` if none
+    pub index_sid: u32,
+    pub body_offset: u64,
+    /// The stream id of the essence container, or `0` if none
+    pub body_sid: u32,
+    pub operational_pattern: UniversalLabel,
+    pub essence_containers: Vec<UniversalLabel>,
+}
+/// [`PartitionPack`] implementation
+impl PartitionPack {
+    /// Encodes the pack as a triplet, with the key of a partition of `kind`
+    pub fn encode_triplet(&self, kind: PartitionKind, closed: bool, complete: bool) -> Vec<u8> {
+        let mut key = PARTITION_PACK_KEY;
+        key[13] = match kind {
+            PartitionKind::Header => 0x02,
+            PartitionKind::Body => 0x03,
+            PartitionKind::Footer => 0x04,
+        };
+        key[14] = 0x01 + u8::from(closed) + 2 * u8::from(complete);
+        self.encode_value().into_klv(key.to_vec(), crate::codecs::ber::enc::ber_length_from_usize)
+    }
+}
+/// [`PartitionPack`] implementation of [`Decode`] for `&[u8]`
+impl Decode<&[u8]> for PartitionPack {
+    fn decode(input: &mut &[u8]) -> winnow::PResult<Self> {
+        use crate::codecs::binary::dec::{be_u16, be_u32, be_u64};
+        let start = *input;
+        Ok(PartitionPack {
+            major_version: item(input, start, "major_version", be_u16)?,
+            minor_version: item(input, start, "minor_version", be_u16)?,
+            kag_size: item(input, start, "kag_size", be_u32)?,
+            this_partition: item(input, start, "this_partition", be_u64)?,
+            previous_partition: item(input, start, "previous_partition", be_u64)?,
+            footer_partition: item(input, start, "footer_partition", be_u64)?,
+            header_byte_count: item(input, start, "header_byte_count", be_u64)?,
+            index_byte_count: item(input, start, "index_byte_count", be_u64)?,
+            index_sid: item(input, start, "index_sid", be_u32)?,
+            body_offset: item(input, start, "body_offset", be_u64)?,
+            body_sid: item(input, start, "body_sid", be_u32)?,
+            operational_pattern: item(input, start, "operational_pattern", UniversalLabel::decode)?,
+            essence_containers: item(input, start, "essence_containers", batch(UniversalLabel::decode))?,
+        })
+    }
+}
+/// [`PartitionPack`] implementation of [`EncodeValue`] for [`Vec<u8>`]
+impl EncodeValue<Vec<u8>> for PartitionPack {
+    fn encode_value(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        output.extend(self.major_version.to_be_bytes());
+        output.extend(self.minor_version.to_be_bytes());
+        output.extend(self.kag_size.to_be_bytes());
+        output.extend(self.this_partition.to_be_bytes());
+        output.extend(self.previous_partition.to_be_bytes());
+        output.extend(self.footer_partition.to_be_bytes());
+        output.extend(self.header_byte_count.to_be_bytes());
+        output.extend(self.index_byte_count.to_be_bytes());
+        output.extend(self.index_sid.to_be_bytes());
+        output.extend(self.body_offset.to_be_bytes());
+        output.extend(self.body_sid.to_be_bytes());
+        output.extend(self.operational_pattern.as_bytes());
+        output.extend(encode_batch(&self.essence_containers, |ul| ul.as_bytes().to_vec()));
+        output
+    }
+}
+
+// --------------------------------------------------
+// primer
+// --------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An entry of a [`Primer`], mapping a local tag to a universal label
+pub struct PrimerEntry {
+    pub tag: u16,
+    pub ul: UniversalLabel,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The value of a primer pack, mapping the 2-byte local tags of the header
+/// metadata sets of a partition to universal labels
+pub struct Primer {
+    pub entries: Vec<PrimerEntry>,
+}
+/// [`Primer`] implementation
+impl Primer {
+    /// Returns the universal label of a local tag
+    pub fn resolve(&self, tag: u16) -> Option<UniversalLabel> {
+        self.entries.iter().find(|entry| entry.tag == tag).map(|entry| entry.ul)
+    }
+
+    /// Returns the local tag of a universal label, ignoring its version byte
+    pub fn tag_of(&self, ul: &UniversalLabel) -> Option<u16> {
+        self.entries.iter().find(|entry| entry.ul.matches_ignoring_version(ul)).map(|entry| entry.tag)
+    }
+
+    /// Encodes the primer as a primer pack triplet
+    pub fn encode_triplet(&self) -> Vec<u8> {
+        self.encode_value().into_klv(PRIMER_PACK_KEY.encode_value(), crate::codecs::ber::enc::ber_length_from_usize)
+    }
+}
+/// [`Primer`] implementation of [`Decode`] for `&[u8]`
+impl Decode<&[u8]> for Primer {
+    fn decode(input: &mut &[u8]) -> winnow::PResult<Self> {
+        let entry = |input: &mut &[u8]| -> winnow::PResult<PrimerEntry> {
+            let tag = crate::codecs::binary::dec::be_u16(input)?;
+            let ul = UniversalLabel::decode(input)?;
+            Ok(PrimerEntry { tag, ul })
+        };
+        let start = *input;
+        Ok(Primer { entries: item(input, start, "entries", batch(entry))? })
+    }
+}
+/// [`Primer`] implementation of [`EncodeValue`] for [`Vec<u8>`]
+impl EncodeValue<Vec<u8>> for Primer {
+    fn encode_value(&self) -> Vec<u8> {
+        encode_batch(&self.entries, |entry| {
+            let mut output = entry.tag.to_be_bytes().to_vec();
+            output.extend(entry.ul.as_bytes());
+            output
+        })
+    }
+}
+
+// --------------------------------------------------
+// header metadata
+// --------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An item of a [`MetadataSet`]
+pub struct LocalItem<'a> {
+    /// The local tag
+    pub tag: u16,
+    /// The universal label of the local tag, or [`None`] if it is not in the primer
+    pub ul: Option<UniversalLabel>,
+    /// The value
+    pub value: &'a [u8],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A header metadata set, with its local tags resolved through a [`Primer`]
+pub struct MetadataSet<'a> {
+    /// The key of the set, e.g. the universal label of a `Preface`
+    pub key: UniversalLabel,
+    /// The items of the set, in order
+    pub items: Vec<LocalItem<'a>>,
+}
+/// [`MetadataSet`] implementation
+impl<'a> MetadataSet<'a> {
+    /// Decodes the value of a set of `key`, of 2-byte local tags and 2-byte
+    /// lengths, resolving the tags through `primer`
+    pub fn decode(key: UniversalLabel, value: &'a [u8], primer: &Primer) -> Result<Self, KlvError> {
+        use crate::codecs::binary::dec::be_u16;
+        let mut input = value;
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            let offset = value.len() - input.len();
+            let (tag, len) = (be_u16, be_u16)
+                .parse_next(&mut input)
+                .map_err(|err| KlvError::from(err).within(PACKET, "local tag", offset))?;
+            let value = decode_value(&mut input, len as usize, winnow::token::rest::<_, ContextError>)
+                .map_err(|err| err.within(PACKET, "local tag", offset + 4))?;
+            items.push(LocalItem { tag, ul: primer.resolve(tag), value });
+        }
+        Ok(Self { key, items })
+    }
+
+    /// Returns the value of the item of a universal label, ignoring its version byte
+    pub fn get(&self, ul: &UniversalLabel) -> Option<&'a [u8]> {
+        self.items
+            .iter()
+            .find(|item| item.ul.is_some_and(|item_ul| item_ul.matches_ignoring_version(ul)))
+            .map(|item| item.value)
+    }
+
+    /// Returns the value of the item of a local tag
+    pub fn get_by_tag(&self, tag: u16) -> Option<&'a [u8]> {
+        self.items.iter().find(|item| item.tag == tag).map(|item| item.value)
+    }
+}
+
+// --------------------------------------------------
+// walker
+// --------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Item of an [`MxfWalker`]
+pub enum MxfItem<'a> {
+    /// A partition pack
+    Partition(Partition),
+    /// A primer pack, which resolves the local tags of the following sets
+    Primer(Primer),
+    /// A header metadata set
+    Set(MetadataSet<'a>),
+    /// Any other triplet, see [`Triplet::kind`]
+    Other(Triplet<'a>),
+}
+
+/// Iterator over the decoded top-level triplets of an MXF file
+///
+/// Header metadata sets are resolved through the last primer pack. Stops
+/// after the first error of the [`triplets`], but continues after a pack or
+/// set which fails to decode.
+pub struct MxfWalker<'a> {
+    triplets: Triplets<'a>,
+    primer: Primer,
+}
+/// [`MxfWalker`] implementation
+impl<'a> MxfWalker<'a> {
+    /// Walks the triplets of `input`
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { triplets: triplets(input), primer: Primer::default() }
+    }
+
+    /// Returns the last primer pack, or an empty primer if none was found yet
+    pub fn primer(&self) -> &Primer {
+        &self.primer
+    }
+}
+/// [`MxfWalker`] implementation of [`Iterator`]
+impl<'a> Iterator for MxfWalker<'a> {
+    type Item = Result<MxfItem<'a>, KlvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let triplet = match self.triplets.next()? {
+            Ok(triplet) => triplet,
+            Err(err) => return Some(Err(err)),
+        };
+        if let Some(partition) = triplet.partition() {
+            return Some(partition.map(MxfItem::Partition))
+        }
+        if let Some(primer) = triplet.primer() {
+            return Some(primer.map(|primer| {
+                self.primer = primer.clone();
+                MxfItem::Primer(primer)
+            }))
+        }
+        if let Some(set) = triplet.metadata_set(&self.primer) {
+            return Some(set.map(MxfItem::Set))
+        }
+        Some(Ok(MxfItem::Other(triplet)))
+    }
+}
+
+// --------------------------------------------------
+// helpers
+// --------------------------------------------------
+/// Decodes an item of a pack, naming the field and placing the error at its
+/// offset from `start`
+fn item<'a, O>(
+    input: &mut &'a [u8],
+    start: &'a [u8],
+    field: &'static str,
+    mut parser: impl FnMut(&mut &'a [u8]) -> winnow::PResult<O>,
+) -> winnow::PResult<O> {
+    let offset = start.len() - input.len();
+    parser(input).map_err(|err| {
+        let mut err = KlvError::from(err).field(field);
+        err.offset += offset;
+        err.into_winnow(input)
+    })
+}
+
+/// Returns a decoder of a batch: a 4-byte count and a 4-byte item length,
+/// followed by the items
+///
+/// Items are decoded from a sub-slice of the item length, so that items
+/// longer than expected are skipped over
+fn batch<'a, O>(mut item: impl FnMut(&mut &'a [u8]) -> winnow::PResult<O>) -> impl FnMut(&mut &'a [u8]) -> winnow::PResult<Vec<O>> {
+    move |input| {
+        use crate::codecs::binary::dec::be_u32;
+        let count = be_u32(input)? as usize;
+        let len = be_u32(input)? as usize;
+        (0..count)
+            .map(|_| winnow::token::take(len).parse_next(input).and_then(|mut value| item(&mut value)))
+            .collect()
+    }
+}
+
+/// Encodes a batch, see [`batch`]
+fn encode_batch<T>(items: &[T], mut item: impl FnMut(&T) -> Vec<u8>) -> Vec<u8> {
+    let encoded: Vec<_> = items.iter().map(&mut item).collect();
+    let len = encoded.first().map_or(0, Vec::len);
+    let mut output = (encoded.len() as u32).to_be_bytes().to_vec();
+    output.extend((len as u32).to_be_bytes());
+    output.extend(encoded.into_iter().flatten());
+    output
+}
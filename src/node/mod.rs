@@ -0,0 +1,281 @@
+//! Schema-less Key-Length-Value trees
+//!
+//! Every decode path of [`crate::Klv`] requires a derived struct. For inspecting,
+//! diffing or proxying packets, it is often more convenient to parse a packet
+//! into a dynamic tree of [`KlvNode`]s, where every value is either left as
+//! raw bytes or, when it is known to be a nested set, parsed into child nodes.
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::prelude::*;
+//! use tinyklv::node::{KlvNode, KlvValue};
+//!
+//! let packet: &[u8] = &[
+//!     0x01, 0x02, 0xAA, 0xBB,         // key: 0x01, len: 2
+//!     0x30, 0x04,                     // key: 0x30, len: 4 (nested set)
+//!         0x03, 0x02, 0xCC, 0xDD,     // key: 0x03, len: 2
+//! ];
+//! let nodes = KlvNode::parse_all(
+//!     &mut &packet[..],
+//!     tinyklv::dec::ber::ber_oid::<u64>,
+//!     tinyklv::dec::ber::ber_length,
+//!     |key: &u64| *key == 0x30,
+//! ).unwrap();
+//!
+//! assert_eq!(nodes.len(), 2);
+//! assert_eq!(nodes[0].value, KlvValue::Raw(&[0xAA, 0xBB]));
+//! match &nodes[1].value {
+//!     KlvValue::Set(children) => assert_eq!(children[0].key, 0x03),
+//!     KlvValue::Raw(_) => panic!("expected a nested set"),
+//! }
+//!
+//! // re-encoding reproduces the input
+//! let encoded = KlvNode::encode_all(
+//!     &nodes,
+//!     |key| tinyklv::enc::ber::ber_oid(&key),
+//!     |len| tinyklv::enc::ber::ber_length(&len),
+//! );
+//! assert_eq!(encoded, packet);
+//! ```
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::error::{AddContext, ContextError, ErrMode, StrContext};
+use winnow::stream::{Offset, Stream};
+use winnow::token::take;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A single key-length-value triplet, parsed without a schema
+///
+/// * `key` - The decoded key, of whatever type the key decoder returns
+/// * `len` - The decoded length of the value, in bytes
+/// * `value` - Either the raw value bytes, or the nested set of [`KlvNode`]s
+///
+/// The key and length bytes of the input are kept, see [`KlvNode::header`]
+///
+/// See the [module-level documentation](crate::node) for an example
+pub struct KlvNode<'a, K> {
+    pub key: K,
+    pub len: usize,
+    pub value: KlvValue<'a, K>,
+    /// The key and length bytes of the input, with the key and length they
+    /// were decoded to
+    parsed: Option<(KlvHeader<'a>, K, usize)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The key and length bytes of a parsed [`KlvNode`], as found in the input
+pub struct KlvHeader<'a> {
+    pub key: &'a [u8],
+    pub len: &'a [u8],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The value of a [`KlvNode`]
+pub enum KlvValue<'a, K> {
+    /// The value bytes, borrowed from the input stream
+    Raw(&'a [u8]),
+    /// A nested set, where the value bytes were parsed into child nodes
+    Set(Vec<KlvNode<'a, K>>),
+}
+
+/// The maximum nesting depth of sets, past which parsing fails
+///
+/// Guards against inputs that nest a set key over and over, which would
+/// otherwise overflow the stack
+pub const MAX_DEPTH: usize = 64;
+
+/// [`KlvNode`] implementation
+impl<'a, K> KlvNode<'a, K> {
+    /// Creates a node which was not parsed, and so has no [`KlvNode::header`]
+    ///
+    /// `len` is the length of a raw value, and `0` for a nested set, whose
+    /// length is computed from its children when encoding
+    pub fn new(key: K, value: KlvValue<'a, K>) -> Self {
+        let len = match &value {
+            KlvValue::Raw(raw) => raw.len(),
+            KlvValue::Set(_) => 0,
+        };
+        Self { key, len, value, parsed: None }
+    }
+
+    /// Parses a single [`KlvNode`] from the input
+    ///
+    /// # Arguments
+    ///
+    /// * `key_dec` - The key decoder, e.g. [`crate::codecs::ber::dec::ber_oid`]
+    /// * `len_dec` - The length decoder, e.g. [`crate::codecs::ber::dec::ber_length`]
+    /// * `is_set` - Returns `true` for keys whose value is a nested set, which is
+    ///   then recursively parsed using the same key and length decoders, up to
+    ///   [`MAX_DEPTH`] levels deep
+    pub fn parse<KD, LD, N>(input: &mut &'a [u8], key_dec: KD, len_dec: LD, is_set: N) -> winnow::PResult<Self>
+    where
+        K: Clone,
+        KD: Fn(&mut &'a [u8]) -> winnow::PResult<K>,
+        LD: Fn(&mut &'a [u8]) -> winnow::PResult<usize>,
+        N: Fn(&K) -> bool,
+    {
+        parse_node(input, &key_dec, &len_dec, &is_set, 0)
+    }
+
+    /// Parses [`KlvNode`]s until the input is exhausted
+    ///
+    /// Fails if any trailing bytes can not be parsed as a node.
+    ///
+    /// See [`KlvNode::parse`] for the arguments.
+    pub fn parse_all<KD, LD, N>(input: &mut &'a [u8], key_dec: KD, len_dec: LD, is_set: N) -> winnow::PResult<Vec<Self>>
+    where
+        K: Clone,
+        KD: Fn(&mut &'a [u8]) -> winnow::PResult<K>,
+        LD: Fn(&mut &'a [u8]) -> winnow::PResult<usize>,
+        N: Fn(&K) -> bool,
+    {
+        parse_nodes(input, &key_dec, &len_dec, &is_set, 0)
+    }
+
+    /// Returns the children of this node, if it is a nested set
+    pub fn children(&self) -> Option<&[KlvNode<'a, K>]> {
+        match &self.value {
+            KlvValue::Set(children) => Some(children),
+            KlvValue::Raw(_) => None,
+        }
+    }
+
+    /// Returns the raw value bytes of this node, if it is not a nested set
+    pub fn raw(&self) -> Option<&'a [u8]> {
+        match self.value {
+            KlvValue::Raw(raw) => Some(raw),
+            KlvValue::Set(_) => None,
+        }
+    }
+
+    /// Returns the key and length bytes of the input, if this node was parsed
+    ///
+    /// These are re-emitted as-is when re-encoding, see [`KlvNode::encode_with`]
+    pub fn header(&self) -> Option<KlvHeader<'a>> {
+        self.parsed.as_ref().map(|(header, _, _)| *header)
+    }
+
+    /// Sets the key of this node, and drops the [`KlvNode::header`], so that
+    /// both the key and the length are re-encoded with the encoders
+    pub fn set_key(&mut self, key: K) {
+        self.key = key;
+        self.parsed = None;
+    }
+}
+
+/// [`KlvNode`] implementation for re-encoding
+impl<K: Clone + PartialEq> KlvNode<'_, K> {
+    /// Encodes this node, and all of its children, back to bytes
+    ///
+    /// The key and length bytes of the input (see [`KlvNode::header`]) are
+    /// re-emitted as-is while the key, and the length of the value, are
+    /// unchanged, so that an unmodified tree is byte-identical to its input,
+    /// even with non-minimal encodings such as a long-form BER length
+    /// `0x83 00 00 10`. Otherwise the key and length are encoded with the
+    /// provided encoders, and the lengths of nested sets are recomputed from
+    /// their children, so that edited trees stay consistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_enc` - The key encoder
+    /// * `len_enc` - The length encoder
+    pub fn encode_with<KE, LE>(&self, key_enc: KE, len_enc: LE) -> Vec<u8>
+    where
+        KE: Fn(K) -> Vec<u8>,
+        LE: Fn(usize) -> Vec<u8>,
+    {
+        let mut output = Vec::new();
+        encode_node(self, &key_enc, &len_enc, &mut output);
+        output
+    }
+
+    /// Encodes a list of nodes back to bytes, see [`KlvNode::encode_with`]
+    pub fn encode_all<KE, LE>(nodes: &[Self], key_enc: KE, len_enc: LE) -> Vec<u8>
+    where
+        KE: Fn(K) -> Vec<u8>,
+        LE: Fn(usize) -> Vec<u8>,
+    {
+        let mut output = Vec::new();
+        nodes.iter().for_each(|node| encode_node(node, &key_enc, &len_enc, &mut output));
+        output
+    }
+}
+
+/// Parses a single node at nesting depth `depth`, recursing into nested sets
+fn parse_node<'a, K, KD, LD, N>(input: &mut &'a [u8], key_dec: &KD, len_dec: &LD, is_set: &N, depth: usize) -> winnow::PResult<KlvNode<'a, K>>
+where
+    K: Clone,
+    KD: Fn(&mut &'a [u8]) -> winnow::PResult<K>,
+    LD: Fn(&mut &'a [u8]) -> winnow::PResult<usize>,
+    N: Fn(&K) -> bool,
+{
+    let start = *input;
+    let key = key_dec(input)?;
+    let key_bytes = &start[..input.offset_from(&start)];
+    let len_start = *input;
+    let len = len_dec(input)?;
+    let len_bytes = &len_start[..input.offset_from(&len_start)];
+    let mut raw: &'a [u8] = take(len).parse_next(input)?;
+    let value = match is_set(&key) {
+        true if depth >= MAX_DEPTH => {
+            let checkpoint = raw.checkpoint();
+            return Err(ErrMode::Backtrack(ContextError::new().add_context(
+                &raw,
+                &checkpoint,
+                StrContext::Label("sets nested deeper than the maximum depth"),
+            )))
+        },
+        true => KlvValue::Set(parse_nodes(&mut raw, key_dec, len_dec, is_set, depth + 1)?),
+        false => KlvValue::Raw(raw),
+    };
+    let parsed = Some((KlvHeader { key: key_bytes, len: len_bytes }, key.clone(), len));
+    Ok(KlvNode { key, len, value, parsed })
+}
+
+/// Parses nodes at nesting depth `depth` until the input is exhausted
+fn parse_nodes<'a, K, KD, LD, N>(input: &mut &'a [u8], key_dec: &KD, len_dec: &LD, is_set: &N, depth: usize) -> winnow::PResult<Vec<KlvNode<'a, K>>>
+where
+    K: Clone,
+    KD: Fn(&mut &'a [u8]) -> winnow::PResult<K>,
+    LD: Fn(&mut &'a [u8]) -> winnow::PResult<usize>,
+    N: Fn(&K) -> bool,
+{
+    let mut nodes = Vec::new();
+    while !input.is_empty() {
+        nodes.push(parse_node(input, key_dec, len_dec, is_set, depth)?);
+    }
+    Ok(nodes)
+}
+
+/// Encodes a single node into `output`, recursing into nested sets
+fn encode_node<K, KE, LE>(node: &KlvNode<'_, K>, key_enc: &KE, len_enc: &LE, output: &mut Vec<u8>)
+where
+    K: Clone + PartialEq,
+    KE: Fn(K) -> Vec<u8>,
+    LE: Fn(usize) -> Vec<u8>,
+{
+    let value = match &node.value {
+        KlvValue::Raw(raw) => raw.to_vec(),
+        KlvValue::Set(children) => {
+            let mut value = Vec::new();
+            children.iter().for_each(|child| encode_node(child, key_enc, len_enc, &mut value));
+            value
+        },
+    };
+    match &node.parsed {
+        Some((header, key, _)) if *key == node.key => output.extend(header.key),
+        _ => output.extend(key_enc(node.key.clone())),
+    }
+    match &node.parsed {
+        Some((header, _, len)) if *len == node.len && *len == value.len() => output.extend(header.len),
+        _ => output.extend(len_enc(value.len())),
+    }
+    output.extend(value);
+}
@@ -0,0 +1,590 @@
+//! Extracting KLV from captured UDP traffic
+//!
+//! ***Requires the `pcap` feature.***
+//!
+//! Reads classic pcap and pcapng captures, keeps the UDP datagrams sent to a
+//! given address and port, and decodes the KLV they carry, either raw or
+//! inside an MPEG transport stream:
+//!
+//! * [`Capture`] reads a capture from disk or memory, and iterates over its
+//!   [`Frame`]s
+//! * [`UdpFilter`] selects the datagrams by destination, and sets their
+//!   [`Encapsulation`]
+//! * [`Capture::payloads`] yields the KLV bytes of the selected datagrams
+//! * [`Capture::extract`] decodes them into timestamped packets of a type
+//!
+//! Ethernet (with VLAN tags), Linux cooked, BSD loopback and raw IP link
+//! types are supported, over IPv4 and IPv6. Fragmented IP datagrams are not
+//! reassembled, and are skipped.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//! use tinyklv::pcap::{Capture, UdpFilter};
+//!
+//! #[derive(Klv, Debug)]
+//! #[klv(
+//!     sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000",
+//!     key(dec = tinyklv::dec::binary::u8),
+//!     len(dec = tinyklv::dec::ber::ber_length),
+//! )]
+//! struct Uas {
+//!     #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u64)]
+//!     timestamp: u64,
+//! }
+//!
+//! let capture = Capture::open("flight.pcapng").unwrap();
+//! let filter = UdpFilter::new().address("239.1.1.1".parse().unwrap()).port(5000);
+//! for captured in capture.extract::<Uas>(filter) {
+//!     let captured = captured.unwrap();
+//!     println!("{:?} from {}: {:?}", captured.timestamp, captured.source, captured.packet);
+//! }
+//! ```
+
+mod mpegts;
+mod net;
+
+// --------------------------------------------------
+// std
+// --------------------------------------------------
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+/// The block type of a pcapng section header block
+const SECTION_HEADER: u32 = 0x0A0D0D0A;
+/// The block type of a pcapng interface description block
+const INTERFACE_DESCRIPTION: u32 = 0x00000001;
+/// The block type of a pcapng simple packet block
+const SIMPLE_PACKET: u32 = 0x00000003;
+/// The block type of a pcapng enhanced packet block
+const ENHANCED_PACKET: u32 = 0x00000006;
+/// The byte-order magic of a pcapng section header block
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+
+#[derive(Debug)]
+/// Error of reading a [`Capture`]
+pub enum PcapError {
+    /// The capture could not be read from disk
+    Io(std::io::Error),
+    /// The capture does not start with a pcap or pcapng magic number
+    Magic(u32),
+    /// A record or block runs past the end of the capture
+    Truncated {
+        offset: usize,
+    },
+    /// A pcapng block has an invalid length
+    BlockLength {
+        offset: usize,
+        len: u32,
+    },
+    /// A pcapng packet block refers to an undescribed interface
+    Interface {
+        offset: usize,
+        id: u32,
+    },
+    /// A timestamp, with the offset of its interface, is out of the range
+    /// of [`SystemTime`]
+    Timestamp {
+        offset: usize,
+    },
+}
+/// [`PcapError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for PcapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PcapError::Io(err) => write!(f, "unable to read capture: {}", err),
+            PcapError::Magic(magic) => write!(f, "unknown capture magic number {:#010X}", magic),
+            PcapError::Truncated { offset } => write!(f, "capture truncated at offset {}", offset),
+            PcapError::BlockLength { offset, len } => write!(f, "invalid pcapng block length {} at offset {}", len, offset),
+            PcapError::Interface { offset, id } => write!(f, "unknown pcapng interface {} at offset {}", id, offset),
+            PcapError::Timestamp { offset } => write!(f, "timestamp out of range at offset {}", offset),
+        }
+    }
+}
+/// [`PcapError`] implementation of [`std::error::Error`]
+impl std::error::Error for PcapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PcapError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+/// [`PcapError`] implementation of [`From<std::io::Error>`]
+impl From<std::io::Error> for PcapError {
+    fn from(err: std::io::Error) -> Self {
+        PcapError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The byte order of a capture
+enum Endian {
+    Little,
+    Big,
+}
+/// [`Endian`] implementation
+impl Endian {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self {
+            Endian::Little => u16::from_le_bytes(bytes),
+            Endian::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            Endian::Little => u32::from_le_bytes(bytes),
+            Endian::Big => u32::from_be_bytes(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The resolution of timestamps, as `10^-n` or `2^-n` seconds
+enum Resolution {
+    Decimal(u8),
+    Binary(u8),
+}
+/// [`Resolution`] implementation
+impl Resolution {
+    /// Returns the time since the UNIX epoch of a timestamp in this resolution,
+    /// if it is within the range of [`SystemTime`]
+    fn time(self, units: u64) -> Option<SystemTime> {
+        let per_second: u128 = match self {
+            Resolution::Decimal(n) => 10u128.pow(n.min(19) as u32),
+            Resolution::Binary(n) => 1u128 << n.min(63),
+        };
+        let units = units as u128;
+        let nanos = (units % per_second) * 1_000_000_000 / per_second;
+        UNIX_EPOCH.checked_add(Duration::new((units / per_second) as u64, nanos as u32))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The format of a capture
+enum Format {
+    /// Classic pcap, with a single link type
+    Pcap {
+        endian: Endian,
+        nanos: bool,
+        link_type: u16,
+    },
+    /// pcapng
+    PcapNg,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A pcap or pcapng capture, held in memory
+pub struct Capture {
+    data: Vec<u8>,
+    format: Format,
+}
+/// [`Capture`] implementation
+impl Capture {
+    /// Reads a capture from disk
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, PcapError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Reads a capture from its bytes
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, PcapError> {
+        let magic = data.get(..4).ok_or(PcapError::Truncated { offset: 0 })?;
+        let magic = u32::from_le_bytes([magic[0], magic[1], magic[2], magic[3]]);
+        let (endian, nanos) = match magic {
+            SECTION_HEADER => return Ok(Self { data, format: Format::PcapNg }),
+            0xA1B2C3D4 => (Endian::Little, false),
+            0xD4C3B2A1 => (Endian::Big, false),
+            0xA1B23C4D => (Endian::Little, true),
+            0x4D3CB2A1 => (Endian::Big, true),
+            magic => return Err(PcapError::Magic(magic)),
+        };
+        let header = data.get(..24).ok_or(PcapError::Truncated { offset: 0 })?;
+        let link_type = endian.u32(&header[20..]) as u16;
+        Ok(Self { format: Format::Pcap { endian, nanos, link_type }, data })
+    }
+
+    /// Iterates over the frames of the capture, ending after an error
+    pub fn frames(&self) -> Frames<'_> {
+        let position = match self.format {
+            Format::Pcap { .. } => 24,
+            Format::PcapNg => 0,
+        };
+        Frames { data: &self.data, format: self.format, position, endian: Endian::Little, interfaces: Vec::new() }
+    }
+
+    /// Iterates over the KLV bytes of the datagrams selected by `filter`
+    pub fn payloads(&self, filter: UdpFilter) -> Payloads<'_> {
+        Payloads {
+            frames: self.frames(),
+            filter,
+            demuxer: mpegts::Demuxer::new(filter.pid),
+            queue: VecDeque::new(),
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Iterates over the packets of type `T` in the datagrams selected by
+    /// `filter`, see [`Captured`]
+    pub fn extract<T>(&self, filter: UdpFilter) -> Captures<'_, T>
+    where
+        T: KlvSchema + for<'a> Seek<&'a [u8]> + for<'a> Decode<&'a [u8]>,
+    {
+        Captures { payloads: self.payloads(filter), queue: VecDeque::new() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A frame of a [`Capture`]
+pub struct Frame<'a> {
+    /// The capture time of the frame
+    pub timestamp: SystemTime,
+    /// The link type of the frame, e.g. `1` for Ethernet
+    pub link_type: u16,
+    /// The captured bytes of the frame, possibly truncated to the snapshot length
+    pub data: &'a [u8],
+}
+/// [`Frame`] implementation
+impl<'a> Frame<'a> {
+    /// Returns the UDP datagram of the frame, if any
+    pub fn udp(&self) -> Option<UdpDatagram<'a>> {
+        net::udp(self.link_type, self.data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A UDP datagram of a [`Frame`]
+pub struct UdpDatagram<'a> {
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    pub payload: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An interface of a pcapng section
+struct Interface {
+    link_type: u16,
+    resolution: Resolution,
+    offset: i64,
+}
+
+/// Iterator over the [`Frame`]s of a [`Capture`]
+///
+/// Non-packet blocks of pcapng captures are skipped. Simple packet blocks,
+/// which have no timestamp, are read at the UNIX epoch.
+pub struct Frames<'a> {
+    data: &'a [u8],
+    format: Format,
+    position: usize,
+    /// The byte order of the current pcapng section
+    endian: Endian,
+    /// The interfaces of the current pcapng section
+    interfaces: Vec<Interface>,
+}
+/// [`Frames`] implementation of [`Iterator`]
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Frame<'a>, PcapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.data.len() {
+            let result = match self.format {
+                Format::Pcap { endian, nanos, link_type } => self.record(endian, nanos, link_type).map(Some),
+                Format::PcapNg => self.block(),
+            };
+            match result {
+                Ok(Some(frame)) => return Some(Ok(frame)),
+                Ok(None) => continue,
+                Err(err) => {
+                    self.position = self.data.len();
+                    return Some(Err(err))
+                },
+            }
+        }
+        None
+    }
+}
+/// [`Frames`] implementation
+impl<'a> Frames<'a> {
+    /// Returns `len` bytes at `start`, or an error at the current position
+    fn bytes(&self, start: usize, len: usize) -> Result<&'a [u8], PcapError> {
+        self.data.get(start..start + len).ok_or(PcapError::Truncated { offset: self.position })
+    }
+
+    /// Reads a classic pcap record
+    fn record(&mut self, endian: Endian, nanos: bool, link_type: u16) -> Result<Frame<'a>, PcapError> {
+        let offset = self.position;
+        let header = self.bytes(offset, 16)?;
+        let seconds = endian.u32(header) as u64;
+        let fraction = endian.u32(&header[4..]) as u64;
+        let data = self.bytes(offset + 16, endian.u32(&header[8..]) as usize)?;
+        self.position += 16 + data.len();
+        let timestamp = match nanos {
+            true => Resolution::Decimal(9).time(seconds * 1_000_000_000 + fraction),
+            false => Resolution::Decimal(6).time(seconds * 1_000_000 + fraction),
+        };
+        let timestamp = timestamp.ok_or(PcapError::Timestamp { offset })?;
+        Ok(Frame { timestamp, link_type, data })
+    }
+
+    /// Reads a pcapng block, returning the frame of packet blocks
+    fn block(&mut self) -> Result<Option<Frame<'a>>, PcapError> {
+        let offset = self.position;
+        let header = self.bytes(offset, 8)?;
+        let ty = self.endian.u32(header);
+        if ty == SECTION_HEADER {
+            let magic = self.bytes(offset + 8, 4)?;
+            self.endian = match u32::from_le_bytes([magic[0], magic[1], magic[2], magic[3]]) {
+                BYTE_ORDER_MAGIC => Endian::Little,
+                _ => Endian::Big,
+            };
+            self.interfaces.clear();
+        }
+        let len = self.endian.u32(&header[4..]);
+        if len < 12 || !len.is_multiple_of(4) {
+            return Err(PcapError::BlockLength { offset, len })
+        }
+        let body = self.bytes(offset + 8, len as usize - 12)?;
+        self.position += len as usize;
+        let endian = self.endian;
+        match ty {
+            INTERFACE_DESCRIPTION if body.len() >= 8 => {
+                let mut interface = Interface { link_type: endian.u16(body), resolution: Resolution::Decimal(6), offset: 0 };
+                let mut options = &body[8..];
+                while options.len() >= 4 {
+                    let code = endian.u16(options);
+                    let len = endian.u16(&options[2..]) as usize;
+                    let Some(value) = options.get(4..4 + len) else { break };
+                    match (code, value) {
+                        (0, _) => break,
+                        // ---- if_tsresol
+                        (9, [resolution]) => interface.resolution = match resolution & 0x80 {
+                            0 => Resolution::Decimal(*resolution),
+                            _ => Resolution::Binary(resolution & 0x7F),
+                        },
+                        // ---- if_tsoffset
+                        (14, value) if value.len() == 8 => {
+                            let value: [u8; 8] = value.try_into().unwrap_or_default();
+                            interface.offset = match endian {
+                                Endian::Little => i64::from_le_bytes(value),
+                                Endian::Big => i64::from_be_bytes(value),
+                            };
+                        },
+                        _ => (),
+                    }
+                    options = options.get(4 + len.next_multiple_of(4)..).unwrap_or_default();
+                }
+                self.interfaces.push(interface);
+                Ok(None)
+            },
+            ENHANCED_PACKET if body.len() >= 20 => {
+                let id = endian.u32(body);
+                let interface = self.interfaces.get(id as usize).ok_or(PcapError::Interface { offset, id })?;
+                let units = ((endian.u32(&body[4..]) as u64) << 32) | endian.u32(&body[8..]) as u64;
+                let data = body.get(20..20 + endian.u32(&body[12..]) as usize).ok_or(PcapError::Truncated { offset })?;
+                let timestamp = interface.resolution.time(units).and_then(|timestamp| match interface.offset >= 0 {
+                    true => timestamp.checked_add(Duration::from_secs(interface.offset as u64)),
+                    false => timestamp.checked_sub(Duration::from_secs(interface.offset.unsigned_abs())),
+                });
+                let timestamp = timestamp.ok_or(PcapError::Timestamp { offset })?;
+                Ok(Some(Frame { timestamp, link_type: interface.link_type, data }))
+            },
+            SIMPLE_PACKET if body.len() >= 4 => {
+                let interface = self.interfaces.first().ok_or(PcapError::Interface { offset, id: 0 })?;
+                let len = (endian.u32(body) as usize).min(body.len() - 4);
+                Ok(Some(Frame { timestamp: UNIX_EPOCH, link_type: interface.link_type, data: &body[4..4 + len] }))
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// How KLV is carried in UDP datagrams
+pub enum Encapsulation {
+    #[default]
+    /// Transport streams if the datagram is a whole number of transport
+    /// stream packets, raw KLV otherwise
+    Auto,
+    /// The datagram is the KLV
+    Raw,
+    /// The datagram is MPEG transport stream packets, with KLV in PES packets
+    /// of the PIDs which the program map tables declare as KLV, see
+    /// [`UdpFilter::pid`]
+    MpegTs,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// Selects UDP datagrams by destination address and port
+///
+/// By default, every datagram is selected, with [`Encapsulation::Auto`]
+pub struct UdpFilter {
+    address: Option<IpAddr>,
+    port: Option<u16>,
+    encapsulation: Encapsulation,
+    pid: Option<u16>,
+}
+/// [`UdpFilter`] implementation
+impl UdpFilter {
+    /// Creates a filter which selects every datagram
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the datagrams sent to `address`, e.g. a multicast group
+    pub fn address(mut self, address: IpAddr) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Selects the datagrams sent to `port`
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets how KLV is carried in the datagrams
+    pub fn encapsulation(mut self, encapsulation: Encapsulation) -> Self {
+        self.encapsulation = encapsulation;
+        self
+    }
+
+    /// Reads the KLV of transport streams from the PES packets of `pid`,
+    /// instead of the PIDs which the program map tables declare as KLV
+    ///
+    /// Useful for streams without tables, or with tables which do not declare
+    /// their KLV
+    pub fn pid(mut self, pid: u16) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Returns `true` if a datagram sent to `destination` is selected
+    pub fn matches(&self, destination: &SocketAddr) -> bool {
+        self.address.is_none_or(|address| address == destination.ip())
+            && self.port.is_none_or(|port| port == destination.port())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The KLV bytes of a datagram, or of a PES packet of a transport stream
+pub struct Payload {
+    /// The capture time of the datagram, or of the first datagram of the
+    /// PES packet
+    pub timestamp: SystemTime,
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    pub data: Vec<u8>,
+}
+
+/// Iterator over the KLV [`Payload`]s of a [`Capture`], see [`Capture::payloads`]
+///
+/// Ends after an error of the capture, once the payloads read before it
+pub struct Payloads<'a> {
+    frames: Frames<'a>,
+    filter: UdpFilter,
+    demuxer: mpegts::Demuxer,
+    queue: VecDeque<Payload>,
+    error: Option<PcapError>,
+    done: bool,
+}
+/// [`Payloads`] implementation of [`Iterator`]
+impl Iterator for Payloads<'_> {
+    type Item = Result<Payload, PcapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(payload) = self.queue.pop_front() {
+                return Some(Ok(payload))
+            }
+            if self.done {
+                return self.error.take().map(Err)
+            }
+            let frame = match self.frames.next() {
+                Some(Ok(frame)) => frame,
+                Some(Err(err)) => {
+                    self.error = Some(err);
+                    self.done = true;
+                    self.demuxer.flush(&mut self.queue);
+                    continue
+                },
+                None => {
+                    self.done = true;
+                    self.demuxer.flush(&mut self.queue);
+                    continue
+                },
+            };
+            let Some(udp) = frame.udp().filter(|udp| self.filter.matches(&udp.destination)) else { continue };
+            let is_transport_stream = match self.filter.encapsulation {
+                Encapsulation::Auto => mpegts::is_transport_stream(udp.payload),
+                Encapsulation::Raw => false,
+                Encapsulation::MpegTs => true,
+            };
+            match is_transport_stream {
+                true => self.demuxer.push(frame.timestamp, udp.source, udp.destination, udp.payload, &mut self.queue),
+                false => self.queue.push_back(Payload {
+                    timestamp: frame.timestamp,
+                    source: udp.source,
+                    destination: udp.destination,
+                    data: udp.payload.to_vec(),
+                }),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A packet extracted from a [`Capture`], see [`Capture::extract`]
+pub struct Captured<T> {
+    /// The capture time of its [`Payload`]
+    pub timestamp: SystemTime,
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    /// The decoded packet, or the error of decoding it, with offsets into
+    /// its [`Payload`]
+    pub packet: Result<T, PacketError>,
+}
+
+/// Iterator over the packets of type `T` of a [`Capture`], see [`Capture::extract`]
+///
+/// Every payload is decoded with a [`PacketIter`], so that bad packets do not
+/// hide the ones after them.
+pub struct Captures<'a, T> {
+    payloads: Payloads<'a>,
+    queue: VecDeque<Captured<T>>,
+}
+/// [`Captures`] implementation of [`Iterator`]
+impl<T> Iterator for Captures<'_, T>
+where
+    T: KlvSchema + for<'a> Seek<&'a [u8]> + for<'a> Decode<&'a [u8]>,
+{
+    type Item = Result<Captured<T>, PcapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(captured) = self.queue.pop_front() {
+                return Some(Ok(captured))
+            }
+            let payload = match self.payloads.next()? {
+                Ok(payload) => payload,
+                Err(err) => return Some(Err(err)),
+            };
+            self.queue.extend(PacketIter::<T>::new(&payload.data).map(|packet| Captured {
+                timestamp: payload.timestamp,
+                source: payload.source,
+                destination: payload.destination,
+                packet,
+            }));
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Demultiplexing of KLV from MPEG transport streams (ISO/IEC 13818-1)
+//!
+//! KLV is carried in PES packets of stream ID `0xBD` (asynchronous metadata,
+//! MISB ST 1402) or `0xFC` (synchronous metadata, in metadata access units).
+//! The PIDs of KLV are read from the program association and program map
+//! tables: those of stream type `0x15` (metadata in PES packets), or of stream
+//! type `0x06` (private data) with a `KLVA` registration descriptor. Until the
+//! tables of a stream are received, none of its PES packets are KLV.
+//!
+//! Sections of the tables spanning several transport stream packets, or
+//! failing their CRC, are ignored.
+
+// --------------------------------------------------
+// std
+// --------------------------------------------------
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::Payload;
+
+/// The length of a transport stream packet
+pub(crate) const PACKET_LEN: usize = 188;
+
+/// The first byte of every transport stream packet
+pub(crate) const SYNC_BYTE: u8 = 0x47;
+
+/// The stream ID of private stream 1, for asynchronous KLV
+const PRIVATE_STREAM_1: u8 = 0xBD;
+
+/// The stream ID of metadata streams, for synchronous KLV
+const METADATA_STREAM: u8 = 0xFC;
+
+/// The PID of the program association table
+const PAT_PID: u16 = 0x0000;
+
+/// The table ID of program association sections
+const PAT_TABLE_ID: u8 = 0x00;
+
+/// The table ID of program map sections
+const PMT_TABLE_ID: u8 = 0x02;
+
+/// The stream type of private data in PES packets, KLV with a [`KLVA`]
+/// registration descriptor
+const PRIVATE_DATA: u8 = 0x06;
+
+/// The stream type of metadata in PES packets
+const METADATA: u8 = 0x15;
+
+/// The tag of registration descriptors
+const REGISTRATION_DESCRIPTOR: u8 = 0x05;
+
+/// The format identifier of KLV, in registration descriptors
+const KLVA: &[u8; 4] = b"KLVA";
+
+/// Returns `true` if `datagram` is a whole number of transport stream packets
+pub(crate) fn is_transport_stream(datagram: &[u8]) -> bool {
+    !datagram.is_empty()
+        && datagram.len().is_multiple_of(PACKET_LEN)
+        && datagram.chunks_exact(PACKET_LEN).all(|packet| packet[0] == SYNC_BYTE)
+}
+
+/// Returns the CRC-32 of `data`, as used by the sections of the tables
+fn crc32(data: &[u8]) -> u32 {
+    data.iter().fold(u32::MAX, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u32) << 24), |crc, _| match crc & 0x8000_0000 {
+            0 => crc << 1,
+            _ => (crc << 1) ^ 0x04C1_1DB7,
+        })
+    })
+}
+
+/// Returns the body of the section of `table_id` which starts in `payload`,
+/// after its 8-byte header and before its CRC
+fn section(payload: &[u8], table_id: u8) -> Option<&[u8]> {
+    let section = payload.get(1 + *payload.first()? as usize..)?;
+    if *section.first()? != table_id {
+        return None
+    }
+    let len = (u16::from_be_bytes([*section.get(1)?, *section.get(2)?]) & 0x0FFF) as usize;
+    let section = section.get(..3 + len)?;
+    // ---- the CRC of a section, including its CRC, is zero
+    match len >= 9 && crc32(section) == 0 {
+        true => Some(&section[8..section.len() - 4]),
+        false => None,
+    }
+}
+
+/// Returns the PIDs of the program map tables of a program association section
+fn pmt_pids(body: &[u8]) -> BTreeSet<u16> {
+    body.chunks_exact(4)
+        // ---- program 0 is the network information table
+        .filter(|program| program[..2] != [0, 0])
+        .map(|program| u16::from_be_bytes([program[2] & 0x1F, program[3]]))
+        .collect()
+}
+
+/// Returns the PIDs of the KLV streams of a program map section
+fn klv_pids(body: &[u8]) -> BTreeSet<u16> {
+    let mut pids = BTreeSet::new();
+    let Some(info_len) = body.get(2..4).map(|len| (u16::from_be_bytes([len[0], len[1]]) & 0x0FFF) as usize) else { return pids };
+    let mut streams = body.get(4 + info_len..).unwrap_or_default();
+    while streams.len() >= 5 {
+        let pid = u16::from_be_bytes([streams[1] & 0x1F, streams[2]]);
+        let len = (u16::from_be_bytes([streams[3], streams[4]]) & 0x0FFF) as usize;
+        let Some(mut descriptors) = streams.get(5..5 + len) else { break };
+        let is_klv = match streams[0] {
+            METADATA => true,
+            PRIVATE_DATA => {
+                let mut registered = false;
+                while let [tag, len, rest @ ..] = descriptors {
+                    let Some(value) = rest.get(..*len as usize) else { break };
+                    registered |= *tag == REGISTRATION_DESCRIPTOR && value.starts_with(KLVA);
+                    descriptors = &rest[*len as usize..];
+                }
+                registered
+            },
+            _ => false,
+        };
+        if is_klv {
+            pids.insert(pid);
+        }
+        streams = &streams[5 + len..];
+    }
+    pids
+}
+
+/// A PES packet being reassembled
+struct Pes {
+    timestamp: SystemTime,
+    source: SocketAddr,
+    continuity: u8,
+    data: Vec<u8>,
+}
+/// [`Pes`] implementation
+impl Pes {
+    /// Returns `true` if the PES packet has a length, and is complete
+    fn is_complete(&self) -> bool {
+        match self.data.get(4..6).map(|len| u16::from_be_bytes([len[0], len[1]]) as usize) {
+            Some(0) | None => false,
+            Some(len) => self.data.len() >= 6 + len,
+        }
+    }
+
+    /// Returns the KLV bytes of the PES packet, or [`None`] if incomplete
+    fn finish(self, destination: SocketAddr) -> Option<Payload> {
+        let len = u16::from_be_bytes([*self.data.get(4)?, *self.data.get(5)?]) as usize;
+        let data = match len {
+            0 => &self.data[..],
+            len => self.data.get(..6 + len)?,
+        };
+        let payload = data.get(9 + *data.get(8)? as usize..)?;
+        let data = match data[3] {
+            // ---- metadata access unit cells, of a 5-byte header each
+            METADATA_STREAM => {
+                let mut cells = Vec::with_capacity(payload.len());
+                let mut rest = payload;
+                while rest.len() >= 5 {
+                    let len = u16::from_be_bytes([rest[3], rest[4]]) as usize;
+                    let cell = rest.get(5..5 + len)?;
+                    cells.extend(cell);
+                    rest = &rest[5 + len..];
+                }
+                cells
+            },
+            _ => payload.to_vec(),
+        };
+        Some(Payload { timestamp: self.timestamp, source: self.source, destination, data })
+    }
+}
+
+#[derive(Default)]
+/// Reassembles the KLV PES packets of transport streams, one stream per UDP
+/// destination
+///
+/// A PES packet with a lost transport stream packet (a discontinuity of its
+/// continuity counter, or a transport error) is dropped.
+pub(crate) struct Demuxer {
+    /// The PID of KLV, instead of those of the tables
+    pid: Option<u16>,
+    /// The PIDs of the program map tables of each stream
+    pmts: BTreeMap<SocketAddr, BTreeSet<u16>>,
+    /// The PIDs of KLV of each program map table
+    klv: BTreeMap<(SocketAddr, u16), BTreeSet<u16>>,
+    streams: BTreeMap<(SocketAddr, u16), Pes>,
+}
+/// [`Demuxer`] implementation
+impl Demuxer {
+    /// Creates a demuxer of the KLV of `pid` if set, otherwise of the KLV
+    /// PIDs of the program map tables
+    pub fn new(pid: Option<u16>) -> Self {
+        Self { pid, ..Default::default() }
+    }
+
+    /// Returns `true` if `pid` carries KLV in the stream to `destination`
+    fn is_klv(&self, destination: SocketAddr, pid: u16) -> bool {
+        match self.pid {
+            Some(klv) => klv == pid,
+            None => self
+                .klv
+                .range((destination, 0)..=(destination, u16::MAX))
+                .any(|(_, pids)| pids.contains(&pid)),
+        }
+    }
+
+    /// Reads the program association and program map sections starting in
+    /// the payload of a packet
+    fn tables(&mut self, destination: SocketAddr, pid: u16, payload: &[u8]) {
+        if pid == PAT_PID {
+            if let Some(body) = section(payload, PAT_TABLE_ID) {
+                let pmts = pmt_pids(body);
+                self.klv.retain(|(to, pmt), _| *to != destination || pmts.contains(pmt));
+                self.pmts.insert(destination, pmts);
+            }
+        } else if self.pmts.get(&destination).is_some_and(|pmts| pmts.contains(&pid)) {
+            if let Some(body) = section(payload, PMT_TABLE_ID) {
+                self.klv.insert((destination, pid), klv_pids(body));
+            }
+        }
+    }
+
+    /// Pushes the transport stream packets of a datagram, appending the
+    /// completed payloads to `output`
+    pub fn push(
+        &mut self,
+        timestamp: SystemTime,
+        source: SocketAddr,
+        destination: SocketAddr,
+        datagram: &[u8],
+        output: &mut VecDeque<Payload>,
+    ) {
+        for packet in datagram.chunks_exact(PACKET_LEN) {
+            let pid = u16::from_be_bytes([packet[1] & 0x1F, packet[2]]);
+            let key = (destination, pid);
+            if packet[0] != SYNC_BYTE || packet[1] & 0x80 != 0 {
+                self.streams.remove(&key);
+                continue
+            }
+            let start = packet[1] & 0x40 != 0;
+            let continuity = packet[3] & 0x0F;
+            let payload = match (packet[3] >> 4) & 0x03 {
+                0x01 => &packet[4..],
+                0x03 => match packet.get(5 + packet[4] as usize..) {
+                    Some(payload) => payload,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            if start {
+                if let Some(pes) = self.streams.remove(&key) {
+                    output.extend(pes.finish(destination));
+                }
+                if self.pid.is_none() {
+                    self.tables(destination, pid, payload);
+                }
+                let is_klv = self.is_klv(destination, pid)
+                    && payload.starts_with(&[0x00, 0x00, 0x01])
+                    && matches!(payload.get(3), Some(&PRIVATE_STREAM_1) | Some(&METADATA_STREAM));
+                if is_klv {
+                    self.streams.insert(key, Pes { timestamp, source, continuity, data: payload.to_vec() });
+                }
+            } else if let Some(pes) = self.streams.get_mut(&key) {
+                match continuity.wrapping_sub(pes.continuity) & 0x0F {
+                    // ---- a duplicate packet
+                    0 => continue,
+                    1 => {
+                        pes.continuity = continuity;
+                        pes.data.extend(payload);
+                    },
+                    _ => {
+                        self.streams.remove(&key);
+                        continue
+                    },
+                }
+            }
+            if self.streams.get(&key).is_some_and(Pes::is_complete) {
+                output.extend(self.streams.remove(&key).and_then(|pes| pes.finish(destination)));
+            }
+        }
+    }
+
+    /// Finishes every PES packet in progress, at the end of a capture
+    pub fn flush(&mut self, output: &mut VecDeque<Payload>) {
+        for ((destination, _), pes) in std::mem::take(&mut self.streams) {
+            output.extend(pes.finish(destination));
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Link, network and transport layers of captured frames, down to UDP
+
+// --------------------------------------------------
+// std
+// --------------------------------------------------
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::UdpDatagram;
+
+/// `LINKTYPE_NULL`, BSD loopback
+const NULL: u16 = 0;
+/// `LINKTYPE_ETHERNET`
+const ETHERNET: u16 = 1;
+/// `LINKTYPE_RAW`, raw IPv4 or IPv6
+const RAW: u16 = 101;
+/// `LINKTYPE_LINUX_SLL`, Linux "cooked" capture
+const LINUX_SLL: u16 = 113;
+/// `LINKTYPE_IPV4`
+const IPV4: u16 = 228;
+/// `LINKTYPE_IPV6`
+const IPV6: u16 = 229;
+/// `LINKTYPE_LINUX_SLL2`, Linux "cooked" capture v2
+const LINUX_SLL2: u16 = 276;
+
+/// The IP protocol number of UDP
+const PROTOCOL_UDP: u8 = 17;
+
+/// Returns the big-endian `u16` at `at`
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Returns the UDP datagram of a frame of link type `link_type`, if any
+///
+/// Fragmented IPv4 and IPv6 datagrams are not reassembled, and are skipped
+pub(crate) fn udp(link_type: u16, data: &[u8]) -> Option<UdpDatagram<'_>> {
+    match link_type {
+        NULL => {
+            // ---- the address family, in the byte order of the capturing host
+            let family = data.get(..4)?;
+            match u32::from_le_bytes(family.try_into().ok()?).min(u32::from_be_bytes(family.try_into().ok()?)) {
+                2 => ipv4(&data[4..]),
+                24 | 28 | 30 => ipv6(&data[4..]),
+                _ => None,
+            }
+        },
+        ETHERNET => ethertype(be_u16(data, 12)?, data.get(14..)?),
+        RAW => match data.first()? >> 4 {
+            4 => ipv4(data),
+            6 => ipv6(data),
+            _ => None,
+        },
+        LINUX_SLL => ethertype(be_u16(data, 14)?, data.get(16..)?),
+        LINUX_SLL2 => ethertype(be_u16(data, 0)?, data.get(20..)?),
+        IPV4 => ipv4(data),
+        IPV6 => ipv6(data),
+        _ => None,
+    }
+}
+
+/// Returns the UDP datagram of a payload of ethertype `ty`, skipping VLAN tags
+fn ethertype(ty: u16, data: &[u8]) -> Option<UdpDatagram<'_>> {
+    match ty {
+        0x0800 => ipv4(data),
+        0x86DD => ipv6(data),
+        0x8100 | 0x88A8 => ethertype(be_u16(data, 2)?, data.get(4..)?),
+        _ => None,
+    }
+}
+
+/// Returns the UDP datagram of an IPv4 packet
+fn ipv4(data: &[u8]) -> Option<UdpDatagram<'_>> {
+    let header_len = 4 * (*data.first()? & 0x0F) as usize;
+    let total_len = be_u16(data, 2)? as usize;
+    // ---- more fragments flag, or a fragment offset
+    if header_len < 20 || be_u16(data, 6)? & 0x3FFF != 0 || *data.get(9)? != PROTOCOL_UDP {
+        return None
+    }
+    let source: [u8; 4] = data.get(12..16)?.try_into().ok()?;
+    let destination: [u8; 4] = data.get(16..20)?.try_into().ok()?;
+    transport(
+        IpAddr::V4(Ipv4Addr::from(source)),
+        IpAddr::V4(Ipv4Addr::from(destination)),
+        data.get(header_len..total_len)?,
+    )
+}
+
+/// Returns the UDP datagram of an IPv6 packet, skipping extension headers
+fn ipv6(data: &[u8]) -> Option<UdpDatagram<'_>> {
+    let payload_len = be_u16(data, 4)? as usize;
+    let source: [u8; 16] = data.get(8..24)?.try_into().ok()?;
+    let destination: [u8; 16] = data.get(24..40)?.try_into().ok()?;
+    let mut next = *data.get(6)?;
+    let mut payload = data.get(40..40 + payload_len)?;
+    loop {
+        match next {
+            PROTOCOL_UDP => break,
+            // ---- hop-by-hop, routing and destination options
+            0 | 43 | 60 => {
+                next = *payload.first()?;
+                payload = payload.get(8 * (*payload.get(1)? as usize + 1)..)?;
+            },
+            _ => return None,
+        }
+    }
+    transport(IpAddr::V6(Ipv6Addr::from(source)), IpAddr::V6(Ipv6Addr::from(destination)), payload)
+}
+
+/// Returns the UDP datagram of the payload of an IP packet
+fn transport(source: IpAddr, destination: IpAddr, data: &[u8]) -> Option<UdpDatagram<'_>> {
+    let len = be_u16(data, 4)? as usize;
+    Some(UdpDatagram {
+        source: SocketAddr::new(source, be_u16(data, 0)?),
+        destination: SocketAddr::new(destination, be_u16(data, 2)?),
+        payload: data.get(8..len)?,
+    })
+}
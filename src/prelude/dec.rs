@@ -117,4 +117,35 @@ where
             winnow::combinator::repeat(0..len, Self::decode).parse_next(input)
         }
     }
-}
\ No newline at end of file
+}
+/// Decodes a value of encoded length `len`, from a sub-slice of `input`
+/// 
+/// Used by generated [`Decode`] implementations. Always consumes `len` items
+/// of `input` (or the remaining items, if fewer), so that a failing value
+/// decoder cannot desynchronize the packet.
+/// 
+/// Fails with [`KlvErrorKind::LengthMismatch`] if the packet is shorter than
+/// `len`, or if the decoder does not consume the whole sub-slice
+pub fn decode_value<S, O, E>(
+    input: &mut S,
+    len: usize,
+    mut decoder: impl FnMut(&mut <S as winnow::stream::Stream>::Slice) -> winnow::PResult<O, E>,
+) -> Result<O, KlvError>
+where
+    S: winnow::stream::Stream + winnow::stream::StreamIsPartial,
+    <S as winnow::stream::Stream>::Slice: winnow::stream::Stream,
+    winnow::error::ErrMode<E>: IntoKlvError,
+{
+    let mut value = match winnow::token::take::<usize, S, winnow::error::ContextError>(len).parse_next(input) {
+        Ok(value) => value,
+        Err(_) => {
+            let actual = input.finish().iter_offsets().count();
+            return Err(KlvError::new(KlvErrorKind::LengthMismatch { expected: len, actual }))
+        },
+    };
+    let decoded = decoder(&mut value).map_err(IntoKlvError::into_klv_error)?;
+    match value.iter_offsets().count() {
+        0 => Ok(decoded),
+        remaining => Err(KlvError::new(KlvErrorKind::LengthMismatch { expected: len, actual: len - remaining })),
+    }
+}
@@ -119,7 +119,7 @@ pub use super::*;
 ///     0x6B, 0x6C, 0x76,   // example 3 value
 /// ]);
 /// ```
-pub trait EncodeValue<O: EncodedOutput> {
+pub trait EncodeValue<O> {
     fn encode_value(&self) -> O;
 }
 
@@ -200,6 +200,58 @@ impl<O: EncodedOutput> IntoKlv<O> for Option<O> {
     }
 }
 
+/// Trait for prepending encoded text with its key and length, and appending
+/// the record terminator: `KEY:LEN:VALUE;`
+/// 
+/// This is the [`String`] counterpart of [`IntoKlv`], used by structs deriving
+/// [`crate::Klv`] with `stream = &str`. The length passed to the length encoder
+/// is the number of characters of the value, to match [`winnow::token::take`]
+/// on `&str`. See [`crate::codecs::string`]
+/// 
+/// # Example
+/// 
+/// ```rust
+/// use tinyklv::prelude::*;
+/// use tinyklv::codecs::string::enc;
+/// 
+/// let encoded = String::from("270").into_klv_string("HDG", enc::len);
+/// assert_eq!(encoded, "HDG:3:270;");
+/// ```
+pub trait IntoKlvString {
+    fn into_klv_string(self, encoded_key: impl Into<String>, len_encoder: fn(usize) -> String) -> String;
+}
+/// [`IntoKlvString`] implementation for [`String`]
+impl IntoKlvString for String {
+    #[inline(always)]
+    fn into_klv_string(self, encoded_key: impl Into<String>, len_encoder: fn(usize) -> String) -> String {
+        let mut output = encoded_key.into();
+        output.push_str(&len_encoder(self.chars().count()));
+        output.push_str(&self);
+        output.push(crate::codecs::string::TERMINATOR);
+        output
+    }
+}
+/// [`IntoKlvString`] implementation for [`Result<String>`]
+impl<E> IntoKlvString for Result<String, E> {
+    #[inline(always)]
+    fn into_klv_string(self, encoded_key: impl Into<String>, len_encoder: fn(usize) -> String) -> String {
+        match self {
+            Ok(x) => x.into_klv_string(encoded_key, len_encoder),
+            Err(_) => String::new(),
+        }
+    }
+}
+/// [`IntoKlvString`] implementation for [`Option<String>`]
+impl IntoKlvString for Option<String> {
+    #[inline(always)]
+    fn into_klv_string(self, encoded_key: impl Into<String>, len_encoder: fn(usize) -> String) -> String {
+        match self {
+            Some(x) => x.into_klv_string(encoded_key, len_encoder),
+            None => String::new(),
+        }
+    }
+}
+
 /// Trait for encoding data to its full key-length-value representation.
 /// 
 /// ```text
@@ -327,6 +379,6 @@ impl<O: EncodedOutput> IntoKlv<O> for Option<O> {
 /// 
 /// assert_eq!(mystruct_klv_1, mystruct_klv_2);
 /// ```
-pub trait Encode<O: EncodedOutput> {
+pub trait Encode<O> {
     fn encode(&self) -> O;
 }
\ No newline at end of file
@@ -0,0 +1,198 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::error::{AddContext, ContextError, ErrMode, ErrorKind, FromExternalError, ParserError, StrContext};
+use winnow::stream::Stream;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The kind of a [`KlvError`]
+pub enum KlvErrorKind {
+    /// A required field is missing from the packet
+    MissingField,
+    /// The value decoder did not consume exactly the encoded length of the
+    /// value, or the packet ends before the encoded length
+    LengthMismatch {
+        /// The encoded length, in stream items
+        expected: usize,
+        /// The length consumed by the value decoder, or remaining in the packet
+        actual: usize,
+    },
+    /// The value decoder failed, with the message of its error
+    Decode(String),
+    /// A [`winnow`] parser failed, when [`KlvError`] is used as the error type
+    Parser(ErrorKind),
+}
+/// [`KlvErrorKind`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for KlvErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KlvErrorKind::MissingField => write!(f, "required value is missing"),
+            KlvErrorKind::LengthMismatch { expected, actual } => write!(f, "expected a length of {}, found {}", expected, actual),
+            KlvErrorKind::Decode(message) if message.is_empty() => write!(f, "unable to decode value"),
+            KlvErrorKind::Decode(message) => write!(f, "unable to decode value: {}", message),
+            KlvErrorKind::Parser(kind) => write!(f, "failed to parse {}", kind.description()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Structured error of decoding a packet
+///
+/// Records where decoding failed: the byte offset from the start of the
+/// decoded input, the key path through nested packets, and the failing field.
+///
+/// Generated [`Decode`](crate::prelude::Decode) implementations return it as
+/// the cause of their [`winnow`] error, which is recovered with [`From`]:
+///
+/// ```
+/// use tinyklv::Klv;
+/// use tinyklv::prelude::*;
+/// use tinyklv::codecs::binary::dec::be_u16;
+///
+/// #[derive(Klv, Debug)]
+/// #[klv(
+///     stream = &[u8],
+///     key(dec = tinyklv::dec::binary::u8),
+///     len(dec = tinyklv::dec::binary::u8_as_usize),
+/// )]
+/// struct Packet {
+///     #[klv(key = 0x01, dec = be_u16)]
+///     id: u16,
+/// }
+///
+/// // ---- the value of tag 1 is one byte too long
+/// let err = Packet::decode(&mut &[0x01, 0x03, 0x00, 0x01, 0x02][..]).unwrap_err();
+/// let err = KlvError::from(err);
+/// assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+/// assert_eq!(err.to_string(), "Packet > tag 1 at byte 2 (field `id`): expected a length of 3, found 2");
+/// ```
+///
+/// It can also be used directly as the [`winnow`] error type of custom
+/// decoders, e.g. `fn dec(input: &mut &[u8]) -> PResult<T, KlvError>`.
+pub struct KlvError {
+    /// The kind of error
+    pub kind: KlvErrorKind,
+    /// The byte offset of the failing value, from the start of the decoded input
+    pub offset: usize,
+    /// The name of the outermost packet
+    pub packet: Option<&'static str>,
+    /// The keys leading to the failing value, outermost first
+    pub path: Vec<&'static str>,
+    /// The name of the failing field
+    pub field: Option<&'static str>,
+}
+/// [`KlvError`] implementation
+impl KlvError {
+    /// Creates an error at offset `0`, without a path
+    pub fn new(kind: KlvErrorKind) -> Self {
+        Self { kind, offset: 0, packet: None, path: Vec::new(), field: None }
+    }
+
+    /// Sets the failing field, unless an inner field is already set
+    pub fn field(mut self, field: &'static str) -> Self {
+        self.field.get_or_insert(field);
+        self
+    }
+
+    /// Places the error within the value of `key` at `offset` of `packet`
+    ///
+    /// Used by generated [`Decode`](crate::prelude::Decode) implementations,
+    /// from the innermost packet outwards
+    pub fn within(mut self, packet: &'static str, key: &'static str, offset: usize) -> Self {
+        self.packet = Some(packet);
+        self.path.insert(0, key);
+        self.offset += offset;
+        self
+    }
+
+    /// Converts the error into a [`winnow`] error, with `self` as the cause
+    pub fn into_winnow<I: Stream>(self, input: &I) -> ErrMode<ContextError> {
+        ErrMode::from_external_error(input, ErrorKind::Verify, self)
+    }
+}
+/// [`KlvError`] implementation of [`std::fmt::Display`]
+///
+/// e.g. `Uas > tag 48 > tag 3 at byte 21 (field `version`): expected a length of 2, found 1`
+impl std::fmt::Display for KlvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut segments = self.packet.iter().chain(self.path.iter()).peekable();
+        if segments.peek().is_some() {
+            write!(f, "{} ", segments.copied().collect::<Vec<_>>().join(" > "))?;
+        }
+        write!(f, "at byte {}", self.offset)?;
+        if let Some(field) = self.field {
+            write!(f, " (field `{}`)", field)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+/// [`KlvError`] implementation of [`std::error::Error`]
+impl std::error::Error for KlvError {}
+
+/// [`KlvError`] implementation of [`winnow::error::ParserError`]
+impl<I: Stream> ParserError<I> for KlvError {
+    fn from_error_kind(_input: &I, kind: ErrorKind) -> Self {
+        KlvError::new(KlvErrorKind::Parser(kind))
+    }
+
+    fn append(self, _input: &I, _token_start: &<I as Stream>::Checkpoint, _kind: ErrorKind) -> Self {
+        self
+    }
+}
+/// [`KlvError`] implementation of [`winnow::error::AddContext`]
+///
+/// Labels replace the kind of bare parser errors
+impl<I: Stream> AddContext<I, StrContext> for KlvError {
+    fn add_context(mut self, _input: &I, _token_start: &<I as Stream>::Checkpoint, context: StrContext) -> Self {
+        if let (KlvErrorKind::Parser(_), StrContext::Label(label)) = (&self.kind, context) {
+            self.kind = KlvErrorKind::Decode(label.to_string());
+        }
+        self
+    }
+}
+/// [`KlvError`] implementation of [`winnow::error::FromExternalError`]
+impl<I, E: std::error::Error> FromExternalError<I, E> for KlvError {
+    fn from_external_error(_input: &I, _kind: ErrorKind, e: E) -> Self {
+        KlvError::new(KlvErrorKind::Decode(e.to_string()))
+    }
+}
+/// [`KlvError`] implementation of [`From`] for [`winnow::error::ErrMode<ContextError>`]
+///
+/// Recovers the [`KlvError`] cause of the error, if any
+impl From<ErrMode<ContextError>> for KlvError {
+    fn from(err: ErrMode<ContextError>) -> Self {
+        err.into_klv_error()
+    }
+}
+
+/// Conversion of the [`winnow`] errors returned by decoders into a [`KlvError`]
+///
+/// Implemented for [`ErrMode<ContextError>`] and [`ErrMode<KlvError>`], so
+/// decoders may use either error type
+pub trait IntoKlvError {
+    /// Converts the error into a [`KlvError`], recovering a [`KlvError`]
+    /// cause if there is one, and reporting incomplete input as a
+    /// [`KlvErrorKind::Decode`] error otherwise
+    fn into_klv_error(self) -> KlvError;
+}
+/// [`ErrMode<ContextError>`] implementation of [`IntoKlvError`]
+impl IntoKlvError for ErrMode<ContextError> {
+    fn into_klv_error(self) -> KlvError {
+        match self.into_inner() {
+            Some(err) => match err.cause().and_then(|cause| cause.downcast_ref::<KlvError>()) {
+                Some(cause) => cause.clone(),
+                None => KlvError::new(KlvErrorKind::Decode(err.to_string())),
+            },
+            None => KlvError::new(KlvErrorKind::Decode("incomplete input".into())),
+        }
+    }
+}
+/// [`ErrMode<KlvError>`] implementation of [`IntoKlvError`]
+impl IntoKlvError for ErrMode<KlvError> {
+    fn into_klv_error(self) -> KlvError {
+        match self.into_inner() {
+            Some(err) => err,
+            None => KlvError::new(KlvErrorKind::Decode("incomplete input".into())),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::stream::Offset;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::{Decode, KlvError, KlvSchema, Seek};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error item of a [`PacketIter`]
+///
+/// Carries the range of bytes skipped to resynchronize with the next sentinel
+pub struct PacketError {
+    /// The skipped bytes, as offsets into the iterated input
+    pub skipped: std::ops::Range<usize>,
+    /// The error of decoding the packet at the start of the skipped bytes,
+    /// or [`None`] if they do not start with a sentinel
+    ///
+    /// Offsets of the error are absolute, into the iterated input
+    pub error: Option<KlvError>,
+}
+/// [`PacketError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for PacketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "skipped bytes {}..{}", self.skipped.start, self.skipped.end)?;
+        match &self.error {
+            Some(error) => write!(f, ": {}", error),
+            None => write!(f, ": no sentinel"),
+        }
+    }
+}
+/// [`PacketError`] implementation of [`std::error::Error`]
+impl std::error::Error for PacketError {}
+
+/// Iterator over the packets of a `&[u8]` stream, which resynchronizes on errors
+///
+/// Each packet is found by its sentinel (see [`KlvSchema::SENTINEL`]), then
+/// decoded. On a bad packet, the iterator skips to the next occurrence of the
+/// sentinel and yields a [`PacketError`] with the skipped byte range, so that
+/// long recordings with bit errors can be decoded end to end. Bytes before a
+/// sentinel are skipped the same way.
+///
+/// The iterator always makes progress, and ends with the input.
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::Klv;
+/// use tinyklv::prelude::*;
+///
+/// #[derive(Klv, Debug, PartialEq)]
+/// #[klv(
+///     stream = &[u8],
+///     sentinel = b"\x06\x0E",
+///     key(dec = tinyklv::dec::binary::u8),
+///     len(dec = tinyklv::dec::binary::u8_as_usize),
+/// )]
+/// struct Packet {
+///     #[klv(key = 0x01, dec = tinyklv::dec::binary::u8)]
+///     id: u8,
+/// }
+///
+/// let data = [
+///     0xFF, 0xFF,                   // noise
+///     0x06, 0x0E, 0x03, 0x01, 0x01, 0x01,
+///     0x06, 0x0E, 0x03, 0x09, 0x01, 0x02, // missing `id`
+///     0x06, 0x0E, 0x03, 0x01, 0x01, 0x03,
+/// ];
+/// let packets: Vec<_> = PacketIter::<Packet>::new(&data).collect();
+/// assert_eq!(packets.len(), 4);
+/// assert_eq!(packets[0].as_ref().unwrap_err().skipped, 0..2);
+/// assert_eq!(packets[1], Ok(Packet { id: 1 }));
+/// assert_eq!(packets[2].as_ref().unwrap_err().skipped, 8..14);
+/// assert_eq!(packets[3], Ok(Packet { id: 3 }));
+/// ```
+pub struct PacketIter<'a, T> {
+    input: &'a [u8],
+    sentinel: Sentinel<'a>,
+    position: usize,
+    _marker: std::marker::PhantomData<T>,
+}
+/// [`PacketIter`] implementation
+impl<'a, T> PacketIter<'a, T>
+where
+    T: KlvSchema,
+{
+    /// Iterates over the packets of `input`, found by [`KlvSchema::SENTINEL`]
+    ///
+    /// Without a sentinel, every byte is a candidate start of a packet
+    pub fn new(input: &'a [u8]) -> Self {
+        let sentinel = Sentinel::of::<T>().unwrap_or(Sentinel::exact(&[]));
+        Self { input, sentinel, position: 0, _marker: std::marker::PhantomData }
+    }
+}
+/// [`PacketIter`] implementation
+impl<'a, T> PacketIter<'a, T> {
+    /// Iterates over the packets of `input`, found by the bytes of `sentinel`
+    /// on the wire
+    ///
+    /// Use this when the bytes on the wire differ from [`KlvSchema::SENTINEL`],
+    /// which are the bytes of the literal
+    pub fn with_sentinel(input: &'a [u8], sentinel: &'a [u8]) -> Self {
+        Self { input, sentinel: Sentinel::exact(sentinel), position: 0, _marker: std::marker::PhantomData }
+    }
+
+    /// Returns the offset of the next byte to be read, into the iterated input
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+/// [`PacketIter`] implementation of [`Iterator`]
+impl<'a, T> Iterator for PacketIter<'a, T>
+where
+    T: Seek<&'a [u8]> + Decode<&'a [u8]>,
+{
+    type Item = Result<T, PacketError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.input.len() {
+            return None
+        }
+        // --------------------------------------------------
+        // skip to the next sentinel, yielding the skipped bytes
+        // --------------------------------------------------
+        let start = match self.sentinel.find(self.input, self.position) {
+            Some(start) if start == self.position => start,
+            Some(start) => return Some(Err(self.skip_to(start, None))),
+            None => return Some(Err(self.skip_to(self.input.len(), None))),
+        };
+        match decode_at::<T>(self.input, start) {
+            Ok((packet, end)) => {
+                self.position = end;
+                Some(Ok(packet))
+            },
+            Err(error) => {
+                let next = self.sentinel.find(self.input, start + 1).unwrap_or(self.input.len());
+                Some(Err(self.skip_to(next, Some(error))))
+            },
+        }
+    }
+}
+/// [`PacketIter`] implementation
+impl<T> PacketIter<'_, T> {
+    /// Skips to `end`, returning the skipped bytes
+    fn skip_to(&mut self, end: usize, error: Option<KlvError>) -> PacketError {
+        let skipped = self.position..end;
+        self.position = end;
+        PacketError { skipped, error }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The bytes of a sentinel on the wire, optionally a universal label whose
+/// version byte is ignored
+pub(crate) struct Sentinel<'a> {
+    pub bytes: &'a [u8],
+    pub ignore_version: bool,
+}
+/// [`Sentinel`] implementation
+impl<'a> Sentinel<'a> {
+    /// A sentinel matched byte for byte
+    pub fn exact(bytes: &'a [u8]) -> Self {
+        Self { bytes, ignore_version: false }
+    }
+
+    /// Returns `true` if `window` is an occurrence of the sentinel
+    pub fn matches(&self, window: &[u8]) -> bool {
+        match self.ignore_version {
+            true => crate::codecs::ul::eq_ignoring_version(window, self.bytes),
+            false => window == self.bytes,
+        }
+    }
+
+    /// Returns `true` if `input` starts with the sentinel
+    pub fn is_prefix_of(&self, input: &[u8]) -> bool {
+        input.get(..self.bytes.len()).is_some_and(|window| self.matches(window))
+    }
+
+    /// Returns the offset of the first occurrence of the sentinel in
+    /// `haystack`, at or after `from`
+    ///
+    /// An empty sentinel occurs everywhere
+    pub fn find(&self, haystack: &[u8], from: usize) -> Option<usize> {
+        if self.bytes.is_empty() {
+            return (from < haystack.len()).then_some(from)
+        }
+        haystack
+            .get(from..)?
+            .windows(self.bytes.len())
+            .position(|window| self.matches(window))
+            .map(|index| from + index)
+    }
+}
+/// [`Sentinel`] implementation
+impl Sentinel<'static> {
+    /// The sentinel of `T`, if any
+    pub fn of<T: KlvSchema>() -> Option<Self> {
+        T::SENTINEL.map(|bytes| Self { bytes, ignore_version: T::SENTINEL_IGNORES_VERSION })
+    }
+}
+
+/// Seeks and decodes a `T` at offset `start` of `input`
+///
+/// Returns the packet and the offset after it, or the error with absolute offsets
+pub(crate) fn decode_at<'a, T>(input: &'a [u8], start: usize) -> Result<(T, usize), KlvError>
+where
+    T: Seek<&'a [u8]> + Decode<&'a [u8]>,
+{
+    let absolute = |err, offset: usize| {
+        let mut err = KlvError::from(err);
+        err.offset += offset;
+        err
+    };
+    let mut rest = &input[start..];
+    let mut packet = T::seek(&mut rest).map_err(|err| absolute(err, start))?;
+    let packet_start = packet.offset_from(&input);
+    let decoded = T::decode(&mut packet).map_err(|err| absolute(err, packet_start))?;
+    Ok((decoded, rest.offset_from(&input)))
+}
+
@@ -10,7 +10,15 @@ pub use winnow::error::AddContext;
 // --------------------------------------------------
 mod dec;
 mod enc;
+mod error;
+pub(crate) mod iter;
+mod report;
 mod types;
+mod schema;
 pub use dec::*;
 pub use enc::*;
-pub use types::*;
\ No newline at end of file
+pub use error::*;
+pub use iter::*;
+pub use report::*;
+pub use types::*;
+pub use schema::*;
\ No newline at end of file
@@ -0,0 +1,108 @@
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use super::{KlvError, KlvErrorKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A key of a packet which does not match any field
+pub struct UnknownKey {
+    /// The key, formatted with [`std::fmt::Debug`]
+    pub key: String,
+    /// The byte offset of the key, from the start of the decoded input
+    pub offset: usize,
+    /// The encoded length of the value
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A key of a packet which occurs more than once
+///
+/// The last occurrence is kept in the decoded value
+pub struct DuplicateKey {
+    /// The name of the field
+    pub field: &'static str,
+    /// The key, e.g. `tag 48`
+    pub key: &'static str,
+    /// The byte offset of the repeated key, from the start of the decoded input
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Per-field diagnostics of decoding a packet
+///
+/// Returned alongside the decoded value by the `decode_with_report` method
+/// generated by [`#[derive(Klv)]`](crate::Klv). Decoding stays lenient:
+/// everything listed here is skipped, rather than failing the packet
+/// (unless a required field is missing).
+///
+/// Only the outermost packet is reported. Nested packets report their
+/// failures as a single [`KlvError`], with the key path leading into them.
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::Klv;
+/// use tinyklv::prelude::*;
+///
+/// #[derive(Klv, Debug)]
+/// #[klv(
+///     stream = &[u8],
+///     key(dec = tinyklv::dec::binary::u8),
+///     len(dec = tinyklv::dec::binary::u8_as_usize),
+/// )]
+/// struct Packet {
+///     #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+///     id: u16,
+///     #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u16)]
+///     heading: Option<u16>,
+/// }
+///
+/// let (packet, report) = Packet::decode_with_report(&mut &[
+///     0x09, 0x01, 0xFF,       // unknown key
+///     0x02, 0x01, 0x00,       // heading is too short
+///     0x01, 0x02, 0x00, 0x01, // id
+///     0x01, 0x02, 0x00, 0x02, // id, again
+///     0x01,                   // trailing
+/// ][..]).unwrap();
+/// assert_eq!(packet.id, 2);
+/// assert_eq!(packet.heading, None);
+/// assert_eq!(report.unknown, vec![UnknownKey { key: "9".into(), offset: 0, len: 1 }]);
+/// assert_eq!(report.duplicates, vec![DuplicateKey { field: "id", key: "tag 1", offset: 10 }]);
+/// assert_eq!(report.failed[0].field, Some("heading"));
+/// assert_eq!(report.trailing, 1);
+/// assert!(!report.is_clean());
+/// ```
+pub struct DecodeReport {
+    /// Keys which do not match any field, and were skipped
+    pub unknown: Vec<UnknownKey>,
+    /// Keys which occur more than once
+    pub duplicates: Vec<DuplicateKey>,
+    /// Fields whose value decoder failed
+    pub failed: Vec<KlvError>,
+    /// Fields whose value decoder did not consume exactly the encoded length
+    /// of the value, or whose value (or that of an unknown key) is cut short
+    /// by the end of the packet
+    pub length_mismatches: Vec<KlvError>,
+    /// The number of trailing bytes which could not be parsed as a key and
+    /// length, or from an unknown key whose value is cut short
+    pub trailing: usize,
+}
+/// [`DecodeReport`] implementation
+impl DecodeReport {
+    /// Returns `true` if nothing was skipped while decoding
+    pub fn is_clean(&self) -> bool {
+        self.unknown.is_empty()
+            && self.duplicates.is_empty()
+            && self.failed.is_empty()
+            && self.length_mismatches.is_empty()
+            && self.trailing == 0
+    }
+
+    /// Records the error of a field, as either failed or a length mismatch
+    pub fn record(&mut self, err: KlvError) {
+        match err.kind {
+            KlvErrorKind::LengthMismatch { .. } => self.length_mismatches.push(err),
+            _ => self.failed.push(err),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Runtime schema / reflection for the [`crate::prelude`]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Schema of a single `#[klv(..)]` field
+///
+/// * `key` - The bytes of the key literal, as written (see [`KlvSchema`])
+/// * `key_literal` - The key literal, as written
+/// * `field` - The name of the field in the struct
+/// * `ty` - The rust type of the field, as written
+/// * `dynlen` - Whether the value is of dynamic length (`dyn = true`)
+/// * `optional` - Whether the field can be missing from a packet, i.e. it is an
+///   [`Option`], has a `default`, or is set to `required = false`
+/// * `enc` - The path of the value encoder, if any
+/// * `dec` - The path of the value decoder, if any
+pub struct FieldSchema {
+    pub key: &'static [u8],
+    pub key_literal: &'static str,
+    pub field: &'static str,
+    pub ty: &'static str,
+    pub dynlen: bool,
+    pub optional: bool,
+    pub enc: Option<&'static str>,
+    pub dec: Option<&'static str>,
+}
+
+/// Trait for reflecting on the layout of a KLV type, without re-parsing source
+///
+/// ***This trait is automatically implemented for structs deriving the
+/// [`tinyklv::Klv`](crate::Klv) trait.***
+///
+/// Useful for building generic pretty-printers, documentation tables and
+/// validators.
+///
+/// Keys and sentinels are stored as the bytes of their literal, as written in
+/// the `#[klv(..)]` attributes:
+///
+/// * byte strings / strings: their contents, e.g. `b"\x06\x0E"` -> `[0x06, 0x0E]`
+/// * integers: their minimal big-endian representation, or the width of their
+///   suffix if one is given, e.g. `0x0102` -> `[0x01, 0x02]`, `0x01u16` -> `[0x00, 0x01]`
+///
+/// Note that these are the bytes ***of the literal***, and not necessarily the bytes
+/// on the wire, which depend on the key encoder (e.g. BER-OID). Literals without
+/// bytes, such as floats, are rejected by the derive macro. To look up a field
+/// by its key on the wire, use [`KlvSchema::field_by_key`].
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::Klv;
+/// use tinyklv::prelude::*;
+///
+/// #[derive(Klv)]
+/// #[klv(
+///     sentinel = b"\x00\x00\x00",
+///     key(dec = tinyklv::dec::binary::u8),
+///     len(dec = tinyklv::dec::binary::u8_as_usize),
+/// )]
+/// struct Foo {
+///     #[klv(key = 0x01, dyn = true, dec = tinyklv::dec::binary::to_string_utf8)]
+///     name: String,
+///
+///     #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u16)]
+///     number: Option<u16>,
+/// }
+///
+/// assert_eq!(Foo::SENTINEL, Some(&[0x00, 0x00, 0x00][..]));
+/// assert_eq!(Foo::KEY_DECODER, Some("tinyklv::dec::binary::u8"));
+/// assert_eq!(Foo::KEY_ENCODER, None);
+///
+/// let number = Foo::field_by_key(&[0x02]).unwrap();
+/// assert_eq!(number.field, "number");
+/// assert_eq!(number.ty, "Option<u16>");
+/// assert!(number.optional);
+/// assert!(!number.dynlen);
+///
+/// let name = Foo::field_by_name("name").unwrap();
+/// assert_eq!(name.key, &[0x01]);
+/// assert!(name.dynlen);
+/// ```
+pub trait KlvSchema {
+    /// The name of the type
+    const NAME: &'static str;
+    /// The stream type, as written
+    const STREAM: &'static str;
+    /// The bytes of the recognition sentinel literal, if any
+    const SENTINEL: Option<&'static [u8]>;
+    /// Whether the sentinel is a universal label, recognized regardless of
+    /// its version byte (see [`crate::codecs::ul`])
+    const SENTINEL_IGNORES_VERSION: bool = false;
+    /// The path of the key encoder, if any
+    const KEY_ENCODER: Option<&'static str>;
+    /// The path of the key decoder, if any
+    const KEY_DECODER: Option<&'static str>;
+    /// The path of the length encoder, if any
+    const LEN_ENCODER: Option<&'static str>;
+    /// The path of the length decoder, if any
+    const LEN_DECODER: Option<&'static str>;
+    /// The schema of every `#[klv(..)]` field, in declaration order
+    const FIELDS: &'static [FieldSchema];
+
+    /// Returns the schema of a field, by its key as found on the wire
+    ///
+    /// Derived implementations decode `key` with the key decoder (or, without
+    /// one, encode the key literals with the key encoder) to find the field.
+    /// This default implementation compares `key` with the bytes of the key
+    /// literals, see [`FieldSchema::key`]
+    fn field_by_key(key: &[u8]) -> Option<&'static FieldSchema> {
+        Self::FIELDS.iter().find(|field| field.key == key)
+    }
+
+    /// Returns the schema of a field, by its name in the struct
+    fn field_by_name(name: &str) -> Option<&'static FieldSchema> {
+        Self::FIELDS.iter().find(|field| field.field == name)
+    }
+}
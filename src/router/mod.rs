@@ -0,0 +1,255 @@
+//! Demultiplexing of interleaved packet types
+//!
+//! A single recording often interleaves several packet types (e.g. MISB ST
+//! 0601, ST 0102 stand-alone, ST 0903 and vendor packets), each recognized by
+//! a different sentinel. A [`KlvRouter`] dispatches each packet of a byte
+//! stream to the type registered for its sentinel, converting it into a
+//! common output type: either an enum, or a trait object.
+//!
+//! Packets keyed by an unregistered SMPTE universal label (starting with
+//! [`UL_PREFIX`], followed by a BER length) are yielded as raw bytes. Any
+//! other bytes are skipped, resynchronizing like a [`PacketIter`].
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//! use tinyklv::router::{KlvRouter, Routed};
+//!
+//! #[derive(Klv, Debug, PartialEq)]
+//! #[klv(
+//!     stream = &[u8],
+//!     sentinel = b"\x06\x0E\x2B\x34\x02\x0B\x01\x01\x0E\x01\x03\x01\x01\x00\x00\x00",
+//!     key(dec = tinyklv::dec::binary::u8),
+//!     len(dec = tinyklv::dec::ber::ber_length),
+//! )]
+//! struct Uas {
+//!     #[klv(key = 0x01, dec = tinyklv::dec::binary::u8)]
+//!     version: u8,
+//! }
+//!
+//! #[derive(Klv, Debug, PartialEq)]
+//! #[klv(
+//!     stream = &[u8],
+//!     sentinel = b"\x06\x0E\x2B\x34\x02\x03\x01\x01\x0E\x01\x03\x03\x02\x00\x00\x00",
+//!     key(dec = tinyklv::dec::binary::u8),
+//!     len(dec = tinyklv::dec::ber::ber_length),
+//! )]
+//! struct Security {
+//!     #[klv(key = 0x01, dec = tinyklv::dec::binary::u8)]
+//!     classification: u8,
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Packet {
+//!     Uas(Uas),
+//!     Security(Security),
+//! }
+//!
+//! let router = KlvRouter::new()
+//!     .route(Packet::Uas)
+//!     .route(Packet::Security);
+//!
+//! let mut data = Vec::new();
+//! data.extend(Uas::SENTINEL.unwrap());
+//! data.extend([0x03, 0x01, 0x01, 0x13]);
+//! data.extend(Security::SENTINEL.unwrap());
+//! data.extend([0x03, 0x01, 0x01, 0x01]);
+//! let vendor = [0x06, 0x0E, 0x2B, 0x34, 0x7F, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0xAA];
+//! data.extend(vendor);
+//!
+//! let routed: Vec<_> = router.iter(&data).collect();
+//! assert_eq!(routed, vec![
+//!     Ok(Routed::Packet(Packet::Uas(Uas { version: 19 }))),
+//!     Ok(Routed::Packet(Packet::Security(Security { classification: 1 }))),
+//!     Ok(Routed::Unmatched { offset: 40, bytes: &vendor[..] }),
+//! ]);
+//! ```
+
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use winnow::Parser;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+use crate::prelude::iter::{decode_at, Sentinel};
+
+/// The first bytes of every SMPTE universal label
+pub const UL_PREFIX: [u8; 4] = crate::codecs::ul::UniversalLabel::PREFIX;
+
+/// The length of a SMPTE universal label, in bytes
+pub const UL_LEN: usize = crate::codecs::ul::UniversalLabel::LEN;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Item of a [`RouterIter`]
+pub enum Routed<'a, O> {
+    /// A packet of a registered type, converted into the output type
+    Packet(O),
+    /// A packet keyed by an unregistered universal label, including its key
+    /// and length
+    Unmatched {
+        /// The offset of the packet, into the iterated input
+        offset: usize,
+        /// The bytes of the packet
+        bytes: &'a [u8],
+    },
+}
+
+/// Seeks and decodes a packet at an offset of the input, returning the output
+/// and the offset after the packet
+type RouteDecoder<O> = Box<dyn Fn(&[u8], usize) -> Result<(O, usize), KlvError>>;
+
+/// A registered packet type of a [`KlvRouter`]
+struct Route<O> {
+    sentinel: Sentinel<'static>,
+    decode: RouteDecoder<O>,
+}
+
+/// Dispatches the packets of a `&[u8]` stream to their registered types, by sentinel
+///
+/// See the [module documentation](self) for an example
+pub struct KlvRouter<O> {
+    routes: Vec<Route<O>>,
+}
+/// [`KlvRouter`] implementation
+impl<O> KlvRouter<O> {
+    /// Creates a router without any registered types
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Registers `T`, found by [`KlvSchema::SENTINEL`], and converted into
+    /// the output type by `into`
+    ///
+    /// A universal label sentinel is found regardless of its version byte,
+    /// see [`KlvSchema::SENTINEL_IGNORES_VERSION`]
+    ///
+    /// # Panics
+    ///
+    /// If `T` has no sentinel, see [`KlvRouter::route_with_sentinel`]
+    pub fn route<T>(self, into: impl Fn(T) -> O + 'static) -> Self
+    where
+        T: KlvSchema + for<'a> Seek<&'a [u8]> + for<'a> Decode<&'a [u8]> + 'static,
+    {
+        let sentinel = Sentinel::of::<T>().unwrap_or_else(|| panic!("`{}` has no sentinel to route by", T::NAME));
+        self.push::<T>(sentinel, into)
+    }
+
+    /// Registers `T`, found by the bytes of `sentinel` on the wire, and
+    /// converted into the output type by `into`
+    ///
+    /// When several sentinels match, the longest wins
+    pub fn route_with_sentinel<T>(self, sentinel: &'static [u8], into: impl Fn(T) -> O + 'static) -> Self
+    where
+        T: for<'a> Seek<&'a [u8]> + for<'a> Decode<&'a [u8]> + 'static,
+    {
+        self.push::<T>(Sentinel::exact(sentinel), into)
+    }
+
+    /// Registers `T`, found by `sentinel`
+    fn push<T>(mut self, sentinel: Sentinel<'static>, into: impl Fn(T) -> O + 'static) -> Self
+    where
+        T: for<'a> Seek<&'a [u8]> + for<'a> Decode<&'a [u8]> + 'static,
+    {
+        self.routes.push(Route {
+            sentinel,
+            decode: Box::new(move |input, start| decode_at::<T>(input, start).map(|(packet, end)| (into(packet), end))),
+        });
+        // ---- stable, to keep ties in registration order
+        self.routes.sort_by_key(|route| std::cmp::Reverse(route.sentinel.bytes.len()));
+        self
+    }
+
+    /// Iterates over the packets of `input`
+    pub fn iter<'a>(&self, input: &'a [u8]) -> RouterIter<'_, 'a, O> {
+        RouterIter { router: self, input, position: 0 }
+    }
+
+    /// Returns the offset of the first sentinel or universal label in
+    /// `input`, at or after `from`
+    fn next_candidate(&self, input: &[u8], from: usize) -> Option<usize> {
+        self.routes
+            .iter()
+            .map(|route| route.sentinel)
+            .chain(std::iter::once(Sentinel::exact(&UL_PREFIX)))
+            .filter_map(|sentinel| sentinel.find(input, from))
+            .min()
+    }
+}
+/// [`KlvRouter`] implementation of [`Default`]
+impl<O> Default for KlvRouter<O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over the packets of a `&[u8]` stream, see [`KlvRouter::iter`]
+///
+/// Bad packets and unrecognized bytes are yielded as a [`PacketError`], with
+/// the bytes skipped to resynchronize with the next sentinel or universal label
+pub struct RouterIter<'r, 'a, O> {
+    router: &'r KlvRouter<O>,
+    input: &'a [u8],
+    position: usize,
+}
+/// [`RouterIter`] implementation
+impl<O> RouterIter<'_, '_, O> {
+    /// Returns the offset of the next byte to be read, into the iterated input
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Skips to the next candidate after `from`, returning the skipped bytes
+    fn skip_from(&mut self, from: usize, error: Option<KlvError>) -> PacketError {
+        let end = self.router.next_candidate(self.input, from).unwrap_or(self.input.len());
+        let skipped = self.position..end;
+        self.position = end;
+        PacketError { skipped, error }
+    }
+}
+/// [`RouterIter`] implementation of [`Iterator`]
+impl<'a, O> Iterator for RouterIter<'_, 'a, O> {
+    type Item = Result<Routed<'a, O>, PacketError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+        let rest = self.input.get(start..).filter(|rest| !rest.is_empty())?;
+        // --------------------------------------------------
+        // registered types, longest sentinel first
+        // --------------------------------------------------
+        if let Some(route) = self.router.routes.iter().find(|route| route.sentinel.is_prefix_of(rest)) {
+            return match (route.decode)(self.input, start) {
+                Ok((packet, end)) => {
+                    self.position = end;
+                    Some(Ok(Routed::Packet(packet)))
+                },
+                Err(error) => Some(Err(self.skip_from(start + 1, Some(error)))),
+            }
+        }
+        // --------------------------------------------------
+        // unregistered universal labels
+        // --------------------------------------------------
+        if rest.starts_with(&UL_PREFIX) {
+            let mut value = rest.get(UL_LEN..).unwrap_or_default();
+            return match crate::codecs::ber::dec::ber_length(&mut value)
+                .and_then(|len| winnow::token::take(len).parse_next(&mut value))
+            {
+                Ok(_) => {
+                    let len = rest.len() - value.len();
+                    self.position = start + len;
+                    Some(Ok(Routed::Unmatched { offset: start, bytes: &rest[..len] }))
+                },
+                Err(err) => {
+                    let mut error = KlvError::from(err);
+                    error.offset += start;
+                    Some(Err(self.skip_from(start + 1, Some(error))))
+                },
+            }
+        }
+        Some(Err(self.skip_from(start, None)))
+    }
+}
@@ -0,0 +1,468 @@
+//! KLV over RTP (RFC 6597)
+//!
+//! RFC 6597 carries KLV units over RTP: a KLV unit (all the KLV items to be
+//! presented at one time) is split across one or more packets, all with the
+//! same timestamp, and the marker bit is set on the last packet of the unit.
+//!
+//! * [`RtpPacket`] parses and encodes RTP packets (RFC 3550)
+//! * [`Depacketizer`] reassembles [`KlvUnit`]s in sequence order, discarding
+//!   units with lost packets
+//! * [`Packetizer`] fragments KLV units into packets of at most a given MTU
+//!
+//! # Example
+//!
+//! ```
+//! use tinyklv::Klv;
+//! use tinyklv::prelude::*;
+//! use tinyklv::rtp::{Depacketizer, Packetizer};
+//!
+//! #[derive(Klv, Debug, PartialEq)]
+//! #[klv(
+//!     sentinel = b"\x06\x0E",
+//!     key(dec = tinyklv::dec::binary::u8),
+//!     len(dec = tinyklv::dec::binary::u8_as_usize),
+//! )]
+//! struct Packet {
+//!     #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+//!     id: u16,
+//! }
+//!
+//! // ---- a 7-byte unit, in packets of 2 bytes of payload
+//! let mut packetizer = Packetizer::new(96, 0x1234, 14);
+//! let packets = packetizer.packetize(&[0x06, 0x0E, 0x04, 0x01, 0x02, 0x00, 0x2A], 90_000);
+//! assert_eq!(packets.len(), 4);
+//!
+//! let mut depacketizer = Depacketizer::new();
+//! let units: Vec<_> = packets
+//!     .iter()
+//!     .filter_map(|packet| depacketizer.push(packet).unwrap())
+//!     .collect();
+//! assert_eq!(units.len(), 1);
+//! assert_eq!(units[0].timestamp, 90_000);
+//! assert_eq!(units[0].extract::<Packet>(), Ok(Packet { id: 42 }));
+//! ```
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::prelude::*;
+
+/// The length of the fixed part of an RTP header, in bytes
+pub const HEADER_LEN: usize = 12;
+
+/// The RTP version, the only one supported
+pub const VERSION: u8 = 2;
+
+/// The usual clock rate of RTP timestamps for KLV, in Hz
+pub const CLOCK_RATE: u32 = 90_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Error of parsing an [`RtpPacket`]
+pub enum RtpError {
+    /// The packet is shorter than its header says
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+    /// The version is not [`VERSION`]
+    Version(u8),
+    /// The padding count is zero, or larger than the payload
+    Padding {
+        count: u8,
+        payload: usize,
+    },
+}
+/// [`RtpError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for RtpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RtpError::Truncated { expected, actual } => write!(f, "RTP packet of {} bytes, expected at least {}", actual, expected),
+            RtpError::Version(version) => write!(f, "RTP version {}, expected {}", version, VERSION),
+            RtpError::Padding { count, payload } => write!(f, "RTP padding of {} bytes, with a payload of {} bytes", count, payload),
+        }
+    }
+}
+/// [`RtpError`] implementation of [`std::error::Error`]
+impl std::error::Error for RtpError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A header extension of an RTP packet (RFC 3550 5.3.1)
+pub struct RtpExtension {
+    /// The profile-defined identifier
+    pub profile: u16,
+    /// The data of the extension, padded with zeros to a multiple of 4 bytes
+    /// when encoded
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The header of an RTP packet (RFC 3550 5.1)
+///
+/// The version is always [`VERSION`], and padding is removed when parsing
+pub struct RtpHeader {
+    /// The marker bit, set on the last packet of a KLV unit
+    pub marker: bool,
+    /// The payload type, of 7 bits (dynamic for KLV, usually 96 to 127)
+    pub payload_type: u8,
+    /// The sequence number, incremented by one for each packet
+    pub sequence: u16,
+    /// The timestamp, shared by all packets of a KLV unit, see [`CLOCK_RATE`]
+    pub timestamp: u32,
+    /// The synchronization source
+    pub ssrc: u32,
+    /// The contributing sources, at most 15
+    pub csrc: Vec<u32>,
+    /// The header extension, if any
+    pub extension: Option<RtpExtension>,
+}
+/// [`RtpHeader`] implementation of [`EncodeValue`] for [`Vec<u8>`]
+impl EncodeValue<Vec<u8>> for RtpHeader {
+    fn encode_value(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(HEADER_LEN + 4 * self.csrc.len());
+        output.push((VERSION << 6) | ((self.extension.is_some() as u8) << 4) | (self.csrc.len() as u8 & 0x0F));
+        output.push(((self.marker as u8) << 7) | (self.payload_type & 0x7F));
+        output.extend(self.sequence.to_be_bytes());
+        output.extend(self.timestamp.to_be_bytes());
+        output.extend(self.ssrc.to_be_bytes());
+        for csrc in self.csrc.iter().take(15) {
+            output.extend(csrc.to_be_bytes());
+        }
+        if let Some(extension) = &self.extension {
+            let words = extension.data.len().div_ceil(4);
+            output.extend(extension.profile.to_be_bytes());
+            output.extend((words as u16).to_be_bytes());
+            output.extend(&extension.data);
+            output.resize(output.len() + 4 * words - extension.data.len(), 0);
+        }
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An RTP packet, of a header and a payload borrowed from the datagram
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::rtp::RtpPacket;
+///
+/// let datagram = [
+///     0x80, 0xE0, 0x00, 0x07, 0x00, 0x01, 0x5F, 0x90, 0xDE, 0xAD, 0xBE, 0xEF,
+///     0x06, 0x0E, 0x2B, 0x34,
+/// ];
+/// let packet = RtpPacket::parse(&datagram).unwrap();
+/// assert!(packet.header.marker);
+/// assert_eq!(packet.header.payload_type, 96);
+/// assert_eq!(packet.header.sequence, 7);
+/// assert_eq!(packet.header.timestamp, 90_000);
+/// assert_eq!(packet.payload, &[0x06, 0x0E, 0x2B, 0x34]);
+/// assert_eq!(packet.encode(), datagram);
+/// ```
+pub struct RtpPacket<'a> {
+    pub header: RtpHeader,
+    /// The payload, without padding
+    pub payload: &'a [u8],
+}
+/// [`RtpPacket`] implementation
+impl<'a> RtpPacket<'a> {
+    /// Parses an RTP packet from a whole datagram
+    pub fn parse(datagram: &'a [u8]) -> Result<Self, RtpError> {
+        let need = |expected: usize| match datagram.len() >= expected {
+            true => Ok(()),
+            false => Err(RtpError::Truncated { expected, actual: datagram.len() }),
+        };
+        let be_u16 = |at: usize| u16::from_be_bytes([datagram[at], datagram[at + 1]]);
+        let be_u32 = |at: usize| u32::from_be_bytes([datagram[at], datagram[at + 1], datagram[at + 2], datagram[at + 3]]);
+
+        need(HEADER_LEN)?;
+        let version = datagram[0] >> 6;
+        if version != VERSION {
+            return Err(RtpError::Version(version))
+        }
+        let padding = datagram[0] & 0x20 != 0;
+        let has_extension = datagram[0] & 0x10 != 0;
+        let csrc_count = (datagram[0] & 0x0F) as usize;
+
+        let mut position = HEADER_LEN + 4 * csrc_count;
+        need(position)?;
+        let csrc = (0..csrc_count).map(|index| be_u32(HEADER_LEN + 4 * index)).collect();
+        let extension = match has_extension {
+            true => {
+                need(position + 4)?;
+                let profile = be_u16(position);
+                let len = 4 * be_u16(position + 2) as usize;
+                position += 4;
+                need(position + len)?;
+                let data = datagram[position..position + len].to_vec();
+                position += len;
+                Some(RtpExtension { profile, data })
+            },
+            false => None,
+        };
+        let mut payload = &datagram[position..];
+        if padding {
+            let count = payload.last().copied().unwrap_or(0);
+            if count == 0 || count as usize > payload.len() {
+                return Err(RtpError::Padding { count, payload: payload.len() })
+            }
+            payload = &payload[..payload.len() - count as usize];
+        }
+        Ok(Self {
+            header: RtpHeader {
+                marker: datagram[1] & 0x80 != 0,
+                payload_type: datagram[1] & 0x7F,
+                sequence: be_u16(2),
+                timestamp: be_u32(4),
+                ssrc: be_u32(8),
+                csrc,
+                extension,
+            },
+            payload,
+        })
+    }
+
+    /// Encodes the packet into a datagram, without padding
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output = self.header.encode_value();
+        output.extend(self.payload);
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A reassembled KLV unit, see [`Depacketizer`]
+pub struct KlvUnit {
+    /// The RTP timestamp of the unit, see [`CLOCK_RATE`]
+    pub timestamp: u32,
+    /// The synchronization source of the unit
+    pub ssrc: u32,
+    /// The sequence number of the first packet of the unit
+    pub sequence: u16,
+    /// The bytes of the unit, of one or more KLV items
+    pub data: Vec<u8>,
+}
+/// [`KlvUnit`] implementation
+impl KlvUnit {
+    /// Extracts the first `T` of the unit, see [`Extract`]
+    ///
+    /// Offsets of the error are into [`KlvUnit::data`]
+    pub fn extract<'a, T>(&'a self) -> Result<T, KlvError>
+    where
+        T: Seek<&'a [u8]> + Decode<&'a [u8]>,
+    {
+        decode_at::<T>(&self.data, 0).map(|(packet, _)| packet)
+    }
+
+    /// Iterates over every `T` of the unit, see [`PacketIter`]
+    pub fn packets<T: KlvSchema>(&self) -> PacketIter<'_, T> {
+        PacketIter::new(&self.data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Counters of a [`Depacketizer`]
+pub struct RtpStats {
+    /// The number of packets pushed, including the late ones
+    pub packets: u64,
+    /// The number of packets lost, from gaps in sequence numbers
+    pub lost: u64,
+    /// The number of packets dropped because they arrived late or twice
+    pub late: u64,
+    /// The number of complete units
+    pub units: u64,
+    /// The number of units discarded because of lost packets, a missing
+    /// marker bit or a change of source
+    pub discarded: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+/// Reassembles [`KlvUnit`]s from RTP packets, in sequence order
+///
+/// Packets are expected in order. A packet older than the last one (late or
+/// duplicate, taking wraparound into account) is dropped. On a gap in
+/// sequence numbers, the unit in progress is discarded, as is the unit of the
+/// next packet, which may have lost its start: packets are dropped up to the
+/// next marker bit, or the next change of timestamp.
+///
+/// A change of timestamp without a marker bit discards the unit in progress,
+/// and a change of SSRC starts over. See [`RtpStats`] for the counters.
+pub struct Depacketizer {
+    /// The source of the packets, and the next expected sequence number
+    source: Option<(u32, u16)>,
+    /// The unit in progress
+    unit: Option<KlvUnit>,
+    /// The timestamp of the unit being dropped, after a loss
+    discarding: Option<u32>,
+    stats: RtpStats,
+}
+/// [`Depacketizer`] implementation
+impl Depacketizer {
+    /// Creates a depacketizer, which treats its first packet as the start
+    /// of a unit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counters of the depacketizer
+    pub fn stats(&self) -> RtpStats {
+        self.stats
+    }
+
+    /// Parses and pushes a datagram, returning the unit it completes, if any
+    pub fn push(&mut self, datagram: &[u8]) -> Result<Option<KlvUnit>, RtpError> {
+        RtpPacket::parse(datagram).map(|packet| self.push_packet(&packet))
+    }
+
+    /// Pushes a parsed packet, returning the unit it completes, if any
+    pub fn push_packet(&mut self, packet: &RtpPacket) -> Option<KlvUnit> {
+        let header = &packet.header;
+        self.stats.packets += 1;
+        // --------------------------------------------------
+        // sequence order, per source
+        // --------------------------------------------------
+        match self.source {
+            Some((ssrc, expected)) if ssrc == header.ssrc => {
+                let gap = header.sequence.wrapping_sub(expected);
+                if gap >= 0x8000 {
+                    self.stats.late += 1;
+                    return None
+                }
+                if gap > 0 {
+                    self.stats.lost += gap as u64;
+                    if let Some(unit) = self.unit.take() {
+                        if unit.timestamp != header.timestamp {
+                            self.stats.discarded += 1;
+                        }
+                    }
+                    if self.discarding != Some(header.timestamp) {
+                        self.stats.discarded += 1;
+                    }
+                    self.discarding = Some(header.timestamp);
+                }
+            },
+            Some(_) => {
+                if self.unit.take().is_some() {
+                    self.stats.discarded += 1;
+                }
+                self.discarding = None;
+            },
+            None => (),
+        }
+        self.source = Some((header.ssrc, header.sequence.wrapping_add(1)));
+        // --------------------------------------------------
+        // drop the packets of a unit with lost packets
+        // --------------------------------------------------
+        if let Some(timestamp) = self.discarding {
+            if timestamp == header.timestamp {
+                if header.marker {
+                    self.discarding = None;
+                }
+                return None
+            }
+            self.discarding = None;
+        }
+        // --------------------------------------------------
+        // a new timestamp is a new unit, even without a marker bit
+        // --------------------------------------------------
+        if self.unit.as_ref().is_some_and(|unit| unit.timestamp != header.timestamp) {
+            self.unit = None;
+            self.stats.discarded += 1;
+        }
+        let unit = self.unit.get_or_insert_with(|| KlvUnit {
+            timestamp: header.timestamp,
+            ssrc: header.ssrc,
+            sequence: header.sequence,
+            data: Vec::new(),
+        });
+        unit.data.extend(packet.payload);
+        if !header.marker {
+            return None
+        }
+        self.stats.units += 1;
+        self.unit.take()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Fragments KLV units into RTP packets of at most a given MTU
+///
+/// All packets of a unit share its timestamp, and the last one has the
+/// marker bit set. Sequence numbers increase by one for each packet, and
+/// wrap around.
+///
+/// # Example
+///
+/// ```
+/// use tinyklv::rtp::{Packetizer, RtpPacket};
+///
+/// let mut packetizer = Packetizer::new(96, 0xDEADBEEF, 20).with_sequence(0xFFFF);
+/// let packets = packetizer.packetize(&[0xAA; 10], 0);
+/// assert_eq!(packets.len(), 2);
+/// assert!(packets.iter().all(|packet| packet.len() <= 20));
+///
+/// let last = RtpPacket::parse(&packets[1]).unwrap();
+/// assert_eq!(last.header.sequence, 0x0000);
+/// assert!(last.header.marker);
+/// assert_eq!(last.payload, &[0xAA; 2]);
+/// assert_eq!(packetizer.sequence(), 0x0001);
+/// ```
+pub struct Packetizer {
+    payload_type: u8,
+    ssrc: u32,
+    sequence: u16,
+    mtu: usize,
+}
+/// [`Packetizer`] implementation
+impl Packetizer {
+    /// Creates a packetizer, whose datagrams are at most `mtu` bytes long,
+    /// starting at sequence number 0
+    ///
+    /// # Panics
+    ///
+    /// If `mtu` leaves no room for a payload after the header
+    pub fn new(payload_type: u8, ssrc: u32, mtu: usize) -> Self {
+        assert!(mtu > HEADER_LEN, "an MTU of {} bytes leaves no room for a payload", mtu);
+        Self { payload_type: payload_type & 0x7F, ssrc, sequence: 0, mtu }
+    }
+
+    /// Starts at sequence number `sequence`, which should be random (RFC 3550 5.1)
+    pub fn with_sequence(mut self, sequence: u16) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Returns the sequence number of the next packet
+    pub fn sequence(&self) -> u16 {
+        self.sequence
+    }
+
+    /// Fragments a KLV unit into datagrams, all with `timestamp`
+    ///
+    /// An empty unit is a single packet with an empty payload
+    pub fn packetize(&mut self, unit: &[u8], timestamp: u32) -> Vec<Vec<u8>> {
+        let mut chunks: Vec<&[u8]> = unit.chunks(self.mtu - HEADER_LEN).collect();
+        if chunks.is_empty() {
+            chunks.push(&[]);
+        }
+        let last = chunks.len() - 1;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                let packet = RtpPacket {
+                    header: RtpHeader {
+                        marker: index == last,
+                        payload_type: self.payload_type,
+                        sequence: self.sequence,
+                        timestamp,
+                        ssrc: self.ssrc,
+                        ..Default::default()
+                    },
+                    payload,
+                };
+                self.sequence = self.sequence.wrapping_add(1);
+                packet.encode()
+            })
+            .collect()
+    }
+}
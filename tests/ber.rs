@@ -0,0 +1,70 @@
+use tinyklv::prelude::*;
+use tinyklv::codecs::ber::{BerLength, BerLengthConfig, BerLengthError};
+
+/// Decodes a [`BerLength`], returning the [`BerLengthError`] cause on failure
+fn decode<T: tinyklv::codecs::ber::OfBerLength>(input: &[u8], config: &BerLengthConfig) -> Result<u128, Option<BerLengthError>> {
+    BerLength::<T>::decode_with(&mut &input[..], config)
+        .map(|len| len.as_u128())
+        .map_err(|e| e
+            .into_inner()
+            .and_then(|e| e.cause().and_then(|c| c.downcast_ref::<BerLengthError>()).copied())
+        )
+}
+
+#[test]
+fn indefinite_and_reserved() {
+    let lenient = BerLengthConfig::new();
+    assert_eq!(decode::<u64>(&[0x80], &lenient), Err(Some(BerLengthError::Indefinite)));
+    assert_eq!(decode::<u64>(&[0xFF], &lenient), Err(Some(BerLengthError::Reserved)));
+}
+
+#[test]
+fn overflow() {
+    let lenient = BerLengthConfig::new();
+    // does not fit the target type
+    assert_eq!(decode::<u8>(&[0x82, 0x01, 0x00], &lenient), Err(Some(BerLengthError::Overflow)));
+    assert_eq!(decode::<u16>(&[0x82, 0x01, 0x00], &lenient), Ok(256));
+    // 17 significant bytes do not fit a u128
+    let mut input = vec![0x80 + 17];
+    input.extend([0x01; 17]);
+    assert_eq!(decode::<u128>(&input, &lenient), Err(Some(BerLengthError::Overflow)));
+    // leading zero bytes are not significant
+    let mut input = vec![0x80 + 20];
+    input.extend([0x00; 19]);
+    input.push(0x05);
+    assert_eq!(decode::<u128>(&input, &lenient), Ok(5));
+}
+
+#[test]
+fn der_strict() {
+    let der = BerLengthConfig::new().der();
+    assert_eq!(decode::<u64>(&[0x05], &der), Ok(5));
+    assert_eq!(decode::<u64>(&[0x81, 0x05], &der), Err(Some(BerLengthError::NonCanonical)));
+    assert_eq!(decode::<u64>(&[0x82, 0x00, 0xC8], &der), Err(Some(BerLengthError::NonCanonical)));
+    assert_eq!(decode::<u64>(&[0x81, 0xC8], &der), Ok(200));
+    // lenient accepts the same encodings
+    let lenient = BerLengthConfig::new();
+    assert_eq!(decode::<u64>(&[0x81, 0x05], &lenient), Ok(5));
+    assert_eq!(decode::<u64>(&[0x82, 0x00, 0xC8], &lenient), Ok(200));
+}
+
+#[test]
+fn maximum_length() {
+    let max = BerLengthConfig::new().max(200);
+    assert_eq!(decode::<u64>(&[0x81, 0xC8], &max), Ok(200));
+    assert_eq!(decode::<u64>(&[0x81, 0xC9], &max), Err(Some(BerLengthError::TooLong { len: 201, max: 200 })));
+    assert!(tinyklv::dec::ber::ber_length_max::<200>(&mut &[0x84, 0xFF, 0xFF, 0xFF, 0xFF][..]).is_err());
+}
+
+#[test]
+fn truncated() {
+    let lenient = BerLengthConfig::new();
+    assert_eq!(decode::<u64>(&[], &lenient), Err(None));
+    assert!(BerLength::<u64>::decode(&mut &[0x83, 0x01][..]).is_err());
+}
+
+#[test]
+fn usize_decoder() {
+    assert_eq!(tinyklv::dec::ber::ber_length(&mut &[0x82, 0x01, 0x00][..]).unwrap(), 256);
+    assert!(tinyklv::dec::ber::ber_length_der(&mut &[0x82, 0x00, 0x01][..]).is_err());
+}
@@ -0,0 +1,123 @@
+use tinyklv::{Klv, KlvBits};
+use tinyklv::prelude::*;
+use tinyklv::codecs::bits::BitField;
+
+#[derive(KlvBits, Debug, PartialEq)]
+/// MISB ST 0601 Generic Flag Data (tag 47)
+struct GenericFlags {
+    #[bits(0)]
+    laser_range: bool,
+    #[bits(1)]
+    auto_track: bool,
+    #[bits(2)]
+    ir_polarity: bool,
+    #[bits(3)]
+    icing: bool,
+    #[bits(4..=5)]
+    slant_range: u8,
+    #[bits(6)]
+    image_invalid: bool,
+}
+
+#[derive(Debug, PartialEq, Default)]
+enum Mode {
+    #[default]
+    Off,
+    On,
+    Auto,
+    Reserved(u8),
+}
+impl BitField for Mode {
+    const BITS: u32 = u8::BITS;
+    fn from_bits(bits: u128) -> Self {
+        match bits {
+            0 => Mode::Off,
+            1 => Mode::On,
+            2 => Mode::Auto,
+            x => Mode::Reserved(x as u8),
+        }
+    }
+    fn to_bits(&self) -> u128 {
+        match self {
+            Mode::Off => 0,
+            Mode::On => 1,
+            Mode::Auto => 2,
+            Mode::Reserved(x) => *x as u128,
+        }
+    }
+}
+
+#[derive(KlvBits, Debug, PartialEq)]
+#[bits(bytes = 2)]
+struct Wide(
+    #[bits(0..4)]
+    u8,
+    #[bits(12..=13)]
+    Mode,
+    #[bits(15)]
+    bool,
+    String,
+);
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Packet {
+    #[klv(key = 0x2F, enc = GenericFlags::encode_value, dec = GenericFlags::decode)]
+    flags: GenericFlags,
+
+    #[klv(key = 0x30, enc = encode_opt_wide, dec = Wide::decode)]
+    wide: Option<Wide>,
+}
+
+fn encode_opt_wide(input: &Option<Wide>) -> Option<Vec<u8>> {
+    input.as_ref().map(Wide::encode_value)
+}
+
+#[test]
+fn single_byte_flags() {
+    let flags = GenericFlags::decode(&mut &[0b0110_0101][..]).unwrap();
+    assert_eq!(GenericFlags::BYTES, 1);
+    assert_eq!(flags, GenericFlags {
+        laser_range: true,
+        auto_track: false,
+        ir_polarity: true,
+        icing: false,
+        slant_range: 2,
+        image_invalid: true,
+    });
+    assert_eq!(flags.encode_value(), vec![0b0110_0101]);
+}
+
+#[test]
+fn multi_byte_word_is_big_endian() {
+    let encoded = [0b1010_0000, 0b0000_1001];
+    let decoded = Wide::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(Wide::BYTES, 2);
+    assert_eq!(decoded, Wide(9, Mode::Auto, true, String::new()));
+    assert_eq!(decoded.encode_value(), encoded);
+}
+
+#[test]
+fn out_of_range_values_are_truncated() {
+    let value = Wide(0xFF, Mode::Reserved(7), false, String::from("ignored"));
+    assert_eq!(value.encode_value(), vec![0b0011_0000, 0b0000_1111]);
+}
+
+#[test]
+fn short_input_fails() {
+    assert!(Wide::decode(&mut &[0x00][..]).is_err());
+}
+
+#[test]
+fn bits_as_klv_field() {
+    let encoded = [0x2F, 0x01, 0b0000_0011, 0x30, 0x02, 0b0001_0000, 0x01];
+    let decoded = Packet::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded.wide, Some(Wide(1, Mode::On, false, String::new())));
+    assert!(decoded.flags.laser_range && decoded.flags.auto_track);
+    assert_eq!(decoded.encode_value(), encoded);
+}
@@ -0,0 +1,114 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+const DEFAULT_HEADING: u16 = 90;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Defaults {
+    #[klv(key = 0x01, default = DEFAULT_HEADING, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: u16,
+
+    #[klv(key = 0x02, required = false, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    pitch: u16,
+
+    #[klv(key = 0x03, default = 7, enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    roll: Option<u16>,
+
+    #[klv(key = 0x04, dyn = true, default = String::from("N/A"), skip_if = String::is_empty, enc = encode_string, dec = tinyklv::dec::binary::to_string_utf8)]
+    name: String,
+
+    #[klv(key = 0x05, skip_if = is_zero, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    counter: u16,
+}
+
+#[derive(Klv, Debug)]
+#[klv(
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Required {
+    #[klv(key = 0x01, required = true, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+}
+
+fn is_zero(input: &u16) -> bool {
+    *input == 0
+}
+
+fn encode_u16(input: &u16) -> Vec<u8> {
+    tinyklv::enc::binary::be_u16(*input)
+}
+
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> {
+    input.map(tinyklv::enc::binary::be_u16)
+}
+
+fn encode_string(input: &String) -> Vec<u8> {
+    input.as_bytes().to_vec()
+}
+
+#[test]
+fn missing_fields_use_defaults() {
+    let decoded = Defaults::decode(&mut &[0x05, 0x02, 0x00, 0x03][..]).unwrap();
+    assert_eq!(decoded, Defaults {
+        heading: DEFAULT_HEADING,
+        pitch: 0,
+        roll: Some(7),
+        name: String::from("N/A"),
+        counter: 3,
+    });
+}
+
+#[test]
+fn present_fields_override_defaults() {
+    let decoded = Defaults::decode(&mut &[
+        0x01, 0x02, 0x00, 0x01,
+        0x02, 0x02, 0x00, 0x02,
+        0x03, 0x02, 0x00, 0x03,
+        0x04, 0x02, b'h', b'i',
+        0x05, 0x02, 0x00, 0x04,
+    ][..]).unwrap();
+    assert_eq!(decoded, Defaults {
+        heading: 1,
+        pitch: 2,
+        roll: Some(3),
+        name: String::from("hi"),
+        counter: 4,
+    });
+}
+
+#[test]
+fn required_option_fails_when_missing() {
+    assert!(Required::decode(&mut &[0x02, 0x02, 0x00, 0x01][..]).is_err());
+    let decoded = Required::decode(&mut &[0x01, 0x02, 0x00, 0x01][..]).unwrap();
+    assert_eq!(decoded.heading, Some(1));
+}
+
+#[test]
+fn skip_if_omits_fields_on_encode() {
+    let value = Defaults {
+        heading: 1,
+        pitch: 2,
+        roll: None,
+        name: String::new(),
+        counter: 0,
+    };
+    assert_eq!(value.encode_value(), vec![
+        0x01, 0x02, 0x00, 0x01,
+        0x02, 0x02, 0x00, 0x02,
+    ]);
+}
+
+#[test]
+fn schema_reflects_required() {
+    assert!(Defaults::field_by_name("heading").unwrap().optional);
+    assert!(Defaults::field_by_name("pitch").unwrap().optional);
+    assert!(!Defaults::field_by_name("counter").unwrap().optional);
+    assert!(!Required::field_by_name("heading").unwrap().optional);
+}
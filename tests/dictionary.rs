@@ -0,0 +1,49 @@
+#![cfg(feature = "dictionary")]
+use tinyklv::codecs::ul::{dictionary, UniversalLabel};
+
+#[test]
+fn lookup_groups_elements_and_labels() {
+    let header: UniversalLabel = "06.0E.2B.34.02.05.01.01.0D.01.02.01.01.02.04.00".parse().unwrap();
+    let entry = dictionary::lookup(&header).unwrap();
+    assert_eq!(entry.register, "Groups");
+    assert_eq!(entry.name, "Closed Complete Header Partition Pack");
+    assert_eq!(entry.ty, None);
+
+    let instance_uid: UniversalLabel = "urn:smpte:ul:060e2b34.01010101.01011502.00000000".parse().unwrap();
+    let entry = instance_uid.entry().unwrap();
+    assert_eq!(entry.register, "Elements");
+    assert_eq!(entry.name, "Instance UID");
+    assert_eq!(entry.ty, Some("UUID"));
+
+    let op1a: UniversalLabel = "06.0E.2B.34.04.01.01.01.0D.01.02.01.01.01.09.00".parse().unwrap();
+    assert_eq!(op1a.entry().map(|entry| entry.register), Some("Labels"));
+}
+
+#[test]
+fn lookup_ignores_version() {
+    let primer: UniversalLabel = "06.0E.2B.34.02.05.01.01.0D.01.02.01.01.05.01.00".parse().unwrap();
+    assert_eq!(primer.with_version(0x0A).name(), Some("Primer Pack"));
+}
+
+#[test]
+fn unknown_labels() {
+    assert_eq!(UniversalLabel::new([0; 16]).entry(), None);
+    assert!(dictionary::len() > 0);
+}
+
+#[test]
+fn lookup_ignores_group_coding() {
+    // ---- the Groups Register leaves byte 5 open, as `0x7F`
+    let primer: UniversalLabel = "06.0E.2B.34.02.7F.01.01.0D.01.02.01.01.05.01.00".parse().unwrap();
+    assert_eq!(dictionary::normalize(&primer), *primer.with_version(0).as_bytes());
+    for coding in [0x05, 0x53, 0x7F] {
+        let mut bytes = *primer.as_bytes();
+        bytes[5] = coding;
+        assert_eq!(UniversalLabel::new(bytes).name(), Some("Primer Pack"));
+    }
+    // ---- only for groups
+    let instance_uid: UniversalLabel = "urn:smpte:ul:060e2b34.01010101.01011502.00000000".parse().unwrap();
+    let mut bytes = *instance_uid.as_bytes();
+    bytes[5] = 0x7F;
+    assert_eq!(UniversalLabel::new(bytes).entry(), None);
+}
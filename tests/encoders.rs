@@ -0,0 +1,81 @@
+//! Round trips for each decoder / encoder pair
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = 0x01,
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Mission {
+    #[klv(key = 0x02, dyn = true, enc = tinyklv::enc::binary::from_string_utf8, dec = tinyklv::dec::binary::to_string_utf8)]
+    name: String,
+
+    #[klv(key = 0x03, dyn = true, enc = tinyklv::enc::binary::from_string_utf16, dec = tinyklv::dec::binary::to_string_utf16)]
+    platform: String,
+
+    #[klv(key = 0x04, enc = encode_id, dec = tinyklv::dec::binary::be_u32_lengthed(3))]
+    id: u32,
+}
+
+fn encode_id(input: &u32) -> Vec<u8> {
+    tinyklv::enc::binary::be_u32_lengthed(3)(*input)
+}
+
+#[test]
+fn strings() {
+    let value = "MISSION01 ✈";
+    let encoded = tinyklv::enc::binary::from_string_utf8(value);
+    assert_eq!(tinyklv::dec::binary::to_string_utf8(encoded.len())(&mut &encoded[..]).unwrap(), value);
+    assert_eq!(tinyklv::dec::binary::to_string_utf8_strict(encoded.len())(&mut &encoded[..]).unwrap(), value);
+
+    let encoded = tinyklv::enc::binary::from_string_utf16(value);
+    assert_eq!(encoded.len(), 2 * value.encode_utf16().count());
+    assert_eq!(tinyklv::dec::binary::to_string_utf16(encoded.len())(&mut &encoded[..]).unwrap(), value);
+}
+
+#[test]
+#[cfg(feature = "ascii")]
+fn ascii() {
+    let encoded = tinyklv::enc::binary::from_string_ascii("AF-101");
+    assert_eq!(tinyklv::dec::binary::to_string_ascii(encoded.len())(&mut &encoded[..]).unwrap(), "AF-101");
+    // non-ascii characters are replaced, so the output always decodes
+    let encoded = tinyklv::enc::binary::from_string_ascii("AF-1✈1");
+    assert_eq!(tinyklv::dec::binary::to_string_ascii(encoded.len())(&mut &encoded[..]).unwrap(), "AF-1?1");
+}
+
+#[test]
+fn lengthed() {
+    let encoded = tinyklv::enc::binary::be_u32_lengthed(3)(0x0001E0FF);
+    assert_eq!(tinyklv::dec::binary::be_u32_lengthed(3)(&mut &encoded[..]).unwrap(), 0x0001E0FF);
+    let encoded = tinyklv::enc::binary::le_u64_lengthed(5)(0x00_00_00_12_34_56_78_9A);
+    assert_eq!(tinyklv::dec::binary::le_u64_lengthed(5)(&mut &encoded[..]).unwrap(), 0x12_34_56_78_9A);
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn chrono() {
+    let date = chrono::NaiveDate::from_ymd_opt(2020, 12, 31).unwrap();
+    let encoded = tinyklv::enc::binary::from_date("%Y-%m-%d")(&date);
+    assert_eq!(tinyklv::as_date!(tinyklv::dec::binary::to_string_utf8, "%Y-%m-%d", encoded.len())(&mut &encoded[..]), Ok(date));
+
+    let time = chrono::NaiveTime::from_hms_opt(12, 34, 56).unwrap();
+    let encoded = tinyklv::enc::binary::from_time("%H%M%S")(&time);
+    assert_eq!(encoded, b"123456");
+    assert_eq!(tinyklv::as_time!(tinyklv::dec::binary::to_string_utf8, "%H%M%S", 6)(&mut &encoded[..]), Ok(time));
+
+    let datetime = date.and_time(time);
+    let encoded = tinyklv::enc::binary::from_datetime("%Y-%m-%dT%H:%M:%S")(&datetime);
+    assert_eq!(tinyklv::as_datetime!(tinyklv::dec::binary::to_string_utf8, "%Y-%m-%dT%H:%M:%S", encoded.len())(&mut &encoded[..]), Ok(datetime));
+}
+
+#[test]
+fn derived_encode() {
+    let value = Mission { name: "MISSION01".into(), platform: "MQ-9 ✈".into(), id: 0x0001E0FF };
+    let encoded = value.encode();
+    assert_eq!(&encoded[..2], &[0x01, (encoded.len() - 2) as u8]);
+    assert_eq!(Mission::extract(&mut &encoded[..]).unwrap(), value);
+}
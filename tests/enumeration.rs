@@ -0,0 +1,86 @@
+use tinyklv::{Klv, KlvEnum};
+use tinyklv::prelude::*;
+
+#[derive(KlvEnum, Debug, Clone, Copy, PartialEq)]
+/// MISB ST 0102 security classification
+enum Classification {
+    #[klv(value = 0x01)]
+    Unclassified,
+    #[klv(value = 0x02)]
+    Restricted,
+    #[klv(value = 0x03)]
+    Confidential,
+    #[klv(value = 0x04)]
+    Secret,
+    #[klv(value = 0x05)]
+    TopSecret,
+}
+
+#[derive(KlvEnum, Debug, Clone, Copy, PartialEq)]
+#[repr(u16)]
+enum SensorType {
+    Eo = 0x0001,
+    Ir = 0x0002,
+    #[klv(value = 0x0100)]
+    Sar,
+    Unknown(u16),
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Security {
+    #[klv(key = 0x01, enc = Classification::encode_value, dec = Classification::decode)]
+    classification: Classification,
+
+    #[klv(key = 0x02, enc = encode_opt_sensor, dec = SensorType::decode)]
+    sensor: Option<SensorType>,
+}
+
+fn encode_opt_sensor(input: &Option<SensorType>) -> Option<Vec<u8>> {
+    input.as_ref().map(SensorType::encode_value)
+}
+
+#[test]
+fn values_round_trip() {
+    assert_eq!(Classification::decode(&mut &[0x03][..]).unwrap(), Classification::Confidential);
+    assert_eq!(Classification::TopSecret.encode_value(), vec![0x05]);
+    assert_eq!(Classification::from_value(0x02), Some(Classification::Restricted));
+    assert_eq!(Classification::Secret.value(), 0x04);
+}
+
+#[test]
+fn unknown_value_without_fallback_fails() {
+    let mut input: &[u8] = &[0x09, 0xFF];
+    assert!(Classification::decode(&mut input).is_err());
+    assert_eq!(Classification::from_value(0x09), None);
+    // input is not consumed on failure
+    assert_eq!(input, &[0x09, 0xFF]);
+}
+
+#[test]
+fn wider_representation_with_fallback() {
+    assert_eq!(SensorType::decode(&mut &[0x00, 0x02][..]).unwrap(), SensorType::Ir);
+    assert_eq!(SensorType::decode(&mut &[0x01, 0x00][..]).unwrap(), SensorType::Sar);
+    let unknown = SensorType::decode(&mut &[0xAB, 0xCD][..]).unwrap();
+    assert_eq!(unknown, SensorType::Unknown(0xABCD));
+    // unknown values are preserved when re-encoded
+    assert_eq!(unknown.encode_value(), vec![0xAB, 0xCD]);
+    assert_eq!(SensorType::Eo.encode_value(), vec![0x00, 0x01]);
+}
+
+#[test]
+fn enums_as_klv_fields() {
+    let encoded = [0x01, 0x01, 0x04, 0x02, 0x02, 0x12, 0x34];
+    let decoded = Security::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded, Security {
+        classification: Classification::Secret,
+        sensor: Some(SensorType::Unknown(0x1234)),
+    });
+    assert_eq!(decoded.encode_value(), encoded);
+    assert!(Security::decode(&mut &[0x01, 0x01, 0x00][..]).is_err());
+}
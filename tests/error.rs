@@ -0,0 +1,128 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Security {
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::u8)]
+    classification: u8,
+
+    #[klv(key = 0x03, dec = tinyklv::dec::binary::be_u16)]
+    version: u16,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = b"\x06\x0E",
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Uas {
+    #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+    checksum: u16,
+
+    #[klv(key = 0x05, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+
+    #[klv(key = 0x30, dec = Security::decode)]
+    security: Security,
+
+    #[klv(key = 0x41, dec = decode_version)]
+    version: Option<u8>,
+}
+
+/// Custom decoder, using [`KlvError`] as the error type
+fn decode_version(input: &mut &[u8]) -> winnow::PResult<u8, KlvError> {
+    winnow::token::any.verify(|x| *x <= 19).parse_next(input)
+}
+
+#[test]
+fn nested_length_mismatch() {
+    let data = [
+        0x01, 0x02, 0xAA, 0xBB,
+        0x30, 0x08,
+            0x02, 0x01, 0x01,
+            0x03, 0x03, 0x00, 0x0C, 0x00,
+    ];
+    let err = KlvError::from(Uas::decode(&mut &data[..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+    assert_eq!(err.packet, Some("Uas"));
+    assert_eq!(err.path, vec!["tag 48", "tag 3"]);
+    assert_eq!(err.field, Some("version"));
+    assert_eq!(err.offset, 11);
+    assert_eq!(err.to_string(), "Uas > tag 48 > tag 3 at byte 11 (field `version`): expected a length of 3, found 2");
+}
+
+#[test]
+fn nested_missing_field() {
+    let data = [
+        0x01, 0x02, 0xAA, 0xBB,
+        0x30, 0x03,
+            0x02, 0x01, 0x01,
+    ];
+    let err = KlvError::from(Uas::decode(&mut &data[..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::MissingField);
+    assert_eq!(err.path, vec!["tag 48", "tag 3"]);
+    assert_eq!(err.field, Some("version"));
+    assert_eq!(err.offset, 6);
+}
+
+#[test]
+fn truncated_value() {
+    let err = KlvError::from(Uas::decode(&mut &[0x01, 0x04, 0xAA, 0xBB][..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 4, actual: 2 });
+    assert_eq!(err.path, vec!["tag 1"]);
+    assert_eq!(err.offset, 2);
+}
+
+#[test]
+fn optional_fields_stay_lenient() {
+    let data = [
+        0x05, 0x01, 0xFF,
+        0x41, 0x01, 0x20,
+        0x01, 0x02, 0xAA, 0xBB,
+        0x30, 0x07,
+            0x02, 0x01, 0x01,
+            0x03, 0x02, 0x00, 0x0C,
+    ];
+    let uas = Uas::decode(&mut &data[..]).unwrap();
+    assert_eq!(uas.heading, None);
+    assert_eq!(uas.version, None);
+    assert_eq!(uas.checksum, 0xAABB);
+    assert_eq!(uas.security, Security { classification: 1, version: 12 });
+}
+
+#[test]
+fn extract_keeps_the_cause() {
+    let data = [
+        0x06, 0x0E, 0x04,
+        0x01, 0x03, 0xAA, 0xBB,
+    ];
+    let err = KlvError::from(Uas::extract(&mut &data[..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+    assert_eq!(err.path, vec!["tag 1"]);
+}
+
+#[test]
+fn nested_decoder_failure() {
+    let data = [
+        0x01, 0x02, 0xAA, 0xBB,
+        0x30, 0x06,
+            0x02, 0x01, 0x01,
+            0x03, 0x01, 0x0C,
+    ];
+    let err = KlvError::from(Uas::decode(&mut &data[..]).unwrap_err());
+    assert!(matches!(err.kind, KlvErrorKind::Decode(_)));
+    assert_eq!(err.path, vec!["tag 48", "tag 3"]);
+}
+
+#[test]
+fn plain_winnow_errors() {
+    let err = KlvError::from(tinyklv::dec::binary::be_u16(&mut &[0x01][..]).unwrap_err());
+    assert!(matches!(err.kind, KlvErrorKind::Decode(_)));
+    assert_eq!(err.packet, None);
+    assert!(err.path.is_empty());
+}
@@ -0,0 +1,137 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::codecs::binary::{Endian, FixedLength, FixedLengthError};
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Packet {
+    #[klv(key = 0x01, enc = FixedLength::encode_lengthed(3), dec = FixedLength::decode_lengthed(3))]
+    offset: i32,
+
+    #[klv(key = 0x02, enc = FixedLength::encode_lengthed(5), dec = FixedLength::decode_lengthed(5))]
+    counter: u64,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct SaturatingPacket {
+    #[klv(key = 0x01, enc = FixedLength::encode_lengthed_saturating(3), dec = FixedLength::decode_lengthed(3))]
+    offset: i32,
+
+    #[klv(key = 0x02, enc = FixedLength::encode_lengthed_saturating(5), dec = FixedLength::decode_lengthed(5))]
+    counter: u64,
+}
+
+#[test]
+fn every_width() {
+    for len in 1..=16usize {
+        for endian in [Endian::Big, Endian::Little] {
+            let codec = FixedLength { len, endian };
+            let bits = 8 * len as u32;
+            let umax = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+            let imin = if bits == 128 { i128::MIN } else { -(1i128 << (bits - 1)) };
+            let imax = if bits == 128 { i128::MAX } else { (1i128 << (bits - 1)) - 1 };
+            for value in [0, 1, umax / 3, umax] {
+                let encoded = codec.encode(&value).unwrap();
+                assert_eq!(encoded.len(), len);
+                assert_eq!(codec.decode::<u128>(&mut &encoded[..]), Ok(value));
+            }
+            for value in [imin, -1, 0, 1, imax] {
+                let encoded = codec.encode(&value).unwrap();
+                assert_eq!(encoded.len(), len);
+                assert_eq!(codec.decode::<i128>(&mut &encoded[..]), Ok(value));
+            }
+            if bits < 128 {
+                assert_eq!(codec.encode(&(umax + 1)), Err(FixedLengthError::Overflow { len }));
+                assert_eq!(codec.encode(&(imin - 1)), Err(FixedLengthError::Overflow { len }));
+                assert_eq!(codec.encode(&(imax + 1)), Err(FixedLengthError::Overflow { len }));
+            }
+        }
+    }
+}
+
+#[test]
+fn keeps_least_significant_bytes() {
+    assert_eq!(FixedLength::be(2).encode(&0x0102u32), Ok(vec![0x01, 0x02]));
+    assert_eq!(FixedLength::le(2).encode(&0x0102u32), Ok(vec![0x02, 0x01]));
+    assert_eq!(FixedLength::be(6).encode(&0x0102u16), Ok(vec![0, 0, 0, 0, 0x01, 0x02]));
+}
+
+#[test]
+fn sign_extension() {
+    assert_eq!(FixedLength::be(1).decode::<i64>(&mut &[0x80][..]), Ok(-128));
+    assert_eq!(FixedLength::le(2).decode::<i16>(&mut &[0xFE, 0xFF][..]), Ok(-2));
+    // unsigned targets are never sign extended
+    assert_eq!(FixedLength::be(1).decode::<u64>(&mut &[0x80][..]), Ok(128));
+}
+
+#[test]
+fn decode_errors() {
+    // does not fit in the target type
+    assert!(FixedLength::be(2).decode::<u8>(&mut &[0x01, 0x00][..]).is_err());
+    assert!(FixedLength::be(1).decode::<u8>(&mut &[0xFF][..]).is_ok());
+    assert!(FixedLength::be(2).decode::<i8>(&mut &[0x00, 0x80][..]).is_err());
+    assert_eq!(FixedLength::be(2).decode::<i8>(&mut &[0xFF, 0x80][..]), Ok(-128));
+    // not enough input
+    assert!(FixedLength::be(4).decode::<u32>(&mut &[0x00, 0x01][..]).is_err());
+    // invalid widths
+    assert!(FixedLength::be(0).decode::<u32>(&mut &[0x00][..]).is_err());
+    assert_eq!(FixedLength::be(17).encode(&0u8), Err(FixedLengthError::InvalidLength(17)));
+}
+
+#[test]
+fn as_klv_field() {
+    let encoded = [0x01, 0x03, 0xFF, 0xFF, 0x9C, 0x02, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00];
+    let decoded = Packet::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded, Packet { offset: -100, counter: 1 << 32 });
+    assert_eq!(decoded.encode_value(), encoded);
+    // overflowing values are left out
+    let overflow = Packet { offset: 1 << 23, counter: 1 << 40 };
+    assert_eq!(overflow.encode_value(), Vec::<u8>::new());
+    let overflow = Packet { offset: -100, counter: 1 << 40 };
+    assert_eq!(overflow.encode_value(), vec![0x01, 0x03, 0xFF, 0xFF, 0x9C]);
+    assert!(Packet::decode(&mut &overflow.encode_value()[..]).is_err());
+}
+
+#[test]
+fn saturating_klv_field() {
+    // overflowing values are saturated, and the packet still decodes
+    let overflow = SaturatingPacket { offset: 1 << 23, counter: 1 << 40 };
+    let encoded = overflow.encode_value();
+    assert_eq!(encoded, vec![0x01, 0x03, 0x7F, 0xFF, 0xFF, 0x02, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(SaturatingPacket::decode(&mut &encoded[..]), Ok(SaturatingPacket { offset: (1 << 23) - 1, counter: (1 << 40) - 1 }));
+    let underflow = SaturatingPacket { offset: -(1 << 23) - 1, counter: 0 };
+    assert_eq!(SaturatingPacket::decode(&mut &underflow.encode_value()[..]), Ok(SaturatingPacket { offset: -(1 << 23), counter: 0 }));
+}
+
+#[test]
+fn saturating() {
+    for len in 1..=16usize {
+        let codec = FixedLength::be(len);
+        let bits = 8 * len as u32;
+        let umax = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+        let imin = if bits == 128 { i128::MIN } else { -(1i128 << (bits - 1)) };
+        let imax = if bits == 128 { i128::MAX } else { (1i128 << (bits - 1)) - 1 };
+        assert_eq!(codec.encode_saturating(&u128::MAX), codec.encode(&umax));
+        assert_eq!(codec.encode_saturating(&i128::MIN), codec.encode(&imin));
+        assert_eq!(codec.encode_saturating(&i128::MAX), codec.encode(&imax));
+        assert_eq!(codec.encode_saturating(&-1i8), codec.encode(&-1i8));
+    }
+}
+
+#[test]
+fn invalid_width_encoder() {
+    assert_eq!(FixedLength::encode_lengthed::<u8>(0)(&0), None);
+    assert_eq!(FixedLength::encode_lengthed::<u8>(17)(&0), None);
+    assert_eq!(FixedLength::encode_lengthed_saturating::<u8>(17)(&0), None);
+}
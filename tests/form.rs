@@ -0,0 +1,103 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(form = defined_pack)]
+struct Position {
+    #[klv(enc = encode_i32, dec = tinyklv::dec::binary::be_i32)]
+    latitude: i32,
+
+    #[klv(enc = encode_i32, dec = tinyklv::dec::binary::be_i32)]
+    longitude: i32,
+
+    #[klv(enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    altitude: Option<u16>,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    form = variable_pack,
+    len(enc = tinyklv::enc::binary::u8_from_usize, dec = tinyklv::dec::binary::u8_as_usize),
+)]
+struct Target {
+    #[klv(enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    id: u16,
+
+    #[klv(dyn = true, enc = tinyklv::enc::binary::from_string_utf8, dec = tinyklv::dec::binary::to_string_utf8)]
+    name: String,
+
+    #[klv(enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(form = universal_set)]
+struct Essence {
+    #[klv(key = b"\x06\x0E\x2B\x34\x01\x01\x01\x01\x07\x01\x02\x01\x01\x01\x00\x00", enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    frame: u16,
+
+    #[klv(key = b"\x06\x0E\x2B\x34\x01\x01\x01\x01\x07\x01\x02\x01\x01\x02\x00\x00", enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    track: Option<u16>,
+}
+
+fn encode_i32(input: &i32) -> Vec<u8> { tinyklv::enc::binary::be_i32(*input) }
+fn encode_u16(input: &u16) -> Vec<u8> { tinyklv::enc::binary::be_u16(*input) }
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_u16) }
+
+#[test]
+fn defined_pack() {
+    let position = Position { latitude: -1, longitude: 2, altitude: Some(3) };
+    let encoded = position.encode_value();
+    assert_eq!(encoded, vec![0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03]);
+    assert_eq!(Position::decode(&mut &encoded[..]), Ok(position));
+}
+
+#[test]
+fn defined_pack_trailing_optional() {
+    let position = Position { latitude: 1, longitude: 2, altitude: None };
+    let encoded = position.encode_value();
+    assert_eq!(encoded.len(), 8);
+    assert_eq!(Position::decode(&mut &encoded[..]), Ok(position));
+}
+
+#[test]
+fn defined_pack_truncated() {
+    let err = KlvError::from(Position::decode(&mut &[0x00, 0x00, 0x00, 0x01, 0x00, 0x00][..]).unwrap_err());
+    assert!(matches!(err.kind, KlvErrorKind::Decode(_)));
+    assert_eq!(err.path, vec!["item 1"]);
+    assert_eq!(err.field, Some("longitude"));
+    assert_eq!(err.offset, 4);
+
+    let err = KlvError::from(Position::decode(&mut &[0x00, 0x00, 0x00, 0x01][..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::MissingField);
+    assert_eq!(err.field, Some("longitude"));
+}
+
+#[test]
+fn variable_pack() {
+    let target = Target { id: 7, name: "ok".into(), heading: Some(90) };
+    let encoded = target.encode_value();
+    assert_eq!(encoded, vec![0x02, 0x00, 0x07, 0x02, b'o', b'k', 0x02, 0x00, 0x5A]);
+    assert_eq!(Target::decode(&mut &encoded[..]), Ok(target));
+}
+
+#[test]
+fn variable_pack_lenient_optional() {
+    let target = Target::decode(&mut &[0x02, 0x00, 0x07, 0x00, 0x01, 0x5A][..]).unwrap();
+    assert_eq!(target, Target { id: 7, name: "".into(), heading: None });
+
+    let err = KlvError::from(Target::decode(&mut &[0x03, 0x00, 0x07, 0x00][..]).unwrap_err());
+    assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+    assert_eq!(err.path, vec!["item 0"]);
+}
+
+#[test]
+fn universal_set() {
+    let essence = Essence { frame: 24, track: Some(1) };
+    let encoded = essence.encode_value();
+    assert_eq!(encoded.len(), 2 * (16 + 1 + 2));
+    assert_eq!(&encoded[..4], &tinyklv::router::UL_PREFIX);
+    assert_eq!(encoded[16], 0x02);
+    assert_eq!(Essence::decode(&mut &encoded[..]), Ok(essence));
+    assert_eq!(Essence::FIELDS[0].key.len(), 16);
+}
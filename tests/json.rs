@@ -0,0 +1,91 @@
+#![cfg(feature = "serde")]
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[klv(
+    stream = &[u8],
+    sentinel = 0x06,
+    key(enc = tinyklv::enc::binary::u8,
+        dec = tinyklv::dec::binary::u8),
+    len(enc = tinyklv::enc::binary::u8_from_usize,
+        dec = tinyklv::dec::binary::u8_as_usize),
+)]
+struct Packet {
+    #[klv(
+        key = 0x02,
+        name = "Precision Time Stamp",
+        units = "us",
+        enc = timestamp_encoder,
+        dec = tinyklv::dec::binary::be_u64,
+    )]
+    timestamp: u64,
+
+    #[klv(
+        key = 0x03,
+        name = "Mission ID",
+        dyn = true,
+        enc = string_encoder,
+        dec = tinyklv::dec::binary::to_string_utf8,
+    )]
+    mission: String,
+
+    #[klv(key = 0x05, enc = heading_encoder, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+}
+
+fn timestamp_encoder(input: &u64) -> Vec<u8> {
+    tinyklv::enc::binary::be_u64(*input)
+}
+
+fn string_encoder(input: &String) -> Vec<u8> {
+    input.as_bytes().to_vec()
+}
+
+fn heading_encoder(input: &Option<u16>) -> Option<Vec<u8>> {
+    input.map(tinyklv::enc::binary::be_u16)
+}
+
+fn packet() -> Packet {
+    Packet {
+        timestamp: 1_231_798_102_000_000,
+        mission: "MISSION01".into(),
+        heading: Some(0x7180),
+    }
+}
+
+#[test]
+fn field_meta() {
+    use tinyklv::json::KlvFieldMeta;
+    assert_eq!(Packet::FIELD_META.len(), 3);
+    let meta = Packet::field_meta("timestamp").unwrap();
+    assert_eq!(meta.name, Some("Precision Time Stamp"));
+    assert_eq!(meta.units, Some("us"));
+    let meta = Packet::field_meta("heading").unwrap();
+    assert_eq!(meta.name, None);
+    assert_eq!(meta.units, None);
+}
+
+#[test]
+fn round_trip() {
+    let encoded = packet().encode();
+    let json = tinyklv::json::extract_to_json::<_, Packet>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(json, serde_json::json!({
+        "timestamp": 1_231_798_102_000_000_u64,
+        "mission": "MISSION01",
+        "heading": 0x7180,
+    }));
+    let reencoded: Vec<u8> = tinyklv::json::encode_from_json::<Packet, _>(json).unwrap();
+    assert_eq!(reencoded, encoded);
+}
+
+#[test]
+fn annotated_round_trip() {
+    let annotated = tinyklv::json::to_annotated_json(&packet()).unwrap();
+    assert_eq!(annotated["timestamp"]["units"], "us");
+    assert_eq!(annotated["mission"]["name"], "Mission ID");
+    assert!(annotated["mission"].get("units").is_none());
+    assert_eq!(annotated["heading"], 0x7180);
+    let decoded: Packet = tinyklv::json::from_annotated_json(annotated).unwrap();
+    assert_eq!(decoded, packet());
+}
@@ -0,0 +1,129 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::codecs::mapped::{dec, enc, Linear};
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Platform {
+    #[klv(key = 0x05, enc = enc::linear_u16(0.0, 360.0), dec = dec::linear_u16(0.0, 360.0))]
+    heading: f64,
+
+    #[klv(key = 0x06, enc = enc::linear_i16_opt(-20.0, 20.0), dec = dec::linear_i16(-20.0, 20.0))]
+    pitch: Option<f64>,
+
+    #[klv(key = 0x0D, enc = enc::linear_i32(-90.0, 90.0), dec = dec::linear_i32_nan(-90.0, 90.0))]
+    latitude: f64,
+}
+
+#[test]
+fn misb_st0601_examples() {
+    // platform heading (tag 5)
+    assert_eq!(enc::linear_u16(0.0, 360.0)(&159.9744), Some(vec![0x71, 0xC2]));
+    let heading = dec::linear_u16(0.0, 360.0)(&mut &[0x71, 0xC2][..]).unwrap();
+    assert!((heading - 159.974364).abs() < 1e-6);
+    // platform pitch (tag 6)
+    assert_eq!(enc::linear_i16(-20.0, 20.0)(&-0.4315251), vec![0xFD, 0x3D]);
+    // sensor latitude (tag 13)
+    assert_eq!(enc::linear_i32(-90.0, 90.0)(&60.176822966978335), vec![0x55, 0x95, 0xB6, 0x6D]);
+    // sensor longitude (tag 14)
+    assert_eq!(enc::linear_i32(-180.0, 180.0)(&128.42675904204452), vec![0x5B, 0x53, 0x60, 0xC4]);
+}
+
+#[test]
+fn signed_mapping_is_symmetric() {
+    let linear = Linear::signed(16, -20.0, 20.0);
+    assert_eq!(linear.raw_range(), (-32767, 32767));
+    assert_eq!(linear.to_value(32767), Some(20.0));
+    assert_eq!(linear.to_value(-32767), Some(-20.0));
+    assert_eq!(linear.to_value(0), Some(0.0));
+    assert_eq!(linear.to_value(-32768), None);
+    // out of range values are clamped, and never hit the reserved value
+    assert_eq!(linear.to_raw(-100.0), Some(-32767));
+    assert_eq!(linear.to_raw(100.0), Some(32767));
+    assert_eq!(linear.to_raw(f64::NAN), Some(-32768));
+}
+
+#[test]
+fn rounds_half_away_from_zero() {
+    let linear = Linear::unsigned(8, 0.0, 255.0);
+    assert_eq!(linear.to_raw(1.5), Some(2));
+    assert_eq!(linear.to_raw(1.49), Some(1));
+    let linear = Linear::signed(8, -127.0, 127.0);
+    assert_eq!(linear.to_raw(-1.5), Some(-2));
+    assert_eq!(linear.to_raw(1.5), Some(2));
+}
+
+#[test]
+fn custom_reserved_values() {
+    let reserved: &[u8] = &[0xFF, 0xFF];
+    assert!(dec::linear_u16_reserved(0.0, 360.0, 0xFFFF)(&mut &reserved[..]).is_err());
+    assert!(dec::linear_u16_reserved_nan(0.0, 360.0, 0xFFFF)(&mut &reserved[..]).unwrap().is_nan());
+    assert_eq!(dec::linear_u16(0.0, 360.0)(&mut &reserved[..]).unwrap(), 360.0);
+    assert_eq!(enc::linear_u16_reserved(0.0, 360.0, 0xFFFF)(&f64::NAN), reserved);
+    // `max` maps onto the reserved value, and is encoded one step below it
+    let linear = Linear::unsigned(16, 0.0, 360.0).reserved(Some(0xFFFF));
+    assert_eq!(linear.to_raw(360.0), Some(0xFFFE));
+    let encoded = enc::linear_u16_reserved(0.0, 360.0, 0xFFFF)(&360.0);
+    assert_eq!(encoded, vec![0xFF, 0xFE]);
+    let heading = dec::linear_u16_reserved(0.0, 360.0, 0xFFFF)(&mut &encoded[..]).unwrap();
+    assert!((heading - 360.0).abs() < 1.5 * 360.0 / 65535.0);
+    // and `min`, onto a reserved lowest value, one step above it
+    let linear = Linear::unsigned(8, 0.0, 255.0).reserved(Some(0));
+    assert_eq!(linear.to_raw(0.0), Some(1));
+}
+
+#[test]
+fn nan_without_reserved_value() {
+    assert_eq!(Linear::unsigned(16, 0.0, 360.0).to_raw(f64::NAN), None);
+    assert_eq!(enc::linear_u16_opt(0.0, 360.0)(&Some(f64::NAN)), None);
+    assert_eq!(enc::linear_u16_opt(0.0, 360.0)(&Some(360.0)), Some(vec![0xFF, 0xFF]));
+    assert_eq!(enc::linear_u8(0.0, 1.0)(&f64::NAN), None);
+    assert_eq!(enc::linear_u16(0.0, 360.0)(&f64::NAN), None);
+    assert_eq!(enc::linear_u32(0.0, 1.0)(&f64::NAN), None);
+    // and is left out of a derived struct
+    let platform = Platform { heading: f64::NAN, pitch: None, latitude: 0.0 };
+    assert_eq!(platform.encode_value(), [0x0D, 0x04, 0x00, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn offset_binary() {
+    // the signed mapping's raw value, plus 2^(n-1)
+    assert_eq!(enc::linear_offset_u16(-20.0, 20.0)(&0.0), vec![0x80, 0x00]);
+    assert_eq!(enc::linear_offset_u16(-20.0, 20.0)(&20.0), vec![0xFF, 0xFF]);
+    assert_eq!(enc::linear_offset_u16(-20.0, 20.0)(&-20.0), vec![0x00, 0x01]);
+    assert_eq!(enc::linear_offset_u16(-20.0, 20.0)(&-0.4315251), vec![0x7D, 0x3D]);
+    assert_eq!(enc::linear_offset_u8(-1.0, 1.0)(&-100.0), vec![0x01]);
+    assert_eq!(dec::linear_offset_u16(-20.0, 20.0)(&mut &[0xFF, 0xFF][..]).unwrap(), 20.0);
+    assert_eq!(dec::linear_offset_u16(-20.0, 20.0)(&mut &[0x80, 0x00][..]).unwrap(), 0.0);
+    let pitch = dec::linear_offset_u16(-20.0, 20.0)(&mut &[0x7D, 0x3D][..]).unwrap();
+    assert!((pitch - -0.4315251).abs() < 40.0 / 65534.0);
+    // `0` is reserved
+    let reserved: &[u8] = &[0x00, 0x00, 0x00, 0x00];
+    assert!(dec::linear_offset_u32(-90.0, 90.0)(&mut &reserved[..]).is_err());
+    assert!(dec::linear_offset_u32_nan(-90.0, 90.0)(&mut &reserved[..]).unwrap().is_nan());
+    assert_eq!(enc::linear_offset_u32(-90.0, 90.0)(&f64::NAN), reserved);
+    assert_eq!(enc::linear_offset_u32_opt(-90.0, 90.0)(&None), None);
+}
+
+#[test]
+fn reserved_values_in_derived_structs() {
+    let encoded = [
+        0x05, 0x02, 0x71, 0xC2,
+        0x06, 0x02, 0x80, 0x00,
+        0x0D, 0x04, 0x80, 0x00, 0x00, 0x00,
+    ];
+    let decoded = Platform::decode(&mut &encoded[..]).unwrap();
+    // the reserved pitch is consumed, and decodes to `None`
+    assert_eq!(decoded.pitch, None);
+    assert!(decoded.latitude.is_nan());
+    assert!((decoded.heading - 159.974364).abs() < 1e-6);
+    assert_eq!(decoded.encode_value(), [
+        0x05, 0x02, 0x71, 0xC2,
+        0x0D, 0x04, 0x80, 0x00, 0x00, 0x00,
+    ]);
+}
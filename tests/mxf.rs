@@ -0,0 +1,152 @@
+use tinyklv::prelude::*;
+use tinyklv::codecs::ul::UniversalLabel;
+use tinyklv::mxf::*;
+
+const PREFACE: [u8; 16] = [0x06, 0x0E, 0x2B, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0D, 0x01, 0x01, 0x01, 0x01, 0x01, 0x2F, 0x00];
+const ESSENCE: [u8; 16] = [0x06, 0x0E, 0x2B, 0x34, 0x01, 0x02, 0x01, 0x01, 0x0D, 0x01, 0x03, 0x01, 0x15, 0x01, 0x05, 0x01];
+
+fn ul(s: &str) -> UniversalLabel {
+    s.parse().unwrap()
+}
+
+fn instance_uid() -> UniversalLabel {
+    ul("06.0E.2B.34.01.01.01.01.01.01.15.02.00.00.00.00")
+}
+
+fn dynamic() -> UniversalLabel {
+    ul("06.0E.2B.34.01.01.01.0E.0E.01.01.01.01.01.01.00")
+}
+
+fn primer() -> Primer {
+    Primer { entries: vec![
+        PrimerEntry { tag: 0x3C0A, ul: instance_uid() },
+        PrimerEntry { tag: 0x8001, ul: dynamic() },
+    ] }
+}
+
+fn header() -> PartitionPack {
+    PartitionPack {
+        major_version: 1,
+        minor_version: 3,
+        kag_size: 1,
+        footer_partition: 0x0200,
+        header_byte_count: 0x0100,
+        operational_pattern: ul("06.0E.2B.34.04.01.01.01.0D.01.02.01.01.01.09.00"),
+        essence_containers: vec![ul("06.0E.2B.34.04.01.01.03.0D.01.03.01.02.7F.01.00")],
+        ..Default::default()
+    }
+}
+
+/// A local set item, of a 2-byte tag and 2-byte length
+fn local(tag: u16, value: &[u8]) -> Vec<u8> {
+    let mut output = tag.to_be_bytes().to_vec();
+    output.extend((value.len() as u16).to_be_bytes());
+    output.extend(value);
+    output
+}
+
+/// A synthetic MXF file: header partition, primer, preface, fill, essence,
+/// footer partition and random index pack
+fn fixture() -> Vec<u8> {
+    let mut file = header().encode_triplet(PartitionKind::Header, true, true);
+    file.extend(primer().encode_triplet());
+    let mut preface = local(0x3C0A, &[0xAB; 16]);
+    preface.extend(local(0x8001, &[0x01, 0x02]));
+    preface.extend(local(0x7777, &[0xFF]));
+    file.extend(preface.into_klv(PREFACE.to_vec(), tinyklv::enc::ber::ber_length_from_usize));
+    file.extend(vec![0x00; 8].into_klv(FILL_KEY.encode_value(), tinyklv::enc::ber::ber_length_from_usize));
+    file.extend(vec![0xEE; 4].into_klv(ESSENCE.to_vec(), tinyklv::enc::ber::ber_length_from_usize));
+    file.extend(PartitionPack { this_partition: 0x0200, ..header() }.encode_triplet(PartitionKind::Footer, true, true));
+    file.extend(vec![0x00; 12].into_klv(RANDOM_INDEX_PACK_KEY.encode_value(), tinyklv::enc::ber::ber_length_from_usize));
+    file
+}
+
+#[test]
+fn triplet_kinds() {
+    let file = fixture();
+    let kinds: Vec<_> = triplets(&file).map(|triplet| triplet.unwrap().kind()).collect();
+    assert_eq!(kinds, vec![
+        TripletKind::Partition(PartitionKind::Header),
+        TripletKind::Primer,
+        TripletKind::MetadataSet,
+        TripletKind::Fill,
+        TripletKind::Essence,
+        TripletKind::Partition(PartitionKind::Footer),
+        TripletKind::RandomIndexPack,
+    ]);
+    let last = triplets(&file).last().unwrap().unwrap();
+    assert_eq!(last.value_offset + last.value.len(), file.len());
+}
+
+#[test]
+fn partition_packs() {
+    let file = fixture();
+    let first = triplets(&file).next().unwrap().unwrap();
+    let partition = first.partition().unwrap().unwrap();
+    assert_eq!(partition.kind, PartitionKind::Header);
+    assert_eq!(partition.status, PartitionStatus { closed: true, complete: true });
+    assert_eq!(partition.pack, header());
+    assert_eq!(first.primer(), None);
+
+    let open = header().encode_triplet(PartitionKind::Body, false, false);
+    let partition = triplets(&open).next().unwrap().unwrap().partition().unwrap().unwrap();
+    assert_eq!(partition.kind, PartitionKind::Body);
+    assert_eq!(partition.status, PartitionStatus { closed: false, complete: false });
+}
+
+#[test]
+fn primer_resolves_local_tags() {
+    let primer = primer();
+    assert_eq!(primer.resolve(0x3C0A), Some(instance_uid()));
+    assert_eq!(primer.resolve(0x7777), None);
+    assert_eq!(primer.tag_of(&dynamic().with_version(0x7F)), Some(0x8001));
+}
+
+#[test]
+fn walker() {
+    let file = fixture();
+    let items: Vec<_> = MxfWalker::new(&file).collect::<Result<_, _>>().unwrap();
+    assert_eq!(items.len(), 7);
+    assert_eq!(items[1], MxfItem::Primer(primer()));
+    let set = match &items[2] {
+        MxfItem::Set(set) => set,
+        item => panic!("expected a metadata set, found {:?}", item),
+    };
+    assert_eq!(set.key, UniversalLabel::new(PREFACE));
+    assert_eq!(set.get(&instance_uid()), Some(&[0xAB; 16][..]));
+    assert_eq!(set.get(&dynamic()), Some(&[0x01, 0x02][..]));
+    assert_eq!(set.items[2], LocalItem { tag: 0x7777, ul: None, value: &[0xFF] });
+    assert_eq!(set.get_by_tag(0x7777), Some(&[0xFF][..]));
+    match &items[4] {
+        MxfItem::Other(triplet) => assert_eq!(triplet.kind(), TripletKind::Essence),
+        item => panic!("expected an essence element, found {:?}", item),
+    }
+    assert!(matches!(&items[5], MxfItem::Partition(partition) if partition.pack.this_partition == 0x0200));
+}
+
+#[test]
+fn truncated_file() {
+    let file = fixture();
+    let truncated = &file[..file.len() - 4];
+    let results: Vec<_> = triplets(truncated).collect();
+    assert_eq!(results.len(), 7);
+    let err = results[6].clone().unwrap_err();
+    assert_eq!(err.kind, KlvErrorKind::LengthMismatch { expected: 12, actual: 8 });
+    assert_eq!(err.offset, file.len() - 12);
+}
+
+#[test]
+fn bad_partition_pack() {
+    let mut file = vec![0x00; 20].into_klv(
+        header().encode_triplet(PartitionKind::Header, true, true)[..16].to_vec(),
+        tinyklv::enc::ber::ber_length_from_usize,
+    );
+    file.extend(primer().encode_triplet());
+    let items: Vec<_> = MxfWalker::new(&file).collect();
+    let err = items[0].clone().unwrap_err();
+    assert_eq!(err.path, vec!["partition pack"]);
+    assert_eq!(err.field, Some("previous_partition"));
+    assert_eq!(err.offset, 17 + 16);
+    // ---- the walker continues after a bad pack
+    assert_eq!(items[1], Ok(MxfItem::Primer(primer())));
+}
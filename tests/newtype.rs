@@ -0,0 +1,98 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Attitude(
+    #[klv(key = 0x05, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    u16,
+    #[klv(key = 0x06, enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    Option<u16>,
+    Vec<u8>,
+);
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(transparent)]
+struct Tag(
+    #[klv(enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    u16,
+);
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(transparent, default(ty = u32, enc = encode_u32, dec = tinyklv::dec::binary::be_u32))]
+struct Counter {
+    value: u32,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(transparent)]
+struct Length(tinyklv::codecs::ber::BerLength<u64>);
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Tagged {
+    #[klv(key = 0x01, enc = Tag::encode_value, dec = Tag::decode)]
+    tag: Tag,
+}
+
+fn encode_u16(input: &u16) -> Vec<u8> {
+    tinyklv::enc::binary::be_u16(*input)
+}
+
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> {
+    input.map(tinyklv::enc::binary::be_u16)
+}
+
+fn encode_u32(input: &u32) -> Vec<u8> {
+    tinyklv::enc::binary::be_u32(*input)
+}
+
+#[test]
+fn tuple_struct_round_trip() {
+    let encoded = [0x05, 0x02, 0x01, 0x00, 0x06, 0x02, 0x00, 0x02];
+    let decoded = Attitude::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded, Attitude(256, Some(2), vec![]));
+    assert_eq!(decoded.encode_value(), encoded);
+}
+
+#[test]
+fn tuple_struct_missing_required() {
+    assert!(Attitude::decode(&mut &[0x06, 0x02, 0x00, 0x02][..]).is_err());
+}
+
+#[test]
+fn tuple_struct_schema() {
+    assert_eq!(Attitude::FIELDS.len(), 2);
+    assert_eq!(Attitude::field_by_key(&[0x06]).unwrap().field, "1");
+}
+
+#[test]
+fn transparent_newtypes() {
+    assert_eq!(Tag::decode(&mut &[0x01, 0x02][..]).unwrap(), Tag(0x0102));
+    assert_eq!(Tag(0x0102).encode_value(), vec![0x01, 0x02]);
+
+    assert_eq!(Counter::decode(&mut &[0x00, 0x00, 0x01, 0x00][..]).unwrap(), Counter { value: 256 });
+    assert_eq!(Counter { value: 256 }.encode_value(), vec![0x00, 0x00, 0x01, 0x00]);
+
+    let length = Length::decode(&mut &[0x81, 0xC8][..]).unwrap();
+    assert_eq!(length.0.as_u128(), 200);
+    assert_eq!(length.encode_value(), vec![0x81, 0xC8]);
+}
+
+#[test]
+fn transparent_newtype_as_field() {
+    let encoded = [0x01, 0x02, 0x00, 0x07];
+    let decoded = Tagged::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded, Tagged { tag: Tag(7) });
+    assert_eq!(decoded.encode_value(), encoded);
+}
@@ -0,0 +1,160 @@
+use tinyklv::node::{
+    KlvHeader,
+    KlvNode,
+    KlvValue,
+    MAX_DEPTH,
+};
+
+#[test]
+fn nested_ber_round_trip() {
+    // long-form BER length on the outer set, 200 byte value inside
+    let mut inner = vec![0x02, 0x81, 0xC8];
+    inner.extend([0x5A; 200]);
+    let mut packet = vec![0x01, 0x01, 0x07];
+    packet.push(0x30);
+    packet.extend(tinyklv::enc::ber::ber_length(&(inner.len() + 3)));
+    packet.extend([0x03, 0x01, 0x09]);
+    packet.extend(&inner);
+
+    let nodes = KlvNode::parse_all(
+        &mut packet.as_slice(),
+        tinyklv::dec::ber::ber_oid::<u64>,
+        tinyklv::dec::ber::ber_length,
+        |key: &u64| *key == 0x30,
+    ).unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].raw(), Some(&[0x07][..]));
+    let children = nodes[1].children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[1].key, 0x02);
+    assert_eq!(children[1].len, 200);
+
+    let encoded = KlvNode::encode_all(
+        &nodes,
+        |key| tinyklv::enc::ber::ber_oid(&key),
+        |len| tinyklv::enc::ber::ber_length(&len),
+    );
+    assert_eq!(encoded, packet);
+}
+
+#[test]
+fn edited_set_recomputes_length() {
+    let packet: &[u8] = &[0x30, 0x03, 0x01, 0x01, 0xFF];
+    let mut node = KlvNode::parse(
+        &mut &packet[..],
+        tinyklv::dec::binary::u8,
+        tinyklv::dec::binary::u8_as_usize,
+        |key: &u8| *key == 0x30,
+    ).unwrap();
+    if let KlvValue::Set(children) = &mut node.value {
+        children[0].value = KlvValue::Raw(&[0xAA, 0xBB]);
+    }
+    let encoded = node.encode_with(
+        tinyklv::enc::binary::u8,
+        tinyklv::enc::binary::u8_from_usize,
+    );
+    assert_eq!(encoded, vec![0x30, 0x04, 0x01, 0x02, 0xAA, 0xBB]);
+}
+
+#[test]
+fn truncated_value_fails() {
+    let packet: &[u8] = &[0x01, 0x05, 0x00];
+    let result = KlvNode::parse_all(
+        &mut &packet[..],
+        tinyklv::dec::binary::u8,
+        tinyklv::dec::binary::u8_as_usize,
+        |_: &u8| false,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn non_minimal_lengths_round_trip() {
+    // MXF-style 4-byte BER lengths, on a set and on its child
+    let packet: &[u8] = &[
+        0x30, 0x83, 0x00, 0x00, 0x07,
+            0x01, 0x83, 0x00, 0x00, 0x02, 0xAA, 0xBB,
+    ];
+    let mut nodes = KlvNode::parse_all(
+        &mut &packet[..],
+        tinyklv::dec::ber::ber_oid::<u64>,
+        tinyklv::dec::ber::ber_length,
+        |key: &u64| *key == 0x30,
+    ).unwrap();
+    assert_eq!(nodes[0].header(), Some(KlvHeader { key: &[0x30], len: &[0x83, 0x00, 0x00, 0x07] }));
+    let encode = |nodes: &[KlvNode<u64>]| KlvNode::encode_all(
+        nodes,
+        |key| tinyklv::enc::ber::ber_oid(&key),
+        |len| tinyklv::enc::ber::ber_length(&len),
+    );
+    assert_eq!(encode(&nodes), packet);
+
+    // ---- an edited value of the same length keeps the header, a longer
+    // one is re-encoded, and so is its parent
+    if let KlvValue::Set(children) = &mut nodes[0].value {
+        children[0].value = KlvValue::Raw(&[0xCC, 0xDD]);
+    }
+    assert_eq!(encode(&nodes), [&packet[..10], &[0xCC, 0xDD]].concat());
+    if let KlvValue::Set(children) = &mut nodes[0].value {
+        children[0].value = KlvValue::Raw(&[0xCC, 0xDD, 0xEE]);
+        children[0].set_key(0x02);
+    }
+    assert_eq!(encode(&nodes), vec![0x30, 0x05, 0x02, 0x03, 0xCC, 0xDD, 0xEE]);
+}
+
+#[test]
+fn edited_key_and_length_are_re_encoded() {
+    let packet: &[u8] = &[0x01, 0x83, 0x00, 0x00, 0x02, 0xAA, 0xBB];
+    let encode = |node: &KlvNode<u64>| node.encode_with(
+        |key| tinyklv::enc::ber::ber_oid(&key),
+        |len| tinyklv::enc::ber::ber_length(&len),
+    );
+    let mut node = KlvNode::parse(
+        &mut &packet[..],
+        tinyklv::dec::ber::ber_oid::<u64>,
+        tinyklv::dec::ber::ber_length,
+        |_: &u64| false,
+    ).unwrap();
+    // ---- the key is encoded, while the header's length still applies
+    node.key = 0x02;
+    assert_eq!(encode(&node), vec![0x02, 0x83, 0x00, 0x00, 0x02, 0xAA, 0xBB]);
+    // ---- as is a length matching a new value
+    node.value = KlvValue::Raw(&[0xCC, 0xDD, 0xEE]);
+    node.len = 3;
+    assert_eq!(encode(&node), vec![0x02, 0x03, 0xCC, 0xDD, 0xEE]);
+    // ---- and restoring both brings the header back
+    node.key = 0x01;
+    node.value = KlvValue::Raw(&[0xAA, 0xBB]);
+    node.len = 2;
+    assert_eq!(encode(&node), packet);
+    // ---- nodes built by hand have no header
+    let node = KlvNode::new(0x01, KlvValue::Set(vec![KlvNode::new(0x02, KlvValue::Raw(&[0xFF]))]));
+    assert_eq!(node.header(), None);
+    assert_eq!(encode(&node), vec![0x01, 0x03, 0x02, 0x01, 0xFF]);
+}
+
+/// A set key nested `depth` times around a raw value
+fn nested(depth: usize) -> Vec<u8> {
+    // ---- built back to front, prepending each header
+    let mut reversed = vec![0xFF, 0x01, 0x01];
+    for _ in 0..depth {
+        let header = [&[0x30][..], &tinyklv::enc::ber::ber_length(&reversed.len())].concat();
+        reversed.extend(header.iter().rev());
+    }
+    reversed.reverse();
+    reversed
+}
+
+#[test]
+fn nesting_depth_is_limited() {
+    let parse = |packet: &[u8]| KlvNode::parse_all(
+        &mut &packet[..],
+        tinyklv::dec::binary::u8,
+        tinyklv::dec::ber::ber_length,
+        |key: &u8| *key == 0x30,
+    ).map(|nodes| nodes.len());
+    assert_eq!(parse(&nested(MAX_DEPTH)), Ok(1));
+    assert!(matches!(parse(&nested(MAX_DEPTH + 1)), Err(winnow::error::ErrMode::Backtrack(_))));
+    // ---- hostile input fails without overflowing the stack
+    assert!(parse(&nested(100_000)).is_err());
+}
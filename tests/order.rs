@@ -0,0 +1,110 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+    default(ty = u8, enc = encode_u8, dec = tinyklv::dec::binary::u8),
+)]
+struct Ordered {
+    #[klv(key = 0x01, last)]
+    checksum: u8,
+
+    #[klv(key = 0x05)]
+    heading: u8,
+
+    #[klv(key = 0x04, order = 2)]
+    pitch: u8,
+
+    #[klv(key = 0x03, order = 1)]
+    roll: u8,
+
+    #[klv(key = 0x02, first)]
+    timestamp: u8,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+    default(ty = u8, enc = encode_u8, dec = tinyklv::dec::binary::u8),
+)]
+struct Recorded {
+    #[klv(key = 0x01, last)]
+    checksum: u8,
+
+    #[klv(key = 0x02, first)]
+    timestamp: u8,
+
+    #[klv(key = 0x03, enc = encode_opt_u8)]
+    heading: Option<u8>,
+
+    #[klv(record_order)]
+    arrival: Vec<usize>,
+}
+
+fn encode_u8(input: &u8) -> Vec<u8> {
+    vec![*input]
+}
+
+fn encode_opt_u8(input: &Option<u8>) -> Option<Vec<u8>> {
+    input.map(|x| vec![x])
+}
+
+#[test]
+fn encode_honours_order() {
+    let value = Ordered { checksum: 1, heading: 5, pitch: 4, roll: 3, timestamp: 2 };
+    assert_eq!(value.encode_value(), vec![
+        0x02, 0x01, 2,
+        0x03, 0x01, 3,
+        0x04, 0x01, 4,
+        0x05, 0x01, 5,
+        0x01, 0x01, 1,
+    ]);
+}
+
+#[test]
+fn decode_accepts_any_order() {
+    let decoded = Ordered::decode(&mut &[
+        0x01, 0x01, 1,
+        0x05, 0x01, 5,
+        0x04, 0x01, 4,
+        0x03, 0x01, 3,
+        0x02, 0x01, 2,
+    ][..]).unwrap();
+    assert_eq!(decoded, Ordered { checksum: 1, heading: 5, pitch: 4, roll: 3, timestamp: 2 });
+}
+
+#[test]
+fn recorded_order_reproduces_input() {
+    let encoded = [
+        0x03, 0x01, 3,
+        0x01, 0x01, 1,
+        0x02, 0x01, 2,
+    ];
+    let decoded = Recorded::decode(&mut &encoded[..]).unwrap();
+    assert_eq!(decoded.arrival, vec![2, 0, 1]);
+    assert_eq!(decoded.encode_value(), encoded);
+}
+
+#[test]
+fn recorded_order_falls_back_to_attributes() {
+    let mut value = Recorded { checksum: 1, timestamp: 2, heading: None, arrival: vec![] };
+    assert_eq!(value.encode_value(), vec![
+        0x02, 0x01, 2,
+        0x01, 0x01, 1,
+    ]);
+    // partially recorded: remaining fields follow in attribute order
+    value.heading = Some(3);
+    value.arrival = vec![0];
+    assert_eq!(value.encode_value(), vec![
+        0x01, 0x01, 1,
+        0x02, 0x01, 2,
+        0x03, 0x01, 3,
+    ]);
+}
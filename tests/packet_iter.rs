@@ -0,0 +1,69 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, Clone, PartialEq)]
+#[klv(
+    sentinel = b"\x06\x0E",
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Packet {
+    #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+    id: u16,
+}
+
+const GOOD: [u8; 7] = [0x06, 0x0E, 0x04, 0x01, 0x02, 0x00, 0x2A];
+
+#[test]
+fn clean_stream() {
+    let data = [GOOD, GOOD, GOOD].concat();
+    let packets: Vec<_> = PacketIter::<Packet>::new(&data).collect();
+    assert_eq!(packets, vec![Ok(Packet { id: 42 }); 3]);
+}
+
+#[test]
+fn bit_error_in_length() {
+    // ---- the length of the second packet runs past the end of the third
+    let mut data = [GOOD, GOOD, GOOD].concat();
+    data[9] = 0x7F;
+    let mut iter = PacketIter::<Packet>::new(&data);
+    assert_eq!(iter.next(), Some(Ok(Packet { id: 42 })));
+    let err = iter.next().unwrap().unwrap_err();
+    assert_eq!(err.skipped, 7..14);
+    assert!(err.error.is_some());
+    assert_eq!(iter.position(), 14);
+    assert_eq!(iter.next(), Some(Ok(Packet { id: 42 })));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn decode_error_offsets_are_absolute() {
+    let mut data = [GOOD, GOOD].concat();
+    // ---- second packet: `id` of length 3 within a packet of length 4
+    data[11] = 0x03;
+    data[13] = 0x2A;
+    let packets: Vec<_> = PacketIter::<Packet>::new(&data).collect();
+    let err = packets[1].as_ref().unwrap_err();
+    assert_eq!(err.skipped, 7..14);
+    let error = err.error.as_ref().unwrap();
+    assert_eq!(error.kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+    assert_eq!(error.offset, 12);
+}
+
+#[test]
+fn truncated_tail() {
+    let data = [&GOOD[..], &GOOD[..5]].concat();
+    let packets: Vec<_> = PacketIter::<Packet>::new(&data).collect();
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[1].as_ref().unwrap_err().skipped, 7..12);
+}
+
+#[test]
+fn noise_without_sentinel() {
+    let data = [0x06, 0x06, 0x0E, 0x06];
+    let packets: Vec<_> = PacketIter::<Packet>::new(&data).collect();
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0], Err(PacketError { skipped: 0..1, error: None }));
+    assert_eq!(packets[1].as_ref().unwrap_err().skipped, 1..4);
+    assert!(PacketIter::<Packet>::new(&[]).next().is_none());
+}
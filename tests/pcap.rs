@@ -0,0 +1,216 @@
+#![cfg(feature = "pcap")]
+use std::net::SocketAddr;
+use std::time::{Duration, UNIX_EPOCH};
+
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::pcap::*;
+
+#[derive(Klv, Debug, Clone, PartialEq)]
+#[klv(
+    sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000",
+    key(dec = tinyklv::dec::binary::u8),
+    len(dec = tinyklv::dec::ber::ber_length),
+)]
+struct Uas {
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u64)]
+    timestamp: u64,
+    #[klv(key = 0x41, dec = tinyklv::dec::binary::u8)]
+    version: u8,
+}
+
+/// The `n`-th packet of the fixtures, see `tests/fixtures/README.md`
+fn uas(n: u64) -> Uas {
+    Uas { timestamp: 0x0004_59F4_A6AA_4AA8 + n, version: 0x13 }
+}
+
+fn fixture(name: &str) -> Capture {
+    Capture::open(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn klv_group() -> UdpFilter {
+    UdpFilter::new().address("239.1.1.1".parse().unwrap()).port(5000)
+}
+
+fn at(seconds: u64, nanos: u32) -> std::time::SystemTime {
+    UNIX_EPOCH + Duration::new(seconds, nanos)
+}
+
+#[test]
+fn frames_of_classic_pcap() {
+    let capture = fixture("klv_udp.pcap");
+    let frames: Vec<_> = capture.frames().collect::<Result<_, _>>().unwrap();
+    assert_eq!(frames.len(), 5);
+    assert!(frames.iter().all(|frame| frame.link_type == 1));
+    assert_eq!(frames[1].timestamp, at(1_700_000_000, 100_000_000));
+    // ---- an ARP frame
+    assert_eq!(frames[0].udp(), None);
+    let udp = frames[1].udp().unwrap();
+    assert_eq!(udp.source, "10.0.0.2:40000".parse::<SocketAddr>().unwrap());
+    assert_eq!(udp.destination, "239.1.1.1:5000".parse::<SocketAddr>().unwrap());
+    assert_eq!(udp.payload.len(), 30);
+    // ---- behind a VLAN tag
+    assert_eq!(frames[3].udp().unwrap().payload.len(), 60);
+}
+
+#[test]
+fn extract_raw_udp() {
+    let capture = fixture("klv_udp.pcap");
+    let captured: Vec<_> = capture.extract::<Uas>(klv_group()).collect::<Result<_, _>>().unwrap();
+    let packets: Vec<_> = captured.iter().map(|captured| captured.packet.clone()).collect();
+    assert_eq!(packets.len(), 5);
+    assert_eq!(packets[..3], [Ok(uas(0)), Ok(uas(1)), Ok(uas(2))]);
+    // ---- garbage before the last packet is skipped
+    assert_eq!(packets[3], Err(PacketError { skipped: 0..4, error: None }));
+    assert_eq!(packets[4], Ok(uas(3)));
+    let timestamps: Vec<_> = captured.iter().map(|captured| captured.timestamp).collect();
+    assert_eq!(timestamps, [100, 200, 200, 300, 300].map(|millis| at(1_700_000_000, millis * 1_000_000)));
+    assert!(captured.iter().all(|captured| captured.source == "10.0.0.2:40000".parse().unwrap()));
+}
+
+#[test]
+fn filter_by_destination() {
+    let capture = fixture("klv_udp.pcap");
+    let count = |filter: UdpFilter| capture.payloads(filter).count();
+    assert_eq!(count(UdpFilter::new()), 4);
+    assert_eq!(count(UdpFilter::new().port(6000)), 1);
+    assert_eq!(count(UdpFilter::new().address("239.1.1.2".parse().unwrap()).port(5000)), 0);
+    let other: Vec<_> = capture
+        .extract::<Uas>(UdpFilter::new().address("239.1.1.2".parse().unwrap()))
+        .map(|captured| captured.unwrap().packet)
+        .collect();
+    assert_eq!(other, vec![Ok(uas(4))]);
+}
+
+#[test]
+fn extract_mpegts_from_pcapng() {
+    let capture = fixture("klv_mpegts.pcapng");
+    let captured: Vec<_> = capture.extract::<Uas>(klv_group()).collect::<Result<_, _>>().unwrap();
+    let packets: Vec<_> = captured.iter().map(|captured| captured.packet.clone()).collect();
+    // ---- an asynchronous PES across two datagrams, then two synchronous ones
+    assert_eq!(packets, vec![Ok(uas(0)), Ok(uas(1)), Ok(uas(2)), Ok(uas(3))]);
+    let timestamps: Vec<_> = captured.iter().map(|captured| captured.timestamp).collect();
+    assert_eq!(timestamps, vec![
+        at(1_700_000_001, 0),
+        at(1_700_000_001, 0),
+        at(1_700_000_001, 66_733_333),
+        at(1_700_000_001, 100_100_000),
+    ]);
+    // ---- forced raw, the payloads are whole datagrams of transport stream packets
+    let raw: Vec<_> = capture.payloads(klv_group().encapsulation(Encapsulation::Raw)).map(Result::unwrap).collect();
+    assert_eq!(raw.len(), 4);
+    assert!(raw.iter().all(|payload| payload.data.len() == 7 * 188 && payload.data[0] == 0x47));
+}
+
+#[test]
+fn ipv6_in_pcapng() {
+    let capture = fixture("klv_mpegts.pcapng");
+    let filter = UdpFilter::new().address("ff15::1".parse().unwrap());
+    let captured: Vec<_> = capture.extract::<Uas>(filter).map(Result::unwrap).collect();
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].packet, Ok(uas(4)));
+    assert_eq!(captured[0].source, "[fe80::2]:40000".parse().unwrap());
+    assert_eq!(captured[0].timestamp, at(1_700_000_001, 200_000_000));
+}
+
+#[test]
+fn mpegts_discontinuity_drops_pes() {
+    let mut data = std::fs::read(format!("{}/tests/fixtures/klv_mpegts.pcapng", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    // ---- the continuity counter of the second packet of the asynchronous PES
+    let index = data.windows(4).position(|window| window == [0x47, 0x01, 0x01, 0x31]).unwrap();
+    data[index + 3] = 0x33;
+    let capture = Capture::from_bytes(data).unwrap();
+    let packets: Vec<_> = capture.extract::<Uas>(klv_group()).map(|captured| captured.unwrap().packet).collect();
+    assert_eq!(packets, vec![Ok(uas(2)), Ok(uas(3))]);
+}
+
+#[test]
+fn mpegts_klv_pids() {
+    let capture = fixture("klv_mpegts.pcapng");
+    let packets = |filter: UdpFilter| -> Vec<_> { capture.extract::<Uas>(filter).map(|captured| captured.unwrap().packet).collect() };
+    // ---- the PES of stream ID 0xBD on PID 0x103 is registered as AC-3, and
+    // ---- is only read as KLV when asked to
+    assert_eq!(packets(klv_group().pid(0x103)), vec![Ok(uas(0))]);
+    assert_eq!(packets(klv_group().pid(0x101)), vec![Ok(uas(0)), Ok(uas(1))]);
+    // ---- without a program association table, no PID is KLV
+    let mut data = std::fs::read(format!("{}/tests/fixtures/klv_mpegts.pcapng", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let index = data.windows(4).position(|window| window == [0x47, 0x40, 0x00, 0x10]).unwrap();
+    data[index + 8] ^= 0xFF;
+    let capture = Capture::from_bytes(data).unwrap();
+    assert_eq!(capture.extract::<Uas>(klv_group()).count(), 0);
+}
+
+#[test]
+fn big_endian_nanosecond_raw_ip() {
+    let capture = fixture("klv_raw_ip.pcap");
+    let frames: Vec<_> = capture.frames().map(Result::unwrap).collect();
+    assert_eq!(frames.len(), 3);
+    assert!(frames.iter().all(|frame| frame.link_type == 101));
+    // ---- fragmented datagrams are skipped
+    let captured: Vec<_> = capture.extract::<Uas>(klv_group()).map(Result::unwrap).collect();
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].packet, Ok(uas(1)));
+    assert_eq!(captured[0].timestamp, at(1_700_000_002, 700));
+}
+
+#[test]
+fn truncated_capture() {
+    let mut data = std::fs::read(format!("{}/tests/fixtures/klv_udp.pcap", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    data.truncate(data.len() - 10);
+    let capture = Capture::from_bytes(data).unwrap();
+    let results: Vec<_> = capture.payloads(klv_group()).collect();
+    assert_eq!(results.len(), 3);
+    assert!(results[..2].iter().all(Result::is_ok));
+    assert!(matches!(results[2], Err(PcapError::Truncated { .. })));
+}
+
+#[test]
+fn bad_magic() {
+    assert!(matches!(Capture::from_bytes(vec![0x00; 24]), Err(PcapError::Magic(0))));
+    assert!(matches!(Capture::open("does/not/exist.pcap"), Err(PcapError::Io(_))));
+}
+
+/// A pcapng capture of one interface, of `options`, and one empty enhanced
+/// packet block at `units`
+fn pcapng(options: &[(u16, &[u8])], units: u64) -> Vec<u8> {
+    let block = |ty: u32, body: &[u8]| {
+        let len = (12 + body.len().next_multiple_of(4)) as u32;
+        let mut block = [ty.to_le_bytes(), len.to_le_bytes()].concat();
+        block.extend(body);
+        block.resize(len as usize - 4, 0);
+        block.extend(len.to_le_bytes());
+        block
+    };
+    let mut interface = vec![0x01, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00];
+    for (code, value) in options {
+        interface.extend(code.to_le_bytes());
+        interface.extend((value.len() as u16).to_le_bytes());
+        interface.extend(*value);
+        interface.resize(interface.len().next_multiple_of(4), 0);
+    }
+    let mut packet = vec![0x00; 4];
+    packet.extend(((units >> 32) as u32).to_le_bytes());
+    packet.extend((units as u32).to_le_bytes());
+    packet.extend([0x00; 8]);
+    [
+        block(0x0A0D0D0A, &[0x4D, 0x3C, 0x2B, 0x1A, 0x01, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+        block(0x00000001, &interface),
+        block(0x00000006, &packet),
+    ].concat()
+}
+
+#[test]
+fn extreme_timestamps() {
+    let timestamp = |options: &[(u16, &[u8])], units: u64| {
+        Capture::from_bytes(pcapng(options, units)).unwrap().frames().next().unwrap().map(|frame| frame.timestamp)
+    };
+    // ---- seconds, as 10^0 and 2^0
+    assert_eq!(timestamp(&[(9, &[0x00])], 1_700_000_000).unwrap(), at(1_700_000_000, 0));
+    assert_eq!(timestamp(&[(9, &[0x80])], 1_700_000_000).unwrap(), at(1_700_000_000, 0));
+    assert!(matches!(timestamp(&[(9, &[0x00])], u64::MAX), Err(PcapError::Timestamp { offset: 56 })));
+    assert!(matches!(timestamp(&[(9, &[0x80])], u64::MAX), Err(PcapError::Timestamp { .. })));
+    // ---- if_tsoffset, in seconds
+    assert_eq!(timestamp(&[(14, &(-1i64).to_le_bytes())], 1_000_000).unwrap(), at(0, 0));
+    assert!(matches!(timestamp(&[(14, &i64::MAX.to_le_bytes())], 1_000_000_000_000_000), Err(PcapError::Timestamp { .. })));
+    assert!(matches!(timestamp(&[(9, &[0x00]), (14, &i64::MAX.to_le_bytes())], 1), Err(PcapError::Timestamp { .. })));
+}
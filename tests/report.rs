@@ -0,0 +1,121 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Feed {
+    #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+    id: u16,
+
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+
+    #[klv(key = 0x03, dyn = true, dec = tinyklv::dec::binary::to_string_utf8)]
+    name: Option<String>,
+
+    #[klv(key = 0x04, dec = tinyklv::dec::binary::u8)]
+    flag: Option<u8>,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    stream = &str,
+    key(dec = tinyklv::codecs::string::dec::key),
+    len(dec = tinyklv::codecs::string::dec::len),
+)]
+struct Text {
+    #[klv(key = "ALT", dec = tinyklv::codecs::string::dec::f64)]
+    altitude: Option<f64>,
+}
+
+#[test]
+fn clean_report() {
+    let (feed, report) = Feed::decode_with_report(&mut &[
+        0x01, 0x02, 0x00, 0x07,
+        0x03, 0x02, b'o', b'k',
+    ][..]).unwrap();
+    assert_eq!(feed, Feed { id: 7, heading: None, name: Some("ok".into()), flag: None });
+    assert!(report.is_clean());
+    assert_eq!(report, DecodeReport::default());
+}
+
+#[test]
+fn unknown_and_trailing() {
+    let mut input = &[
+        0x01, 0x02, 0x00, 0x07,
+        0x7F, 0x03, 0xAA, 0xBB, 0xCC,
+        0x02,
+    ][..];
+    let (feed, report) = Feed::decode_with_report(&mut input).unwrap();
+    assert_eq!(feed.id, 7);
+    assert_eq!(report.unknown, vec![UnknownKey { key: "127".into(), offset: 4, len: 3 }]);
+    assert_eq!(report.trailing, 1);
+    // ---- trailing bytes are left in the input
+    assert_eq!(input, &[0x02]);
+}
+
+#[test]
+fn failed_fields_and_length_mismatches() {
+    let (feed, report) = Feed::decode_with_report(&mut &[
+        0x02, 0x03, 0x00, 0x01, 0x02,
+        0x04, 0x00,
+        0x01, 0x02, 0x00, 0x07,
+    ][..]).unwrap();
+    assert_eq!(feed, Feed { id: 7, heading: None, name: None, flag: None });
+    assert_eq!(report.length_mismatches.len(), 1);
+    assert_eq!(report.length_mismatches[0].field, Some("heading"));
+    assert_eq!(report.length_mismatches[0].kind, KlvErrorKind::LengthMismatch { expected: 3, actual: 2 });
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].field, Some("flag"));
+    assert_eq!(report.failed[0].path, vec!["tag 4"]);
+    assert_eq!(report.failed[0].offset, 7);
+}
+
+#[test]
+fn duplicates_keep_the_last_value() {
+    let (feed, report) = Feed::decode_with_report(&mut &[
+        0x01, 0x01, 0x00,
+        0x01, 0x02, 0x00, 0x07,
+        0x01, 0x02, 0x00, 0x08,
+    ][..]).unwrap();
+    assert_eq!(feed.id, 8);
+    assert_eq!(report.duplicates, vec![
+        DuplicateKey { field: "id", key: "tag 1", offset: 3 },
+        DuplicateKey { field: "id", key: "tag 1", offset: 7 },
+    ]);
+    assert_eq!(report.failed.len(), 1);
+}
+
+#[test]
+fn missing_required_field_fails() {
+    let err = Feed::decode_with_report(&mut &[0x02, 0x02, 0x00, 0x07][..]).unwrap_err();
+    assert_eq!(KlvError::from(err).kind, KlvErrorKind::MissingField);
+}
+
+#[test]
+fn text_report() {
+    let (text, report) = Text::decode_with_report(&mut "ALT:5:100.5;HDG:2:90;").unwrap();
+    assert_eq!(text.altitude, Some(100.5));
+    assert_eq!(report.unknown, vec![UnknownKey { key: "\"HDG\"".into(), offset: 12, len: 2 }]);
+    assert_eq!(report.trailing, 0);
+}
+
+#[test]
+fn truncated_unknown_value() {
+    let mut input = &[
+        0x01, 0x02, 0x00, 0x07,
+        0x09, 0x05, 0x04, 0x01, 0x02,
+    ][..];
+    let (feed, report) = Feed::decode_with_report(&mut input).unwrap();
+    // ---- the value bytes are not read as keys
+    assert_eq!(feed, Feed { id: 7, heading: None, name: None, flag: None });
+    assert_eq!(report.unknown, vec![UnknownKey { key: "9".into(), offset: 4, len: 5 }]);
+    assert_eq!(report.length_mismatches.len(), 1);
+    assert_eq!(report.length_mismatches[0].kind, KlvErrorKind::LengthMismatch { expected: 5, actual: 3 });
+    assert_eq!(report.length_mismatches[0].offset, 6);
+    assert_eq!(report.trailing, 5);
+    assert_eq!(input, &[0x09, 0x05, 0x04, 0x01, 0x02]);
+}
@@ -0,0 +1,255 @@
+//! Property-based round trips (encode -> decode = identity) for the codecs
+//! and for a set of representative derived structs
+use proptest::prelude::*;
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::codecs::ber::{BerLength, BerOid};
+
+// --------------------------------------------------
+// binary
+// --------------------------------------------------
+macro_rules! binary_roundtrip {
+    ($($ty:ident),*) => { paste::paste! { $(
+        proptest! {
+            #[test]
+            fn [<binary_ $ty>](value: $ty) {
+                prop_assert_eq!(tinyklv::dec::binary::[<be_ $ty>](&mut &tinyklv::enc::binary::[<be_ $ty>](value)[..]).unwrap(), value);
+                prop_assert_eq!(tinyklv::dec::binary::[<le_ $ty>](&mut &tinyklv::enc::binary::[<le_ $ty>](value)[..]).unwrap(), value);
+                prop_assert_eq!(tinyklv::dec::binary::$ty(&mut &tinyklv::enc::binary::$ty(value)[..]).unwrap(), value);
+            }
+
+            #[test]
+            fn [<binary_ $ty _lengthed>](value: $ty, len in 1..=std::mem::size_of::<$ty>()) {
+                // only the lowest `len` bytes are kept, so mask the value
+                let bits = 8 * len as u32;
+                let value = match bits >= <$ty>::BITS {
+                    true => value,
+                    false => value & ((1 << bits) - 1),
+                };
+                let be = tinyklv::enc::binary::[<be_ $ty _lengthed>](len)(value);
+                let le = tinyklv::enc::binary::[<le_ $ty _lengthed>](len)(value);
+                prop_assert_eq!(be.len(), len);
+                prop_assert_eq!(le.len(), len);
+                prop_assert_eq!(tinyklv::dec::binary::[<be_ $ty _lengthed>](len)(&mut &be[..]).unwrap(), value);
+                prop_assert_eq!(tinyklv::dec::binary::[<le_ $ty _lengthed>](len)(&mut &le[..]).unwrap(), value);
+            }
+        }
+    )* }};
+}
+binary_roundtrip!(u16, u32, u64, u128);
+
+macro_rules! binary_roundtrip_signed {
+    ($($ty:ident),*) => { paste::paste! { $(
+        proptest! {
+            #[test]
+            fn [<binary_ $ty>](value: $ty) {
+                prop_assert_eq!(tinyklv::dec::binary::[<be_ $ty>](&mut &tinyklv::enc::binary::[<be_ $ty>](value)[..]).unwrap(), value);
+                prop_assert_eq!(tinyklv::dec::binary::[<le_ $ty>](&mut &tinyklv::enc::binary::[<le_ $ty>](value)[..]).unwrap(), value);
+                prop_assert_eq!(tinyklv::dec::binary::$ty(&mut &tinyklv::enc::binary::$ty(value)[..]).unwrap(), value);
+            }
+        }
+    )* }};
+}
+binary_roundtrip_signed!(i16, i32, i64, i128);
+
+macro_rules! binary_roundtrip_float {
+    ($($ty:ident),*) => { paste::paste! { $(
+        proptest! {
+            #[test]
+            fn [<binary_ $ty>](value: $ty) {
+                // compare bits, since NaN != NaN
+                prop_assert_eq!(tinyklv::dec::binary::[<be_ $ty>](&mut &tinyklv::enc::binary::[<be_ $ty>](value)[..]).unwrap().to_bits(), value.to_bits());
+                prop_assert_eq!(tinyklv::dec::binary::[<le_ $ty>](&mut &tinyklv::enc::binary::[<le_ $ty>](value)[..]).unwrap().to_bits(), value.to_bits());
+                prop_assert_eq!(tinyklv::dec::binary::$ty(&mut &tinyklv::enc::binary::$ty(value)[..]).unwrap().to_bits(), value.to_bits());
+            }
+        }
+    )* }};
+}
+binary_roundtrip_float!(f32, f64);
+
+proptest! {
+    #[test]
+    fn binary_u8_i8(unsigned: u8, signed: i8) {
+        prop_assert_eq!(tinyklv::dec::binary::u8(&mut &tinyklv::enc::binary::u8(unsigned)[..]).unwrap(), unsigned);
+        prop_assert_eq!(tinyklv::dec::binary::i8(&mut &tinyklv::enc::binary::i8(signed)[..]).unwrap(), signed);
+    }
+
+    #[test]
+    fn binary_as_usize(value: u16) {
+        let encoded = tinyklv::enc::binary::be_u16_from_usize(value as usize);
+        prop_assert_eq!(tinyklv::dec::binary::be_u16_as_usize(&mut &encoded[..]).unwrap(), value as usize);
+    }
+
+    // --------------------------------------------------
+    // ber
+    // --------------------------------------------------
+    #[test]
+    fn ber_length(value: u64, wide: u128) {
+        let encoded = BerLength::new(&value).encode_value();
+        prop_assert_eq!(BerLength::<u64>::decode(&mut &encoded[..]).unwrap(), BerLength::new(&value));
+        let encoded = BerLength::new(&wide).encode_value();
+        prop_assert_eq!(BerLength::<u128>::decode(&mut &encoded[..]).unwrap(), BerLength::new(&wide));
+        // canonical encodings are also valid DER
+        let encoded = tinyklv::enc::ber::ber_length(&(value as usize));
+        prop_assert_eq!(tinyklv::dec::ber::ber_length_der(&mut &encoded[..]).unwrap(), value as usize);
+    }
+
+    #[test]
+    fn ber_oid(value in prop_oneof![Just(0u64), any::<u64>()], wide: u128) {
+        let encoded = tinyklv::enc::ber::ber_oid(&value);
+        prop_assert_eq!(tinyklv::dec::ber::ber_oid::<u64>(&mut &encoded[..]).unwrap(), value);
+        let encoded = BerOid::encode_value(&wide);
+        prop_assert_eq!(BerOid::<u128>::decode(&mut &encoded[..]).unwrap().value, wide);
+    }
+
+    // --------------------------------------------------
+    // strings
+    // --------------------------------------------------
+    #[test]
+    fn string_utf8(value: String) {
+        let encoded = tinyklv::enc::binary::from_string_utf8(&value);
+        prop_assert_eq!(&tinyklv::dec::binary::to_string_utf8(encoded.len())(&mut &encoded[..]).unwrap(), &value);
+        prop_assert_eq!(&tinyklv::dec::binary::to_string_utf8_strict(encoded.len())(&mut &encoded[..]).unwrap(), &value);
+    }
+
+    #[test]
+    fn string_utf16(value: String) {
+        let encoded = tinyklv::enc::binary::from_string_utf16(&value);
+        prop_assert_eq!(tinyklv::dec::binary::to_string_utf16(encoded.len())(&mut &encoded[..]).unwrap(), value);
+    }
+}
+
+#[cfg(feature = "ascii")]
+proptest! {
+    #[test]
+    fn string_ascii(value in "[ -~]*") {
+        let encoded = tinyklv::enc::binary::from_string_ascii(&value);
+        prop_assert_eq!(tinyklv::dec::binary::to_string_ascii(encoded.len())(&mut &encoded[..]).unwrap(), value);
+    }
+}
+
+// --------------------------------------------------
+// derived structs
+// --------------------------------------------------
+#[derive(Klv, Debug, Clone, PartialEq)]
+#[klv(
+    sentinel = 0x06,
+    key(enc = encode_key, dec = tinyklv::dec::ber::ber_oid::<u64>),
+    len(enc = encode_len, dec = tinyklv::dec::ber::ber_length),
+)]
+/// A MISB ST 0601-like local set, with BER-OID keys and BER lengths
+/// 
+/// The 16-byte universal key is shortened to a single byte sentinel
+struct Uas {
+    #[klv(key = 0x02, enc = encode_u64, dec = tinyklv::dec::binary::be_u64)]
+    timestamp: u64,
+
+    #[klv(key = 0x03, dyn = true, enc = tinyklv::enc::binary::from_string_utf8, dec = tinyklv::dec::binary::to_string_utf8)]
+    mission: String,
+
+    #[klv(key = 0x05, enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: Option<u16>,
+
+    #[klv(key = 0x06, enc = encode_opt_i16, dec = tinyklv::dec::binary::be_i16)]
+    pitch: Option<i16>,
+
+    #[klv(key = 0x0D, enc = encode_opt_i32, dec = tinyklv::dec::binary::be_i32)]
+    latitude: Option<i32>,
+
+    #[klv(key = 0x8101, dyn = true, enc = encode_bytes, dec = take_bytes)]
+    extended: Vec<u8>,
+}
+
+#[derive(Klv, Debug, Clone, PartialEq)]
+#[klv(
+    key(enc = tinyklv::enc::binary::u8, dec = tinyklv::dec::binary::u8),
+    len(enc = tinyklv::enc::binary::u8_from_usize, dec = tinyklv::dec::binary::u8_as_usize),
+)]
+/// A tuple struct, with single byte keys and lengths
+struct Pair(
+    #[klv(key = 0x01, enc = encode_f64, dec = tinyklv::dec::binary::be_f64)]
+    f64,
+    #[klv(key = 0x02, enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    Option<u16>,
+);
+
+fn encode_key(key: u64) -> Vec<u8> { tinyklv::enc::ber::ber_oid(&key) }
+fn encode_len(len: usize) -> Vec<u8> { tinyklv::enc::ber::ber_length(&len) }
+fn encode_u64(input: &u64) -> Vec<u8> { tinyklv::enc::binary::be_u64(*input) }
+fn encode_f64(input: &f64) -> Vec<u8> { tinyklv::enc::binary::be_f64(*input) }
+fn encode_bytes(input: &[u8]) -> Vec<u8> { input.to_vec() }
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_u16) }
+fn encode_opt_i16(input: &Option<i16>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_i16) }
+fn encode_opt_i32(input: &Option<i32>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_i32) }
+fn take_bytes(len: usize) -> impl Fn(&mut &[u8]) -> winnow::PResult<Vec<u8>> {
+    move |input| winnow::token::take(len).map(|x: &[u8]| x.to_vec()).parse_next(input)
+}
+
+prop_compose! {
+    fn uas()(
+        timestamp: u64,
+        mission in "[A-Z0-9 ]{0,32}",
+        heading: Option<u16>,
+        pitch: Option<i16>,
+        latitude: Option<i32>,
+        extended in proptest::collection::vec(any::<u8>(), 0..300),
+    ) -> Uas {
+        Uas { timestamp, mission, heading, pitch, latitude, extended }
+    }
+}
+
+proptest! {
+    #[test]
+    fn derived_local_set(value in uas()) {
+        let encoded = value.encode();
+        prop_assert_eq!(Uas::extract(&mut &encoded[..]).unwrap(), value.clone());
+        prop_assert_eq!(Uas::decode(&mut &value.encode_value()[..]).unwrap(), value);
+    }
+
+    #[test]
+    fn derived_tuple_struct(first: f64, second: Option<u16>) {
+        let value = Pair(first, second);
+        let decoded = Pair::decode(&mut &value.encode_value()[..]).unwrap();
+        prop_assert_eq!(decoded.0.to_bits(), first.to_bits());
+        prop_assert_eq!(decoded.1, second);
+    }
+}
+
+// --------------------------------------------------
+// mapped
+// --------------------------------------------------
+proptest! {
+    #[test]
+    fn mapped_linear_raw(unsigned: u16, signed in -i32::MAX..=i32::MAX) {
+        let encoded = unsigned.to_be_bytes();
+        let value = tinyklv::dec::mapped::linear_u16(0.0, 360.0)(&mut &encoded[..]).unwrap();
+        prop_assert_eq!(tinyklv::enc::mapped::linear_u16(0.0, 360.0)(&value), Some(encoded.to_vec()));
+        let encoded = signed.to_be_bytes();
+        let value = tinyklv::dec::mapped::linear_i32(-90.0, 90.0)(&mut &encoded[..]).unwrap();
+        prop_assert_eq!(tinyklv::enc::mapped::linear_i32(-90.0, 90.0)(&value), encoded.to_vec());
+    }
+
+    #[test]
+    fn mapped_linear_value(value in -90.0..=90.0f64) {
+        let encoded = tinyklv::enc::mapped::linear_i32(-90.0, 90.0)(&value);
+        let decoded = tinyklv::dec::mapped::linear_i32(-90.0, 90.0)(&mut &encoded[..]).unwrap();
+        // within half a step
+        prop_assert!((decoded - value).abs() <= 90.0 / (i32::MAX as f64));
+    }
+}
+
+// --------------------------------------------------
+// fixed length
+// --------------------------------------------------
+proptest! {
+    #[test]
+    fn fixed_length(value: i64, len in 1..=16usize, big: bool) {
+        use tinyklv::codecs::binary::{Endian, FixedLength};
+        let codec = FixedLength { len, endian: if big { Endian::Big } else { Endian::Little } };
+        match codec.encode(&value) {
+            Ok(encoded) => prop_assert_eq!(codec.decode::<i64>(&mut &encoded[..]).unwrap(), value),
+            // only values needing more than `len` bytes overflow
+            Err(_) => prop_assert!(len < 8 && (value >= 1 << (8 * len - 1) || value < -(1 << (8 * len - 1)))),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::router::{KlvRouter, Routed};
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = b"\x06\x0E\x2B\x34\x01",
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Short {
+    #[klv(key = 0x01, dec = tinyklv::dec::binary::u8)]
+    value: u8,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = b"\x06\x0E\x2B\x34\x01\x02",
+    key(dec = tinyklv::codecs::binary::dec::u8),
+    len(dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Long {
+    #[klv(key = 0x01, dec = tinyklv::dec::binary::be_u16)]
+    value: u16,
+}
+
+#[derive(Debug, PartialEq)]
+enum Packet {
+    Short(Short),
+    Long(Long),
+}
+
+fn router() -> KlvRouter<Packet> {
+    KlvRouter::new()
+        .route(Packet::Short)
+        .route(Packet::Long)
+}
+
+#[test]
+fn longest_sentinel_wins() {
+    let data = [
+        0x06, 0x0E, 0x2B, 0x34, 0x01, 0x02, 0x04, 0x01, 0x02, 0x01, 0x02,
+        0x06, 0x0E, 0x2B, 0x34, 0x01, 0x03, 0x01, 0x01, 0x07,
+    ];
+    let routed: Vec<_> = router().iter(&data).collect();
+    assert_eq!(routed, vec![
+        Ok(Routed::Packet(Packet::Long(Long { value: 0x0102 }))),
+        Ok(Routed::Packet(Packet::Short(Short { value: 7 }))),
+    ]);
+}
+
+#[test]
+fn trait_objects() {
+    let router: KlvRouter<Box<dyn std::fmt::Debug>> = KlvRouter::new()
+        .route(|x: Short| Box::new(x) as Box<dyn std::fmt::Debug>)
+        .route(|x: Long| Box::new(x) as Box<dyn std::fmt::Debug>);
+    let data = [0x06, 0x0E, 0x2B, 0x34, 0x01, 0x03, 0x01, 0x01, 0x07];
+    let routed: Vec<_> = router.iter(&data).collect();
+    match &routed[..] {
+        [Ok(Routed::Packet(packet))] => assert_eq!(format!("{:?}", packet), "Short { value: 7 }"),
+        _ => panic!("expected a single packet"),
+    }
+}
+
+#[test]
+fn noise_and_bad_packets_resynchronize() {
+    let data = [
+        0xFF, 0xFF,
+        0x06, 0x0E, 0x2B, 0x34, 0x01, 0x03, 0x09, 0x01, 0x07,
+        0x06, 0x0E, 0x2B, 0x34, 0x01, 0x03, 0x01, 0x01, 0x07,
+    ];
+    let router = router();
+    let mut iter = router.iter(&data);
+    let noise = iter.next().unwrap().unwrap_err();
+    assert_eq!(noise.skipped, 0..2);
+    assert_eq!(noise.error, None);
+    let bad = iter.next().unwrap().unwrap_err();
+    assert_eq!(bad.skipped, 2..11);
+    assert_eq!(bad.error.unwrap().kind, KlvErrorKind::MissingField);
+    assert_eq!(iter.next(), Some(Ok(Routed::Packet(Packet::Short(Short { value: 7 })))));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.position(), data.len());
+}
+
+#[test]
+fn truncated_unmatched_label() {
+    let data = [0x06, 0x0E, 0x2B, 0x34, 0x7F, 0x00];
+    let routed: Vec<_> = router().iter(&data).collect();
+    assert_eq!(routed.len(), 1);
+    let err = routed[0].as_ref().unwrap_err();
+    assert_eq!(err.skipped, 0..6);
+    assert!(err.error.is_some());
+}
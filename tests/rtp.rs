@@ -0,0 +1,175 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::rtp::*;
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000",
+    key(dec = tinyklv::dec::binary::u8),
+    len(dec = tinyklv::dec::ber::ber_length),
+)]
+struct Uas {
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::be_u64)]
+    timestamp: u64,
+    #[klv(key = 0x41, dec = tinyklv::dec::binary::u8)]
+    version: u8,
+}
+
+// --------------------------------------------------
+// a 30-byte local set, in three packets of payload
+// type 96, SSRC 0xDEADBEEF and timestamp 90000
+// --------------------------------------------------
+const P1: [u8; 24] = [
+    0x80, 0x60, 0x12, 0x34, 0x00, 0x01, 0x5F, 0x90, 0xDE, 0xAD, 0xBE, 0xEF,
+    0x06, 0x0E, 0x2B, 0x34, 0x02, 0x0B, 0x01, 0x01, 0x0E, 0x01, 0x03, 0x01,
+];
+const P2: [u8; 24] = [
+    0x80, 0x60, 0x12, 0x35, 0x00, 0x01, 0x5F, 0x90, 0xDE, 0xAD, 0xBE, 0xEF,
+    0x01, 0x00, 0x00, 0x00, 0x0D, 0x02, 0x08, 0x00, 0x04, 0x59, 0xF4, 0xA6,
+];
+const P3: [u8; 18] = [
+    0x80, 0xE0, 0x12, 0x36, 0x00, 0x01, 0x5F, 0x90, 0xDE, 0xAD, 0xBE, 0xEF,
+    0xAA, 0x4A, 0xA8, 0x41, 0x01, 0x13,
+];
+// ---- a single-packet unit, at timestamp 93003
+const P4: [u8; 42] = [
+    0x80, 0xE0, 0x12, 0x37, 0x00, 0x01, 0x6B, 0x4B, 0xDE, 0xAD, 0xBE, 0xEF,
+    0x06, 0x0E, 0x2B, 0x34, 0x02, 0x0B, 0x01, 0x01, 0x0E, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00,
+    0x0D, 0x02, 0x08, 0x00, 0x04, 0x59, 0xF4, 0xA6, 0xAA, 0x4A, 0xA9, 0x41, 0x01, 0x13,
+];
+
+fn unit() -> Vec<u8> {
+    [&P1[12..], &P2[12..], &P3[12..]].concat()
+}
+
+fn push_all(depacketizer: &mut Depacketizer, packets: &[&[u8]]) -> Vec<KlvUnit> {
+    packets.iter().filter_map(|packet| depacketizer.push(packet).unwrap()).collect()
+}
+
+#[test]
+fn parse_header() {
+    let packet = RtpPacket::parse(&P1).unwrap();
+    assert_eq!(packet.header, RtpHeader {
+        marker: false,
+        payload_type: 96,
+        sequence: 0x1234,
+        timestamp: 90_000,
+        ssrc: 0xDEADBEEF,
+        ..Default::default()
+    });
+    assert_eq!(packet.payload, &P1[12..]);
+    assert!(RtpPacket::parse(&P3).unwrap().header.marker);
+}
+
+#[test]
+fn parse_csrc_extension_and_padding() {
+    let datagram = [
+        0xB1, 0xE0, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        // ---- one CSRC
+        0x00, 0x00, 0x00, 0x02,
+        // ---- a one-word extension
+        0xBE, 0xDE, 0x00, 0x01, 0x01, 0x02, 0x03, 0x04,
+        // ---- payload, then 3 bytes of padding
+        0xAA, 0xBB, 0x00, 0x00, 0x03,
+    ];
+    let packet = RtpPacket::parse(&datagram).unwrap();
+    assert_eq!(packet.header.csrc, vec![2]);
+    assert_eq!(packet.header.extension, Some(RtpExtension { profile: 0xBEDE, data: vec![1, 2, 3, 4] }));
+    assert_eq!(packet.payload, &[0xAA, 0xBB]);
+    // ---- encoded without padding
+    let mut expected = datagram[..26].to_vec();
+    expected[0] = 0x91;
+    assert_eq!(packet.encode(), expected);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(RtpPacket::parse(&P1[..11]), Err(RtpError::Truncated { expected: 12, actual: 11 }));
+    let mut packet = P1;
+    packet[0] = 0x40;
+    assert_eq!(RtpPacket::parse(&packet), Err(RtpError::Version(1)));
+    packet[0] = 0x81;
+    assert_eq!(RtpPacket::parse(&packet[..14]), Err(RtpError::Truncated { expected: 16, actual: 14 }));
+    packet[0] = 0xA0;
+    packet[23] = 0x20;
+    assert_eq!(RtpPacket::parse(&packet), Err(RtpError::Padding { count: 0x20, payload: 12 }));
+}
+
+#[test]
+fn reassemble() {
+    let mut depacketizer = Depacketizer::new();
+    let units = push_all(&mut depacketizer, &[&P1, &P2, &P3, &P4]);
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0], KlvUnit { timestamp: 90_000, ssrc: 0xDEADBEEF, sequence: 0x1234, data: unit() });
+    assert_eq!(units[0].extract::<Uas>(), Ok(Uas { timestamp: 0x0004_59F4_A6AA_4AA8, version: 0x13 }));
+    assert_eq!(units[1].timestamp, 93_003);
+    assert_eq!(units[1].extract::<Uas>(), Ok(Uas { timestamp: 0x0004_59F4_A6AA_4AA9, version: 0x13 }));
+    assert_eq!(depacketizer.stats(), RtpStats { packets: 4, units: 2, ..Default::default() });
+}
+
+#[test]
+fn lost_packet_discards_unit() {
+    let mut depacketizer = Depacketizer::new();
+    let units = push_all(&mut depacketizer, &[&P1, &P3, &P4]);
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].timestamp, 93_003);
+    assert_eq!(depacketizer.stats(), RtpStats { packets: 3, lost: 1, units: 1, discarded: 1, ..Default::default() });
+}
+
+#[test]
+fn lost_start_discards_unit() {
+    // ---- P1 lost after a previous unit: P2 and P3 cannot be trusted
+    let mut depacketizer = Depacketizer::new();
+    let mut previous = P4;
+    previous[2..4].copy_from_slice(&0x1233u16.to_be_bytes());
+    let units = push_all(&mut depacketizer, &[&previous, &P2, &P3, &P4]);
+    assert_eq!(units.iter().map(|unit| unit.sequence).collect::<Vec<_>>(), vec![0x1233, 0x1237]);
+    assert_eq!(depacketizer.stats().lost, 1);
+    assert_eq!(depacketizer.stats().discarded, 1);
+}
+
+#[test]
+fn late_and_duplicate_packets() {
+    let mut depacketizer = Depacketizer::new();
+    let units = push_all(&mut depacketizer, &[&P1, &P2, &P2, &P1, &P3]);
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].data, unit());
+    assert_eq!(depacketizer.stats().late, 2);
+}
+
+#[test]
+fn missing_marker() {
+    // ---- a new timestamp ends the unit in progress, which is discarded
+    let mut depacketizer = Depacketizer::new();
+    let mut next = P4;
+    next[2..4].copy_from_slice(&0x1235u16.to_be_bytes());
+    let units = push_all(&mut depacketizer, &[&P1, &next]);
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].data, &P4[12..]);
+    assert_eq!(depacketizer.stats(), RtpStats { packets: 2, units: 1, discarded: 1, ..Default::default() });
+}
+
+#[test]
+fn packetize() {
+    let mut packetizer = Packetizer::new(96, 0xDEADBEEF, 24).with_sequence(0x1234);
+    let packets = packetizer.packetize(&unit(), 90_000);
+    assert_eq!(packets, vec![P1.to_vec(), P2.to_vec(), P3.to_vec()]);
+    assert_eq!(packetizer.sequence(), 0x1237);
+    // ---- within the MTU, a single packet
+    let mut packetizer = Packetizer::new(96, 0xDEADBEEF, 1400).with_sequence(0x1237);
+    assert_eq!(packetizer.packetize(&P4[12..], 93_003), vec![P4.to_vec()]);
+}
+
+#[test]
+fn roundtrip_with_wraparound() {
+    let data: Vec<u8> = (0..=255).collect();
+    let mut packetizer = Packetizer::new(127, 7, 100).with_sequence(0xFFFE);
+    let mut depacketizer = Depacketizer::new();
+    for timestamp in [0, 3003, 6006] {
+        let packets = packetizer.packetize(&data, timestamp);
+        assert!(packets.iter().all(|packet| packet.len() <= 100));
+        let units: Vec<_> = packets.iter().filter_map(|packet| depacketizer.push(packet).unwrap()).collect();
+        assert_eq!(units, vec![KlvUnit { timestamp, ssrc: 7, sequence: units[0].sequence, data: data.clone() }]);
+    }
+    assert_eq!(depacketizer.stats(), RtpStats { packets: 9, units: 3, ..Default::default() });
+}
@@ -0,0 +1,71 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+
+#[derive(Klv)]
+#[klv(
+    sentinel = 0x06u8,
+    key(enc = tinyklv::codecs::binary::enc::u8,
+        dec = tinyklv::codecs::binary::dec::u8),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Bar {
+    #[klv(key = 0x0Au8, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    value: u16,
+    not_klv: Vec<u8>,
+}
+
+#[derive(Klv)]
+#[klv(
+    key(enc = encode_key, dec = tinyklv::dec::ber::ber_oid::<u64>),
+    len(enc = tinyklv::codecs::binary::enc::u8_from_usize,
+        dec = tinyklv::codecs::binary::dec::u8_as_usize),
+)]
+struct Oid {
+    #[klv(key = 0x01, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    small: u16,
+    #[klv(key = 200, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    large: u16,
+}
+
+fn encode_key(key: u64) -> Vec<u8> {
+    tinyklv::enc::ber::ber_oid(&key)
+}
+
+fn encode_u16(input: &u16) -> Vec<u8> {
+    tinyklv::enc::binary::be_u16(*input)
+}
+
+#[test]
+fn schema_table() {
+    assert_eq!(Bar::NAME, "Bar");
+    assert_eq!(Bar::STREAM, "&[u8]");
+    assert_eq!(Bar::SENTINEL, Some(&[0x06][..]));
+    assert_eq!(Bar::LEN_ENCODER, Some("tinyklv::codecs::binary::enc::u8_from_usize"));
+    // fields without `#[klv(..)]` are not part of the schema
+    assert_eq!(Bar::FIELDS.len(), 1);
+    let field = &Bar::FIELDS[0];
+    assert_eq!(field.key, &[0x0A]);
+    assert_eq!(field.key_literal, "0x0Au8");
+    assert_eq!(field.ty, "u16");
+    assert_eq!(field.enc, Some("encode_u16"));
+    assert_eq!(field.dec, Some("tinyklv::dec::binary::be_u16"));
+    assert!(!field.optional);
+    assert!(Bar::field_by_key(&[0x0B]).is_none());
+
+    let bar = Bar::decode(&mut &[0x0A, 0x02, 0x01, 0x02][..]).unwrap();
+    assert_eq!(bar.value, 0x0102);
+    assert!(bar.not_klv.is_empty());
+}
+
+#[test]
+fn field_by_key_on_the_wire() {
+    // ---- BER-OID keys, whose literal bytes differ from the wire
+    assert_eq!(Oid::FIELDS[1].key, &[200]);
+    assert_eq!(Oid::field_by_key(&[0x81, 0x48]).map(|field| field.field), Some("large"));
+    assert_eq!(Oid::field_by_key(&[0x01]).map(|field| field.field), Some("small"));
+    assert!(Oid::field_by_key(&[200]).is_none());
+    // ---- trailing bytes are not a key
+    assert!(Oid::field_by_key(&[0x01, 0x00]).is_none());
+    assert!(Oid::field_by_key(&[]).is_none());
+}
@@ -0,0 +1,114 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::codecs::string::{dec, enc};
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    stream = &str,
+    sentinel = "UAS",
+    key(enc = enc::key, dec = dec::key),
+    len(enc = enc::len, dec = dec::len),
+)]
+struct Uas {
+    #[klv(key = "ID", dyn = true, enc = enc::from_string, dec = dec::to_string)]
+    mission: String,
+
+    #[klv(key = "HDG", enc = enc::decimal, dec = dec::u16)]
+    heading: u16,
+
+    #[klv(key = "ALT", enc = encode_opt_f64, dec = dec::f64)]
+    altitude: Option<f64>,
+
+    #[klv(key = "FLG", enc = enc::hex, dec = dec::hex_u8)]
+    flags: u8,
+}
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    stream = &str,
+    key(enc = enc::key, dec = dec::u16),
+    len(enc = enc::len, dec = dec::len),
+)]
+/// Numeric keys
+struct Numbered {
+    #[klv(key = 1, enc = enc::decimal, dec = dec::i32)]
+    offset: i32,
+
+    #[klv(key = 12, dyn = true, enc = enc::from_string, dec = dec::to_string)]
+    name: String,
+}
+
+fn encode_opt_f64(input: &Option<f64>) -> Option<String> {
+    input.as_ref().map(enc::decimal)
+}
+
+#[test]
+fn decoders_advance_input() {
+    let mut input = "270;-12;1F;3.5";
+    assert_eq!(dec::u16(&mut input), Ok(270));
+    assert_eq!(input, ";-12;1F;3.5");
+    input = &input[1..];
+    assert_eq!(dec::i8(&mut input), Ok(-12));
+    input = &input[1..];
+    assert_eq!(dec::hex_u8(&mut input), Ok(0x1F));
+    input = &input[1..];
+    assert_eq!(dec::f64(&mut input), Ok(3.5));
+    assert_eq!(input, "");
+}
+
+#[test]
+fn decoders_fail_on_invalid_text() {
+    assert!(dec::u8(&mut "abc").is_err());
+    assert!(dec::u8(&mut "256").is_err());
+    assert!(dec::len(&mut "3:").is_err());
+    assert!(dec::key(&mut ":3:").is_err());
+}
+
+#[test]
+fn encode_decode_round_trip() {
+    let value = Uas {
+        mission: "MISSION;01 ✈".into(),
+        heading: 270,
+        altitude: Some(-12.25),
+        flags: 0x1F,
+    };
+    let encoded = value.encode_value();
+    assert_eq!(encoded, "ID:12:MISSION;01 ✈;HDG:3:270;ALT:6:-12.25;FLG:2:1F;");
+    assert_eq!(Uas::decode(&mut encoded.as_str()), Ok(value));
+}
+
+#[test]
+fn extract_packets_in_sequence() {
+    let first = Uas { mission: "A".into(), heading: 1, altitude: None, flags: 0 };
+    let second = Uas { mission: "B".into(), heading: 2, altitude: Some(100.0), flags: 0xFF };
+    let stream = first.encode() + &second.encode();
+    assert!(stream.starts_with("UAS:23:ID:1:A;"));
+    let mut input = stream.as_str();
+    assert_eq!(Uas::extract(&mut input), Ok(first));
+    assert_eq!(Uas::extract(&mut input), Ok(second));
+    assert_eq!(input, "");
+}
+
+#[test]
+fn unknown_keys_are_skipped() {
+    let mut input = "XYZ:4:junk;HDG:2:90;ID:0:;FLG:1:A;";
+    let decoded = Uas::decode(&mut input).unwrap();
+    assert_eq!(decoded, Uas { mission: String::new(), heading: 90, altitude: None, flags: 0x0A });
+    assert_eq!(input, "");
+}
+
+#[test]
+fn numeric_keys() {
+    let value = Numbered { offset: -40, name: "north".into() };
+    let encoded = value.encode_value();
+    assert_eq!(encoded, "1:3:-40;12:5:north;");
+    assert_eq!(Numbered::decode(&mut encoded.as_str()), Ok(value));
+}
+
+#[test]
+fn field_by_key() {
+    assert_eq!(Uas::field_by_key(b"HDG").map(|field| field.field), Some("heading"));
+    assert_eq!(Numbered::field_by_key(b"12").map(|field| field.field), Some("name"));
+    assert!(Numbered::field_by_key(b"13").is_none());
+    assert!(Numbered::field_by_key(&[0xFF]).is_none());
+}
@@ -0,0 +1,91 @@
+use tinyklv::codecs::time::{dec, enc};
+
+#[test]
+fn systemtime_round_trip() {
+    let timestamp = std::time::UNIX_EPOCH + std::time::Duration::from_micros(1_224_807_209_913_000);
+    let encoded = enc::systemtime_us(&timestamp);
+    assert_eq!(encoded, vec![0x00, 0x04, 0x59, 0xF4, 0xA6, 0xAA, 0x4A, 0xA8]);
+    assert_eq!(dec::systemtime_us(&mut &encoded[..]).unwrap(), timestamp);
+    // before the epoch is clamped
+    let before = std::time::UNIX_EPOCH - std::time::Duration::from_secs(1);
+    assert_eq!(enc::systemtime_us(&before), vec![0; 8]);
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_time {
+    use super::*;
+    use tinyklv::codecs::time::{LEAP_SECONDS, leap_seconds_before};
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn leap_second_table() {
+        // every entry is midnight on the 1st of January or July
+        for leap in LEAP_SECONDS {
+            let date = DateTime::from_timestamp(leap, 0).unwrap();
+            assert_eq!(date.time(), chrono::NaiveTime::MIN);
+            assert!(date.format("%m-%d").to_string() == "01-01" || date.format("%m-%d").to_string() == "07-01");
+        }
+        assert_eq!(leap_seconds_before(0), 0);
+        assert_eq!(leap_seconds_before(utc("2016-12-31T23:59:59Z").timestamp()), 26);
+        assert_eq!(leap_seconds_before(utc("2017-01-01T00:00:00Z").timestamp()), 27);
+    }
+
+    #[test]
+    fn timestamp_us_round_trip() {
+        let timestamp = utc("2008-10-24T00:13:29.913Z");
+        let encoded = enc::timestamp_us(&timestamp);
+        assert_eq!(dec::timestamp_us(&mut &encoded[..]).unwrap(), timestamp);
+        // out of range for chrono
+        assert!(dec::timestamp_us(&mut &[0xFF; 8][..]).is_err());
+    }
+
+    #[test]
+    fn misp_timestamp_round_trip() {
+        for timestamp in [
+            "1970-01-01T00:00:00Z",
+            "1972-06-30T23:59:59.999999999Z",
+            "1972-07-01T00:00:00Z",
+            "2008-10-24T00:13:29.913Z",
+            "2016-12-31T23:59:59.5Z",
+            "2017-01-01T00:00:00Z",
+            "2024-02-29T12:00:00.000000001Z",
+        ] {
+            let timestamp = utc(timestamp);
+            let encoded = enc::misp_timestamp_ns(&timestamp);
+            assert_eq!(dec::misp_timestamp_ns(&mut &encoded[..]).unwrap(), timestamp);
+        }
+    }
+
+    #[test]
+    fn misp_gps_epoch() {
+        // ST 0603: MISP Time = GPS Time + 315 964 819 s, so the GPS
+        // epoch is 315 964 819 s of MISP Time
+        let gps_epoch = utc("1980-01-06T00:00:00Z");
+        let misp: u64 = 315_964_819 * 1_000_000_000;
+        assert_eq!(enc::misp_timestamp_ns(&gps_epoch), misp.to_be_bytes());
+        assert_eq!(dec::misp_timestamp_ns(&mut &misp.to_be_bytes()[..]).unwrap(), gps_epoch);
+        // TAI - UTC = 37 s since 2017
+        let misp: u64 = (1_483_228_800 + 37) * 1_000_000_000;
+        assert_eq!(enc::misp_timestamp_ns(&utc("2017-01-01T00:00:00Z")), misp.to_be_bytes());
+    }
+
+    #[test]
+    fn misp_leap_second() {
+        let before = utc("2016-12-31T23:59:59Z");
+        let leap = NaiveDate::from_ymd_opt(2016, 12, 31).unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_250_000_000).unwrap()
+            .and_utc();
+        let after = utc("2017-01-01T00:00:00Z");
+        let misp = |t: &DateTime<Utc>| u64::from_be_bytes(enc::misp_timestamp_ns(t).try_into().unwrap());
+        // MISP time keeps counting through the leap second
+        assert_eq!(misp(&leap) - misp(&before), 1_250_000_000);
+        assert_eq!(misp(&after) - misp(&before), 2_000_000_000);
+        let encoded = enc::misp_timestamp_ns(&leap);
+        assert_eq!(dec::misp_timestamp_ns(&mut &encoded[..]).unwrap(), leap);
+        assert_eq!(leap.format("%H:%M:%S").to_string(), "23:59:60");
+    }
+}
@@ -0,0 +1,13 @@
+use tinyklv::KlvBits;
+
+#[derive(KlvBits)]
+#[bits(bytes = 2)]
+struct Flags {
+    #[bits(0..9)]
+    mode: u8,
+
+    #[bits(9..=10)]
+    active: bool,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use tinyklv::Klv;
+
+#[derive(Klv)]
+#[klv(
+    sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301",
+    key(dec = tinyklv::dec::binary::u8),
+    len(dec = tinyklv::dec::ber::ber_length),
+)]
+struct Uas {
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::u8)]
+    version: u8,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use tinyklv::Klv;
+
+#[derive(Klv)]
+#[klv(form = defined_pack)]
+struct Position {
+    #[klv(default = 0, enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    altitude: u16,
+
+    #[klv(enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: u16,
+}
+
+fn encode_u16(input: &u16) -> Vec<u8> { tinyklv::enc::binary::be_u16(*input) }
+
+fn main() {}
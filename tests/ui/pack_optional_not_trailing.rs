@@ -0,0 +1,16 @@
+use tinyklv::Klv;
+
+#[derive(Klv)]
+#[klv(form = defined_pack)]
+struct Position {
+    #[klv(enc = encode_opt_u16, dec = tinyklv::dec::binary::be_u16)]
+    altitude: Option<u16>,
+
+    #[klv(enc = encode_u16, dec = tinyklv::dec::binary::be_u16)]
+    heading: u16,
+}
+
+fn encode_u16(input: &u16) -> Vec<u8> { tinyklv::enc::binary::be_u16(*input) }
+fn encode_opt_u16(input: &Option<u16>) -> Option<Vec<u8>> { input.map(tinyklv::enc::binary::be_u16) }
+
+fn main() {}
@@ -0,0 +1,13 @@
+use tinyklv::Klv;
+
+#[derive(Klv)]
+#[klv(
+    key(dec = tinyklv::dec::binary::u8),
+    len(dec = tinyklv::dec::binary::u8_as_usize),
+)]
+struct Foo {
+    #[klv(key = 1.5, dec = tinyklv::dec::binary::u8)]
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,106 @@
+use tinyklv::Klv;
+use tinyklv::prelude::*;
+use tinyklv::codecs::ul::{UniversalLabel, UniversalLabelError};
+use tinyklv::router::{KlvRouter, Routed};
+
+const UAS_UL: &str = "06.0E.2B.34.02.0B.01.01.0E.01.03.01.01.00.00.00";
+
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = "urn:smpte:ul:060e2b34.020b0101.0e010301.01000000",
+    key(enc = tinyklv::enc::binary::u8, dec = tinyklv::dec::binary::u8),
+    len(enc = tinyklv::enc::ber::ber_length_from_usize, dec = tinyklv::dec::ber::ber_length),
+)]
+struct Uas {
+    #[klv(key = 0x02, enc = encode_u8, dec = tinyklv::dec::binary::u8)]
+    version: u8,
+}
+
+// ---- hex digits and dots, but not a universal label
+#[derive(Klv, Debug, PartialEq)]
+#[klv(
+    sentinel = "12.34",
+    key(dec = tinyklv::dec::binary::u8),
+    len(dec = tinyklv::dec::binary::u8_as_usize),
+)]
+struct Versioned {
+    #[klv(key = 0x02, dec = tinyklv::dec::binary::u8)]
+    version: u8,
+}
+
+fn encode_u8(input: &u8) -> Vec<u8> { vec![*input] }
+
+/// A packet of [`Uas`], with the version byte of its universal label set
+fn packet(version: u8, value: u8) -> Vec<u8> {
+    let ul: UniversalLabel = UAS_UL.parse().unwrap();
+    let mut data = ul.with_version(version).encode_value();
+    data.extend([0x03, 0x02, 0x01, value]);
+    data
+}
+
+#[test]
+fn parse_and_display() {
+    let ul: UniversalLabel = UAS_UL.parse().unwrap();
+    assert_eq!(ul.to_string(), UAS_UL);
+    assert!(ul.is_smpte());
+    assert_eq!(ul.category_designator(), 0x02);
+    assert_eq!(ul.registry_designator(), 0x0B);
+    assert_eq!(ul.structure_designator(), 0x01);
+    assert_eq!(ul.version(), 0x01);
+    assert_eq!(ul.item_designator(), &[0x0E, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00]);
+    assert_eq!("060e2b34020b01010e01030101000000".parse(), Ok(ul));
+    assert_eq!(format!("{:?}", ul), format!("UniversalLabel({})", UAS_UL));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!("06.0E.2B".parse::<UniversalLabel>(), Err(UniversalLabelError::InvalidLength(6)));
+    assert_eq!("06-0E".parse::<UniversalLabel>(), Err(UniversalLabelError::InvalidChar('-')));
+}
+
+#[test]
+fn matching_ignores_version() {
+    let ul: UniversalLabel = UAS_UL.parse().unwrap();
+    assert!(ul.matches_ignoring_version(&ul.with_version(0x0A)));
+    let mut other = *ul.as_bytes();
+    other[8] = 0xFF;
+    assert!(!ul.matches_ignoring_version(&UniversalLabel::new(other)));
+}
+
+#[test]
+fn sentinel_ignores_version() {
+    assert_eq!(Uas::SENTINEL, Some(&UAS_UL.parse::<UniversalLabel>().unwrap().as_bytes()[..]));
+    const { assert!(Uas::SENTINEL_IGNORES_VERSION) };
+    for version in [0x01, 0x0A, 0xFF] {
+        assert_eq!(Uas::extract(&mut &packet(version, 19)[..]), Ok(Uas { version: 19 }));
+    }
+    // ---- encoded as written
+    assert_eq!(Uas { version: 19 }.encode(), packet(0x01, 19));
+}
+
+#[test]
+fn sentinel_rejects_other_labels() {
+    let mut data = packet(0x01, 19);
+    data[8] = 0x0F;
+    assert!(Uas::extract(&mut &data[..]).is_err());
+}
+
+#[test]
+fn iterators_ignore_version() {
+    let mut data = packet(0x01, 1);
+    data.extend(packet(0x0A, 2));
+    let packets: Vec<_> = PacketIter::<Uas>::new(&data).collect();
+    assert_eq!(packets, vec![Ok(Uas { version: 1 }), Ok(Uas { version: 2 })]);
+
+    let router = KlvRouter::new().route(|uas: Uas| uas.version);
+    let routed: Vec<_> = router.iter(&data).collect();
+    assert_eq!(routed, vec![Ok(Routed::Packet(1)), Ok(Routed::Packet(2))]);
+}
+
+#[test]
+fn plain_string_sentinel() {
+    assert_eq!(Versioned::SENTINEL, Some(&b"12.34"[..]));
+    const { assert!(!Versioned::SENTINEL_IGNORES_VERSION) };
+    let data = [b'1', b'2', b'.', b'3', b'4', 0x03, 0x02, 0x01, 0x13];
+    assert_eq!(Versioned::extract(&mut &data[..]), Ok(Versioned { version: 0x13 }));
+}